@@ -0,0 +1,36 @@
+import {GeneratedCommand} from "@project-gauntlet/api/helpers";
+
+interface PreferenceSearchResult {
+    pluginId: string,
+    pluginName: string,
+    entrypointId: string | undefined,
+    entrypointName: string | undefined,
+    preferenceId: string,
+    preferenceName: string,
+    description: string,
+}
+
+// @ts-expect-error
+const denoCore: DenoCore = Deno[Deno.internal].core;
+const InternalApi: InternalApi = denoCore.ops;
+
+interface InternalApi {
+    search_all_preferences(text: string): Promise<PreferenceSearchResult[]>
+    open_settings_preferences(pluginId: string, entrypointId: string | undefined): void
+}
+
+// every preference of every plugin is listed, so main search can find them
+export default async function Preferences(): Promise<GeneratedCommand[]> {
+    return (await InternalApi.search_all_preferences(""))
+        .map(value => {
+            const location = value.entrypointName ? `${value.pluginName} / ${value.entrypointName}` : value.pluginName;
+
+            return {
+                id: `${value.pluginId}-${value.entrypointId ?? ""}-${value.preferenceId}`,
+                name: `${location}: ${value.preferenceName}`,
+                fn: () => {
+                    InternalApi.open_settings_preferences(value.pluginId, value.entrypointId)
+                }
+            }
+        });
+}
@@ -21,6 +21,12 @@ export function showHud(display: string): void {
     return showHudWindow(display)
 }
 
+// intended for inline view entrypoints, shown instead of rendered inline view.
+// if copyText is specified, answer can be copied to clipboard
+export async function showInlineAnswer(value: string, copyText?: string): Promise<void> {
+    return await InternalApi.show_inline_answer(value, copyText)
+}
+
 export interface GeneratedCommand {
     id: string
     name: string
@@ -77,3 +83,135 @@ export interface Clipboard {
     writeText(data: string): Promise<void>;
     clear(): Promise<void>;
 }
+
+export type PermissionKind = "network" | "environment" | "filesystem-read" | "filesystem-write"
+
+// asks user to grant permission which is not declared in manifest, decision is remembered
+export async function requestPermission(kind: PermissionKind, detail: string): Promise<boolean> {
+    return await InternalApi.op_request_permission(kind, detail)
+}
+
+export type SystemInfoKind = "os" | "arch" | "hostname" | "osRelease"
+
+// requires kind to be listed in "system" permission in manifest
+export function systemInfo(kind: SystemInfoKind): string {
+    return InternalApi.op_system_info(kind)
+}
+
+// requires name to be listed in "environment" permission in manifest
+export function environmentVariable(name: string): string | undefined {
+    return InternalApi.op_get_env(name) ?? undefined
+}
+
+export interface InstalledEntrypoint {
+    id: string
+    name: string
+    pluginId: string
+    pluginName: string
+}
+
+// requires "list_entrypoints" permission in manifest
+export async function listEntrypoints(): Promise<InstalledEntrypoint[]> {
+    return await InternalApi.op_list_entrypoints()
+}
+
+// sub-millisecond precision requires "high_resolution_time" permission in manifest
+export function performanceNow(): number {
+    return InternalApi.op_performance_now()
+}
+
+// requires path to be listed in "exec" permission in manifest, application keeps running after plugin is stopped
+export function launchApplication(path: string, args: string[] = []): void {
+    InternalApi.op_launch_application(path, args)
+}
+
+export interface SubprocessResult {
+    stdout: string
+    stderr: string
+    code: number | null
+}
+
+// requires program to be listed in "exec" permission in manifest, program is not run through a shell
+export async function runSubprocess(program: string, args: string[] = []): Promise<SubprocessResult> {
+    return await InternalApi.op_run_subprocess(program, args)
+}
+
+export interface FetchOptions {
+    method?: string
+    headers?: Record<string, string>
+    body?: string
+    timeoutMs?: number
+}
+
+export interface FetchResult {
+    status: number
+    headers: Record<string, string>
+    body: Uint8Array
+    text(): string
+    json<T = unknown>(): T
+}
+
+// requires host to be listed in "network" permission in manifest, redirects are not followed.
+// unlike global fetch, host can also be matched by wildcard permission, e.g. "*.example.com"
+export async function fetchUrl(url: string, options: FetchOptions = {}): Promise<FetchResult> {
+    const response = await InternalApi.op_fetch({ url, ...options });
+    const body = new Uint8Array(response.body);
+
+    return {
+        status: response.status,
+        headers: response.headers,
+        body,
+        text: () => new TextDecoder().decode(body),
+        json: () => JSON.parse(new TextDecoder().decode(body)),
+    }
+}
+
+export interface FetchStreamResult {
+    status: number
+    headers: Record<string, string>
+    body: AsyncIterable<Uint8Array>
+}
+
+// same as fetchUrl, but body is read in chunks, so large responses don't need to fit in memory
+export async function fetchUrlStream(url: string, options: FetchOptions = {}): Promise<FetchStreamResult> {
+    const response = await InternalApi.op_fetch_stream({ url, ...options });
+
+    async function* body(): AsyncIterable<Uint8Array> {
+        try {
+            while (true) {
+                const chunk = await InternalApi.op_fetch_stream_read(response.streamId);
+                if (chunk === null) {
+                    return
+                }
+
+                yield new Uint8Array(chunk)
+            }
+        } finally {
+            // noop if body was read to the end
+            InternalApi.op_fetch_stream_close(response.streamId)
+        }
+    }
+
+    return {
+        status: response.status,
+        headers: response.headers,
+        body: body(),
+    }
+}
+
+// requires path to be inside one of directories listed in "filesystem.read" permission in manifest
+export async function readFile(path: string): Promise<Uint8Array> {
+    const bytes = await InternalApi.op_fs_read(path);
+    return new Uint8Array(bytes)
+}
+
+// requires path to be inside one of directories listed in "filesystem.write" permission in manifest
+export async function writeFile(path: string, bytes: Uint8Array): Promise<void> {
+    await InternalApi.op_fs_write(path, Array.from(bytes))
+}
+
+// requires path to be listed in "ffi" permission in manifest
+export function openLibrary<S extends Deno.ForeignLibraryInterface>(path: string, symbols: S): Deno.DynamicLibrary<S> {
+    const resolvedPath = InternalApi.op_ffi_resolve_library(path);
+    return Deno.dlopen(resolvedPath, symbols)
+}
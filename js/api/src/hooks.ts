@@ -15,6 +15,23 @@ export function useNavigation(): { popView: () => void, pushView: (component: Re
     }
 }
 
+// same as useState, but value is saved when view is closed and restored next time it is opened
+// value needs to be serializable to json
+export function useViewState<T>(initialState: T): [T, Dispatch<SetStateAction<T>>] {
+    const { restoredViewState, setViewState }: { restoredViewState: () => unknown, setViewState: (state: unknown) => void } = useGauntletContext();
+
+    const [state, setState] = useState<T>(() => {
+        const restored = restoredViewState();
+        return restored !== undefined ? restored as T : initialState
+    });
+
+    useEffect(() => {
+        setViewState(state)
+    }, [state]);
+
+    return [state, setState]
+}
+
 export type AsyncState<T> = {
     isLoading: boolean;
     error?: unknown;
@@ -269,6 +269,7 @@ function packageForLinux(projectRoot: string, arch: string): { filePath: string;
 
     const sourceExecutableFilePath = path.join(releaseDirPath, 'gauntlet');
     const sourceDesktopFilePath = path.join(assetsDirPath, 'gauntlet.desktop');
+    const sourceUriHandlerDesktopFilePath = path.join(assetsDirPath, 'gauntlet-uri-handler.desktop');
     const sourceServiceFilePath = path.join(assetsDirPath, 'gauntlet.service');
     const sourceLogoFilePath = path.join(assetsDirPath, 'icon_256.png');
 
@@ -280,6 +281,9 @@ function packageForLinux(projectRoot: string, arch: string): { filePath: string;
     const targetDesktopFileName = 'gauntlet.desktop';
     const targetDesktopFilePath = path.join(bundleDir, targetDesktopFileName);
 
+    const targetUriHandlerDesktopFileName = 'gauntlet-uri-handler.desktop';
+    const targetUriHandlerDesktopFilePath = path.join(bundleDir, targetUriHandlerDesktopFileName);
+
     const targetServiceFileName = 'gauntlet.service';
     const targetServiceFilePath = path.join(bundleDir, targetServiceFileName);
 
@@ -293,10 +297,11 @@ function packageForLinux(projectRoot: string, arch: string): { filePath: string;
 
     copyFileSync(sourceExecutableFilePath, targetExecutableFilePath)
     copyFileSync(sourceDesktopFilePath, targetDesktopFilePath)
+    copyFileSync(sourceUriHandlerDesktopFilePath, targetUriHandlerDesktopFilePath)
     copyFileSync(sourceServiceFilePath, targetServiceFilePath)
     copyFileSync(sourceLogoFilePath, targetLogoFilePath)
 
-    spawnWithErrors(`tar`, ['-czvf', archiveFileName, targetExecutableFileName, targetDesktopFileName, targetServiceFileName, targetLogoFileName], {
+    spawnWithErrors(`tar`, ['-czvf', archiveFileName, targetExecutableFileName, targetDesktopFileName, targetUriHandlerDesktopFileName, targetServiceFileName, targetLogoFileName], {
         cwd: bundleDir
     })
 
@@ -166,10 +166,11 @@ async function runLoop() {
 
                     const View: FC = (await import(`gauntlet:entrypoint?${pluginEvent.entrypointId}`)).default;
                     const { render } = await import("gauntlet:renderer");
-                    latestRootUiWidget = render(pluginEvent.entrypointId, "View", <View/>);
+                    const viewState = await InternalApi.op_load_view_state(pluginEvent.entrypointId);
+                    latestRootUiWidget = render(pluginEvent.entrypointId, "View", <View/>, viewState ?? undefined);
                 } catch (e) {
                     console.error("Error occurred when rendering view", pluginEvent.entrypointId, e)
-                    InternalApi.show_plugin_error_view(pluginEvent.entrypointId, "View")
+                    InternalApi.show_plugin_error_view(pluginEvent.entrypointId, "View", String(e))
                 }
                 break;
             }
@@ -183,13 +184,30 @@ async function runLoop() {
                         break;
                     }
 
-                    const command: () => Promise<void> | void = (await import(`gauntlet:entrypoint?${pluginEvent.entrypointId}`)).default;
-                    command()
+                    const command: (actionId?: string) => Promise<void> | void = (await import(`gauntlet:entrypoint?${pluginEvent.entrypointId}`)).default;
+                    command(pluginEvent.actionId)
                 } catch (e) {
                     console.error("Error occurred when running a command", pluginEvent.entrypointId, e)
                 }
                 break;
             }
+            case "RunFallbackCommand": {
+                try {
+                    if (await checkRequiredPreferencesAndAsk(pluginEvent.entrypointId)) {
+                        break;
+                    }
+
+                    const fallback: ((text: string) => Promise<void> | void) | undefined = (await import(`gauntlet:entrypoint?${pluginEvent.entrypointId}`)).fallback;
+                    if (typeof fallback !== "function") {
+                        throw new Error("Entrypoint with id '" + pluginEvent.entrypointId + "' doesn't export fallback function")
+                    }
+
+                    fallback(pluginEvent.text)
+                } catch (e) {
+                    console.error("Error occurred when running a fallback command", pluginEvent.entrypointId, e)
+                }
+                break;
+            }
             case "RunGeneratedCommand": {
                 try {
                     runGeneratedCommand(pluginEvent.entrypointId, pluginEvent.actionIndex)
@@ -236,6 +254,27 @@ async function runLoop() {
     }
 }
 
+function forwardConsole(level: "trace" | "debug" | "log" | "info" | "warn" | "error") {
+    const original = console[level];
+
+    console[level] = (...args: any[]) => {
+        original(...args);
+
+        const message = args
+            .map(arg => typeof arg === "string" ? arg : Deno.inspect(arg))
+            .join(" ");
+
+        InternalApi.op_console_log(level, message);
+    }
+}
+
+forwardConsole("trace");
+forwardConsole("debug");
+forwardConsole("log");
+forwardConsole("info");
+forwardConsole("warn");
+forwardConsole("error");
+
 denoCore.setPromiseRejectCallback((_type, _promise, reason) => {
     console.error("Rejected promise", reason)
 })
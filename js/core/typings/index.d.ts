@@ -1,7 +1,7 @@
 declare module "gauntlet:renderer" {
     import { ReactNode } from "react";
 
-    const render: (entrypointId: string, renderLocation: RenderLocation, component: ReactNode) => UiWidget;
+    const render: (entrypointId: string, renderLocation: RenderLocation, component: ReactNode, restoredViewState?: unknown) => UiWidget;
     const clearRenderer: () => void;
     export { render, clearRenderer };
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 import ReactReconciler, { HostConfig, OpaqueHandle } from "react-reconciler";
-import { createContext, FC, ReactNode, useContext } from 'react';
+import { Component, createContext, createElement, ErrorInfo, FC, ReactNode, useContext } from 'react';
 import { DefaultEventPriority } from 'react-reconciler/constants';
 
 // @ts-expect-error does typescript support such symbol declarations?
@@ -13,8 +13,13 @@ const InternalApi = denoCore.ops;
 // will fall back on setTimeout if MessageChannel is not present
 globalThis.MessageChannel = undefined as any;
 
+// widget ids are not reset between renders, so they stay unique for the lifetime of the plugin runtime
+// and events sent by frontend for widgets of a previous view generation don't match any widget of the current one.
+// ids start at epoch of this runtime, so events for widgets rendered before plugin reload are dropped by server
+let nextWidgetId = InternalApi.op_widget_id_epoch_start() + 1;
+
 class HostContext {
-    constructor(public nextId: number, public componentModel: Record<string, Component>) {
+    constructor(public componentModel: Record<string, Component>) {
     }
 
     [Symbol.for("Deno.customInspect")]() {
@@ -42,14 +47,20 @@ class GauntletContextValue {
     private _rerender: ((node: ReactNode) => void) | undefined
     private _entrypointId: string | undefined;
     private _clear: (() => void) | undefined;
+    private _restoredViewState: unknown
+    private _viewState: unknown
+    private _container: RootUiWidget | undefined
 
-    reset(entrypointId: string, renderLocation: RenderLocation, view: ReactNode, rerender: (node: ReactNode) => void, clear: () => void) {
+    reset(entrypointId: string, renderLocation: RenderLocation, view: ReactNode, container: RootUiWidget, rerender: (node: ReactNode) => void, clear: () => void, restoredViewState: unknown) {
         this._entrypointId = entrypointId
+        this._container = container
         this._renderLocation = renderLocation
         this._rerender = rerender
         this._clear = clear
         this._navStack = []
         this._navStack.push(view)
+        this._restoredViewState = restoredViewState
+        this._viewState = undefined
     }
 
     renderLocation = (): RenderLocation => {
@@ -73,9 +84,31 @@ class GauntletContextValue {
     };
 
     clear = () => {
+        // only views opt in to state persistence, view state is saved when view is closed
+        if (this._viewState !== undefined && this._renderLocation === "View") {
+            InternalApi.op_save_view_state(this.entrypointId(), this._viewState)
+                .catch((e: unknown) => console.error("Unable to save view state", e))
+        }
+
         this._clear!!()
+
+        // updates scheduled after view is closed, e.g. state set by promises resolved later,
+        // would otherwise be sent to frontend which doesn't show the view anymore
+        this._container = undefined
     };
 
+    isCurrentContainer = (container: RootUiWidget) => {
+        return this._container === container
+    }
+
+    restoredViewState = (): unknown => {
+        return this._restoredViewState
+    }
+
+    setViewState = (state: unknown) => {
+        this._viewState = state
+    }
+
     pushView = (component: ReactNode) => {
         this._navStack.push(component)
 
@@ -133,13 +166,13 @@ function createWidget(hostContext: HostContext, type: ComponentType, properties:
     );
 
     const instance: Instance = {
-        widgetId: hostContext.nextId,
+        widgetId: nextWidgetId,
         widgetType: type,
         widgetProperties: props,
         widgetChildren: children,
         hostContext
     };
-    hostContext.nextId += 1
+    nextWidgetId += 1
     return instance
 }
 
@@ -224,7 +257,7 @@ export const createHostConfig = (): HostConfig<
     getRootHostContext: (_rootContainer: RootUiWidget): HostContext | null => {
         const componentModel = InternalApi.op_component_model();
 
-        return new HostContext(1, componentModel);
+        return new HostContext(componentModel);
     },
     getChildHostContext: (parentHostContext: HostContext, _type: ComponentType, _rootContainer: RootUiWidget): HostContext => {
         return parentHostContext;
@@ -330,6 +363,11 @@ export const createHostConfig = (): HostConfig<
 
         container.widgetChildren = newChildren
 
+        if (!gauntletContextValue.isCurrentContainer(container)) {
+            InternalApi.op_log_trace("renderer_js_persistence", `replaceContainerChildren skipped, container doesn't belong to current view`)
+            return
+        }
+
         InternalApi.op_react_replace_view(gauntletContextValue.renderLocation(), gauntletContextValue.isBottommostView(), gauntletContextValue.entrypointId(), container)
     },
 
@@ -394,7 +432,35 @@ export function clearRenderer() {
     gauntletContextValue.clear()
 }
 
-export function render(entrypointId: string, renderLocation: RenderLocation, view: ReactNode): UiWidget {
+type ErrorBoundaryProps = {
+    entrypointId: string,
+    renderLocation: RenderLocation,
+    children?: ReactNode,
+}
+
+// errors thrown during render would otherwise unmount the whole tree and leave the view blank
+class ErrorBoundary extends Component<ErrorBoundaryProps, { failed: boolean }> {
+    state = { failed: false }
+
+    static getDerivedStateFromError() {
+        return { failed: true }
+    }
+
+    componentDidCatch(error: unknown, errorInfo: ErrorInfo) {
+        console.error("Error occurred when rendering view", this.props.entrypointId, error, errorInfo.componentStack)
+        InternalApi.show_plugin_error_view(this.props.entrypointId, this.props.renderLocation, String(error))
+    }
+
+    render() {
+        if (this.state.failed) {
+            return null
+        }
+
+        return this.props.children
+    }
+}
+
+export function render(entrypointId: string, renderLocation: RenderLocation, view: ReactNode, restoredViewState?: unknown): UiWidget {
     const hostConfig = createHostConfig();
 
     // const reconciler = ReactReconciler(createTracedHostConfig(hostConfig));
@@ -411,9 +477,10 @@ export function render(entrypointId: string, renderLocation: RenderLocation, vie
         entrypointId,
         renderLocation,
         view,
+        container,
         (node: ReactNode) => {
             reconciler.updateContainer(
-                node,
+                createElement(ErrorBoundary, { entrypointId, renderLocation }, node),
                 root,
                 null,
                 null
@@ -426,7 +493,8 @@ export function render(entrypointId: string, renderLocation: RenderLocation, vie
                 null,
                 null
             );
-        }
+        },
+        restoredViewState
     )
 
     const root = reconciler.createContainer(
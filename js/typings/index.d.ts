@@ -9,7 +9,7 @@ interface DenoCore {
 
 type PromiseRejectCallback = (type: number, promise: Promise<unknown>, reason: any) => void;
 
-type PluginEvent = ViewEvent | NotReactsKeyboardEvent | RunCommand | RunGeneratedCommand | OpenView | CloseView | OpenInlineView | ReloadSearchIndex | RefreshSearchIndex
+type PluginEvent = ViewEvent | NotReactsKeyboardEvent | RunCommand | RunFallbackCommand | RunGeneratedCommand | OpenView | CloseView | OpenInlineView | ReloadSearchIndex | RefreshSearchIndex
 type RenderLocation = "InlineView" | "View"
 
 type ViewEvent = {
@@ -45,6 +45,13 @@ type CloseView = {
 type RunCommand = {
     type: "RunCommand"
     entrypointId: string
+    actionId: string | undefined
+}
+
+type RunFallbackCommand = {
+    type: "RunFallbackCommand"
+    entrypointId: string
+    text: string
 }
 
 type RunGeneratedCommand = {
@@ -95,14 +102,23 @@ type AdditionalSearchItemAction = {
     label: string,
 }
 
+type InstalledEntrypoint = {
+    id: string,
+    name: string,
+    pluginId: string,
+    pluginName: string,
+}
+
 interface InternalApi {
     op_log_trace(target: string, message: string): void;
     op_log_debug(target: string, message: string): void;
     op_log_info(target: string, message: string): void;
     op_log_warn(target: string, message: string): void;
     op_log_error(target: string, message: string): void;
+    op_console_log(level: "trace" | "debug" | "log" | "info" | "warn" | "error", message: string): void;
 
     op_component_model(): Record<string, Component>;
+    op_widget_id_epoch_start(): number;
     asset_data(path: string): Promise<number[]>;
     asset_data_blocking(path: string): number[];
 
@@ -117,11 +133,15 @@ interface InternalApi {
     show_preferences_required_view(entrypointId: string, pluginPreferencesRequired: boolean, entrypointPreferencesRequired: boolean): void;
 
     reload_search_index(searchItems: AdditionalSearchItem[], refreshSearchList: boolean): Promise<void>;
+    op_list_entrypoints(): Promise<InstalledEntrypoint[]>;
 
     show_hud(display: string): void;
+    show_inline_answer(value: string, copyText: string | undefined): Promise<void>;
 
     op_react_replace_view(render_location: RenderLocation, top_level_view: boolean, entrypoint_id: string, container: UiWidget): void;
-    show_plugin_error_view(entrypoint_id: string, render_location: RenderLocation): void;
+    show_plugin_error_view(entrypoint_id: string, render_location: RenderLocation, message?: string): void;
+    op_save_view_state(entrypoint_id: string, view_state: unknown): Promise<void>;
+    op_load_view_state(entrypoint_id: string): Promise<unknown | null>;
 
     fetch_action_id_for_shortcut(entrypointId: string, key: string, modifierShift: boolean, modifierControl: boolean, modifierAlt: boolean, modifierMeta: boolean): Promise<string | undefined>;
 
@@ -130,8 +150,51 @@ interface InternalApi {
     clipboard_write(data: { text_data?: string, png_data?: number[] }): Promise<void>;
     clipboard_write_text(data: string): Promise<void>;
     clipboard_clear(): Promise<void>;
+
+    op_request_permission(kind: PermissionKind, detail: string): Promise<boolean>;
+
+    op_system_info(kind: "os" | "arch" | "hostname" | "osRelease"): string;
+    op_get_env(name: string): string | null;
+    op_performance_now(): number;
+    op_ffi_resolve_library(path: string): string;
+    op_launch_application(path: string, args: string[]): void;
+    op_run_subprocess(program: string, args: string[]): Promise<SubprocessOutput>;
+    op_fetch(request: FetchRequest): Promise<FetchResponse>;
+    op_fetch_stream(request: FetchRequest): Promise<FetchStreamResponse>;
+    op_fetch_stream_read(stream_id: number): Promise<number[] | null>;
+    op_fetch_stream_close(stream_id: number): void;
+    op_fs_read(path: string): Promise<number[]>;
+    op_fs_write(path: string, bytes: number[]): Promise<void>;
 }
 
+type SubprocessOutput = {
+    stdout: string,
+    stderr: string,
+    code: number | null,
+}
+
+type FetchRequest = {
+    url: string,
+    method?: string,
+    headers?: Record<string, string>,
+    body?: string,
+    timeoutMs?: number,
+}
+
+type FetchResponse = {
+    status: number,
+    headers: Record<string, string>,
+    body: number[],
+}
+
+type FetchStreamResponse = {
+    streamId: number,
+    status: number,
+    headers: Record<string, string>,
+}
+
+type PermissionKind = "network" | "environment" | "filesystem-read" | "filesystem-write"
+
 // component model types
 
 type Component = StandardComponent | RootComponent | TextPartComponent
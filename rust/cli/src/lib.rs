@@ -1,9 +1,9 @@
 use anyhow::{anyhow, Context};
 use clap::Parser;
 
-use client::{generate_color_theme_sample, generate_theme_sample, open_window};
+use client::{generate_color_theme_sample, generate_theme_sample, open_uri, open_window, print_search_results};
 use management_client::start_management_client;
-use server::start;
+use server::{lint_plugin, scaffold_plugin, start};
 
 #[derive(Debug, clap::Parser)]
 struct Cli {
@@ -18,10 +18,34 @@ struct Cli {
 enum Commands {
     Open,
     Settings,
+    /// Open gauntlet:// uri, e.g. gauntlet://run/<plugin_id>/<entrypoint_id>
+    OpenUri {
+        uri: String,
+    },
+    /// Search entrypoints in running server and print results as json
+    Search {
+        query: String,
+    },
+    Plugin {
+        #[command(subcommand)]
+        command: PluginCommands,
+    },
     GenerateSampleTheme,
     GenerateSampleColorTheme,
 }
 
+#[derive(Debug, clap::Subcommand)]
+enum PluginCommands {
+    /// Generate new plugin in current directory
+    New {
+        name: String,
+    },
+    /// Check plugin manifest for errors and style issues
+    Lint {
+        path: std::path::PathBuf,
+    },
+}
+
 pub fn init() {
     tracing_subscriber::fmt::init();
 
@@ -48,6 +72,19 @@ pub fn init() {
             match command {
                 Commands::Open => open_window(),
                 Commands::Settings => start_management_client(),
+                Commands::OpenUri { uri } => open_uri(uri),
+                Commands::Search { query } => print_search_results(query),
+                Commands::Plugin { command } => {
+                    match command {
+                        PluginCommands::New { name } => scaffold_plugin(name).expect("Unable to generate plugin"),
+                        PluginCommands::Lint { path } => {
+                            if let Err(err) = lint_plugin(path) {
+                                eprintln!("{}", err);
+                                std::process::exit(1)
+                            }
+                        }
+                    }
+                }
                 Commands::GenerateSampleTheme => generate_theme_sample().expect("Unable to generate sample theme"),
                 Commands::GenerateSampleColorTheme => generate_color_theme_sample().expect("Unable to generate sample color theme")
             };
@@ -1,5 +1,6 @@
+use common::deeplink::{DeepLink, parse_deeplink};
 use common::dirs::Dirs;
-use common::model::{BackendRequestData, BackendResponseData, UiRequestData, UiResponseData};
+use common::model::{BackendRequestData, BackendResponseData, SearchResult, SearchResultEntrypointType, SearchResultKind, UiRequestData, UiResponseData};
 use common::rpc::backend_api::BackendApi;
 use utils::channel::{RequestReceiver, RequestSender};
 use crate::ui::GauntletTheme;
@@ -59,6 +60,109 @@ pub fn open_settings_window() {
         })
 }
 
+pub fn open_uri(uri: &str) {
+    let deeplink = match parse_deeplink(uri) {
+        Ok(deeplink) => deeplink,
+        Err(err) => exit_with_error(format!("Unable to parse uri: {:#}", err)),
+    };
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("unable to start server tokio runtime")
+        .block_on(async {
+            let result = BackendApi::new().await;
+
+            match result {
+                Ok(mut backend_api) => {
+                    let result = match deeplink {
+                        DeepLink::RunEntrypoint { plugin_id, entrypoint_id } => {
+                            backend_api.run_entrypoint(plugin_id, entrypoint_id).await
+                        }
+                    };
+
+                    if let Err(err) = result {
+                        exit_with_error(format!("Unable to open uri: {}", err))
+                    }
+                }
+                Err(_) => {
+                    exit_with_error("Unable to connect to server. Please check if you have Gauntlet running on your PC")
+                }
+            }
+        })
+}
+
+pub fn print_search_results(text: &str) {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("unable to start server tokio runtime")
+        .block_on(async {
+            let result = BackendApi::new().await;
+
+            match result {
+                Ok(mut backend_api) => {
+                    match backend_api.search(text.to_string()).await {
+                        Ok(results) => {
+                            let json = search_results_to_json(results);
+
+                            println!("{}", serde_json::to_string_pretty(&json).expect("unable to serialize search results"))
+                        }
+                        Err(err) => {
+                            exit_with_error(format!("Unable to search: {}", err))
+                        }
+                    }
+                }
+                Err(_) => {
+                    exit_with_error("Unable to connect to server. Please check if you have Gauntlet running on your PC")
+                }
+            }
+        })
+}
+
+// these commands are used from scripts, so failure has to be visible in exit code and not only in logs
+fn exit_with_error(message: impl std::fmt::Display) -> ! {
+    eprintln!("{}", message);
+
+    std::process::exit(1)
+}
+
+fn search_results_to_json(results: Vec<SearchResult>) -> serde_json::Value {
+    let results = results.into_iter()
+        .map(|result| {
+            let entrypoint_type = match result.entrypoint_type {
+                SearchResultEntrypointType::Command => "command",
+                SearchResultEntrypointType::View => "view",
+                SearchResultEntrypointType::GeneratedCommand => "generated-command",
+            };
+
+            let result_kind = match result.result_kind {
+                SearchResultKind::Command => "command",
+                SearchResultKind::View => "view",
+            };
+
+            let entrypoint_actions: Vec<_> = result.entrypoint_actions
+                .into_iter()
+                .map(|action| serde_json::json!({ "label": action.label }))
+                .collect();
+
+            serde_json::json!({
+                "plugin_id": result.plugin_id.to_string(),
+                "plugin_name": result.plugin_name,
+                "entrypoint_id": result.entrypoint_id.to_string(),
+                "entrypoint_name": result.entrypoint_name,
+                "entrypoint_icon": result.entrypoint_icon,
+                "entrypoint_type": entrypoint_type,
+                "entrypoint_actions": entrypoint_actions,
+                "subtitle": result.subtitle,
+                "result_kind": result_kind,
+            })
+        })
+        .collect();
+
+    serde_json::Value::Array(results)
+}
+
 pub fn generate_theme_sample() -> anyhow::Result<()> {
     let dirs = Dirs::new();
 
@@ -105,4 +209,70 @@ pub fn generate_color_theme_sample() -> anyhow::Result<()> {
     println!("Make changes and rename file to {:?}", theme_color_file.file_name().unwrap());
 
     Ok(())
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use common::model::{EntrypointId, PluginId, SearchResultEntrypointAction};
+
+    use super::*;
+
+    #[test]
+    fn search_results_are_printed_as_json_array() {
+        let results = vec![
+            SearchResult {
+                plugin_id: PluginId::from_string("file:///plugin"),
+                plugin_name: "Plugin".to_string(),
+                entrypoint_id: EntrypointId::from_string("view"),
+                entrypoint_name: "View".to_string(),
+                entrypoint_icon: Some("/icons/view.png".to_string()),
+                entrypoint_type: SearchResultEntrypointType::View,
+                entrypoint_actions: vec![
+                    SearchResultEntrypointAction {
+                        label: "Open".to_string(),
+                        shortcut: None,
+                    }
+                ],
+                subtitle: Some("Plugin".to_string()),
+                result_kind: SearchResultKind::View,
+            },
+            SearchResult {
+                plugin_id: PluginId::from_string("file:///plugin"),
+                plugin_name: "Plugin".to_string(),
+                entrypoint_id: EntrypointId::from_string("command"),
+                entrypoint_name: "Command".to_string(),
+                entrypoint_icon: None,
+                entrypoint_type: SearchResultEntrypointType::Command,
+                entrypoint_actions: vec![],
+                subtitle: None,
+                result_kind: SearchResultKind::Command,
+            },
+        ];
+
+        let json = search_results_to_json(results);
+
+        assert_eq!(json, serde_json::json!([
+            {
+                "plugin_id": "file:///plugin",
+                "plugin_name": "Plugin",
+                "entrypoint_id": "view",
+                "entrypoint_name": "View",
+                "entrypoint_icon": "/icons/view.png",
+                "entrypoint_type": "view",
+                "entrypoint_actions": [{ "label": "Open" }],
+                "subtitle": "Plugin",
+                "result_kind": "view",
+            },
+            {
+                "plugin_id": "file:///plugin",
+                "plugin_name": "Plugin",
+                "entrypoint_id": "command",
+                "entrypoint_name": "Command",
+                "entrypoint_icon": null,
+                "entrypoint_type": "command",
+                "entrypoint_actions": [],
+                "subtitle": null,
+                "result_kind": "command",
+            }
+        ]));
+    }
+}
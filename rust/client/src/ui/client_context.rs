@@ -1,5 +1,6 @@
-use std::collections::HashMap;
-use common::model::{EntrypointId, PhysicalShortcut, PluginId, UiRenderLocation, UiWidget, UiWidgetId};
+use std::collections::{HashMap, VecDeque};
+use common::model::{EntrypointId, PhysicalShortcut, PluginId, UiRenderLocation, UiResponseData, UiWidget, UiWidgetId};
+use utils::channel::Responder;
 use crate::model::UiViewEvent;
 
 use crate::ui::widget::{ActionPanel, ComponentWidgetEvent};
@@ -9,6 +10,21 @@ pub struct ClientContext {
     inline_views: Vec<(PluginId, PluginWidgetContainer)>, // Vec to have stable ordering
     inline_view_shortcuts: HashMap<PluginId, HashMap<String, PhysicalShortcut>>,
     view: PluginWidgetContainer,
+    permission_requests: VecDeque<PermissionRequest>,
+    inline_answer: Option<InlineAnswer>,
+}
+
+pub struct InlineAnswer {
+    pub plugin_id: PluginId,
+    pub value: String,
+    pub copy_text: Option<String>,
+}
+
+pub struct PermissionRequest {
+    pub plugin_name: String,
+    pub kind: String,
+    pub detail: String,
+    responder: Responder<UiResponseData>,
 }
 
 impl ClientContext {
@@ -17,9 +33,23 @@ impl ClientContext {
             inline_views: vec![],
             inline_view_shortcuts: HashMap::new(),
             view: PluginWidgetContainer::new(),
+            permission_requests: VecDeque::new(),
+            inline_answer: None,
         }
     }
 
+    pub fn get_inline_answer(&self) -> Option<&InlineAnswer> {
+        self.inline_answer.as_ref()
+    }
+
+    pub fn set_inline_answer(&mut self, plugin_id: PluginId, value: String, copy_text: Option<String>) {
+        self.inline_answer = Some(InlineAnswer {
+            plugin_id,
+            value,
+            copy_text,
+        })
+    }
+
     pub fn get_all_inline_view_containers(&self) -> &Vec<(PluginId, PluginWidgetContainer)> {
         &self.inline_views
     }
@@ -86,13 +116,18 @@ impl ClientContext {
     }
 
      pub fn clear_all_inline_views(&mut self) {
-        self.inline_views.clear()
+        self.inline_views.clear();
+        self.inline_answer = None;
     }
 
     pub fn clear_inline_view(&mut self, plugin_id: &PluginId) {
         if let Some(index) = self.inline_views.iter().position(|(id, _)| id == plugin_id) {
             self.inline_views.remove(index);
         }
+
+        if self.inline_answer.as_ref().is_some_and(|answer| &answer.plugin_id == plugin_id) {
+            self.inline_answer = None;
+        }
     }
 
     pub fn handle_event(&self, render_location: UiRenderLocation, plugin_id: &PluginId, event: ComponentWidgetEvent) -> Option<UiViewEvent> {
@@ -109,4 +144,65 @@ impl ClientContext {
     pub fn get_action_ids(&self) -> Vec<UiWidgetId> {
         self.view.get_action_ids()
     }
+
+    pub fn set_permission_request(&mut self, plugin_name: String, kind: String, detail: String, responder: Responder<UiResponseData>) {
+        // only one request is shown at a time, others wait for their turn instead of being denied,
+        // because denial would be saved as user decision
+        self.permission_requests.push_back(PermissionRequest {
+            plugin_name,
+            kind,
+            detail,
+            responder,
+        })
+    }
+
+    pub fn get_permission_request(&self) -> Option<&PermissionRequest> {
+        self.permission_requests.front()
+    }
+
+    // returns false if server stopped waiting for decision after timeout
+    pub fn respond_permission_request(&mut self, granted: bool) -> bool {
+        match self.permission_requests.pop_front() {
+            Some(permission_request) if !permission_request.responder.is_closed() => {
+                permission_request.responder.respond(UiResponseData::PermissionDecision { granted });
+
+                true
+            }
+            _ => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::model::UiRequestData;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn overlapping_permission_requests_are_answered_in_order() {
+        let (sender, mut receiver) = utils::channel::channel::<UiRequestData, UiResponseData>();
+
+        let mut first_response = sender.send(UiRequestData::ShowWindow);
+        let mut second_response = sender.send(UiRequestData::ShowWindow);
+
+        let (_, first_responder) = receiver.recv().await;
+        let (_, second_responder) = receiver.recv().await;
+
+        let mut client_context = ClientContext::new();
+
+        client_context.set_permission_request("First".to_string(), "network".to_string(), "example.com".to_string(), first_responder);
+        client_context.set_permission_request("Second".to_string(), "environment".to_string(), "HOME".to_string(), second_responder);
+
+        assert_eq!(client_context.get_permission_request().unwrap().plugin_name, "First");
+        assert!(client_context.respond_permission_request(true));
+        assert!(matches!(first_response.recv().await, UiResponseData::PermissionDecision { granted: true }));
+
+        // second request is not denied when first one is shown
+        assert_eq!(client_context.get_permission_request().unwrap().plugin_name, "Second");
+        assert!(client_context.respond_permission_request(false));
+        assert!(matches!(second_response.recv().await, UiResponseData::PermissionDecision { granted: false }));
+
+        assert!(client_context.get_permission_request().is_none());
+    }
 }
@@ -3,6 +3,7 @@ use std::fs;
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::{Arc, RwLock as StdRwLock};
+use std::time::Duration;
 use anyhow::anyhow;
 use iced::{event, executor, font, futures, keyboard, subscription, window, Alignment, Command, Event, Font, Length, Padding, Pixels, Settings, Size, Subscription};
 use iced::advanced::graphics::core::SmolStr;
@@ -77,6 +78,8 @@ pub struct AppModel {
     client_context: Arc<StdRwLock<ClientContext>>,
     global_state: GlobalState,
     search_results: Vec<SearchResult>,
+    search_suggestion: Option<String>,
+    search_error: Option<String>,
     hud_display: Option<String>
 }
 
@@ -89,10 +92,15 @@ pub enum AppMsg {
         entrypoint_id: EntrypointId,
         entrypoint_name: String,
     },
+    ShowViewLoading {
+        plugin_id: PluginId,
+        entrypoint_id: EntrypointId,
+    },
     RunCommand {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
     },
+    RunFallback,
     RunGeneratedCommandEvent {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
@@ -101,7 +109,13 @@ pub enum AppMsg {
     PromptChanged(String),
     PromptSubmit,
     UpdateSearchResults,
-    SetSearchResults(Vec<SearchResult>),
+    SetSearchResults {
+        results: Vec<SearchResult>,
+        suggestion: Option<String>,
+    },
+    ShowSearchError {
+        message: String,
+    },
     ReplaceView {
         top_level_view: bool,
         has_children: bool,
@@ -140,7 +154,14 @@ pub enum AppMsg {
     ShowPluginErrorView {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
-        render_location: UiRenderLocation
+        render_location: UiRenderLocation,
+        message: Option<String>,
+    },
+    ReloadPlugin {
+        plugin_id: PluginId,
+    },
+    ForceStopPlugin {
+        plugin_id: PluginId,
     },
     RunSearchItemAction(SearchResult, Option<usize>),
     Screenshot {
@@ -161,9 +182,20 @@ pub enum AppMsg {
     ShowHud {
         display: String
     },
+    CopyInlineAnswer {
+        text: String
+    },
     CloseHudWindow {
         id: window::Id
     },
+    ShowPermissionRequest,
+    PermissionDecision {
+        granted: bool
+    },
+    KeyboardEventNotCaptured {
+        text: Option<SmolStr>,
+        modifiers: Modifiers,
+    },
 }
 
 pub struct AppFlags {
@@ -180,6 +212,7 @@ impl Default for AppFlags {
 
 const WINDOW_WIDTH: f32 = 750.0;
 const WINDOW_HEIGHT: f32 = 450.0;
+const VIEW_LOADING_PLACEHOLDER_DELAY: Duration = Duration::from_millis(300);
 
 #[cfg(target_os = "linux")]
 fn layer_shell_settings() -> iced::wayland::runtime::command::platform_specific::wayland::layer_surface::SctkLayerSurfaceSettings {
@@ -364,6 +397,7 @@ impl Application for AppModel {
                     let error_view = ErrorViewData::PluginError {
                         plugin_id: PluginId::from_string("__SCREENSHOT_GEN___"),
                         entrypoint_id: EntrypointId::from_string(entrypoint_id),
+                        message: None,
                     };
 
                     (Arc::new(StdRwLock::new(ClientContext::new())), GlobalState::new_error(error_view))
@@ -392,6 +426,8 @@ impl Application for AppModel {
                 global_state,
                 client_context,
                 search_results: vec![],
+                search_suggestion: None,
+                search_error: None,
                 hud_display: None,
             },
             Command::batch(commands),
@@ -410,7 +446,7 @@ impl Application for AppModel {
         match message {
             AppMsg::OpenView { plugin_id, plugin_name, entrypoint_id, entrypoint_name } => {
                 match &mut self.global_state {
-                    GlobalState::MainView { pending_plugin_view_data, .. } => {
+                    GlobalState::MainView { pending_plugin_view_data, pending_plugin_view_loading, .. } => {
                         *pending_plugin_view_data = Some(PluginViewData {
                             top_level_view: true,
                             plugin_id: plugin_id.clone(),
@@ -419,8 +455,23 @@ impl Application for AppModel {
                             entrypoint_name,
                             action_shortcuts: HashMap::new(),
                         });
+                        *pending_plugin_view_loading = false;
+
+                        // render is complete when first ReplaceView arrives, placeholder is shown only if it takes a while
+                        let show_loading = {
+                            let plugin_id = plugin_id.clone();
+                            let entrypoint_id = entrypoint_id.clone();
+
+                            Command::perform(
+                                async { tokio::time::sleep(VIEW_LOADING_PLACEHOLDER_DELAY).await },
+                                move |_| AppMsg::ShowViewLoading { plugin_id, entrypoint_id }
+                            )
+                        };
 
-                        self.open_plugin_view(plugin_id, entrypoint_id)
+                        Command::batch([
+                            self.open_plugin_view(plugin_id, entrypoint_id),
+                            show_loading,
+                        ])
                     }
                     GlobalState::ErrorView { .. } => {
                         Command::none()
@@ -433,7 +484,17 @@ impl Application for AppModel {
             AppMsg::RunCommand { plugin_id, entrypoint_id } => {
                 Command::batch([
                     self.hide_window(),
-                    self.run_command(plugin_id, entrypoint_id),
+                    self.run_command(plugin_id, entrypoint_id, false, false, false, false),
+                ])
+            }
+            AppMsg::RunFallback => {
+                if self.prompt.is_empty() {
+                    return Command::none()
+                }
+
+                Command::batch([
+                    self.hide_window(),
+                    self.run_fallback(self.prompt.clone()),
                 ])
             }
             AppMsg::RunGeneratedCommandEvent { plugin_id, entrypoint_id, action_index } => {
@@ -474,8 +535,22 @@ impl Application for AppModel {
             AppMsg::PromptSubmit => {
                 self.global_state.primary(&self.search_results)
             },
-            AppMsg::SetSearchResults(new_search_results) => {
-                self.search_results = new_search_results;
+            AppMsg::ShowViewLoading { plugin_id, entrypoint_id } => {
+                self.global_state.show_pending_plugin_view_loading(&plugin_id, &entrypoint_id);
+
+                Command::none()
+            }
+            AppMsg::SetSearchResults { results, suggestion } => {
+                self.search_results = results;
+                self.search_suggestion = suggestion;
+                self.search_error = None;
+
+                Command::none()
+            }
+            AppMsg::ShowSearchError { message } => {
+                self.search_results = vec![];
+                self.search_suggestion = None;
+                self.search_error = Some(message);
 
                 Command::none()
             }
@@ -531,7 +606,29 @@ impl Application for AppModel {
                             Key::Named(Named::Escape) => self.global_state.back(),
                             Key::Named(Named::Enter) => {
                                 if modifiers.logo() || modifiers.alt() || modifiers.control() {
-                                    Command::none() // to avoid not wanted "enter" presses
+                                    // commands select their main or alternative action by modifiers,
+                                    // everything else ignores it to avoid not wanted "enter" presses
+                                    let command = match &self.global_state {
+                                        GlobalState::MainView { sub_state: MainViewState::None, focused_search_result, .. } => {
+                                            match focused_search_result.get(&self.search_results) {
+                                                Some(SearchResult { plugin_id, entrypoint_id, entrypoint_type: SearchResultEntrypointType::Command, .. }) => {
+                                                    Some((plugin_id.clone(), entrypoint_id.clone()))
+                                                }
+                                                _ => None
+                                            }
+                                        }
+                                        _ => None
+                                    };
+
+                                    match command {
+                                        Some((plugin_id, entrypoint_id)) => {
+                                            Command::batch([
+                                                self.hide_window(),
+                                                self.run_command(plugin_id, entrypoint_id, modifiers.shift(), modifiers.control(), modifiers.alt(), modifiers.logo()),
+                                            ])
+                                        }
+                                        None => Command::none()
+                                    }
                                 } else {
                                     if modifiers.shift() {
                                         // for main view, also fired in cases where main text field is not focused
@@ -574,7 +671,9 @@ impl Application for AppModel {
                                                                         modifier_shift,
                                                                         modifier_control,
                                                                         modifier_alt,
-                                                                        modifier_meta
+                                                                        modifier_meta,
+                                                                        text,
+                                                                        modifiers
                                                                     )
                                                                 } else {
                                                                     Command::none()
@@ -585,7 +684,9 @@ impl Application for AppModel {
                                                                     modifier_shift,
                                                                     modifier_control,
                                                                     modifier_alt,
-                                                                    modifier_meta
+                                                                    modifier_meta,
+                                                                    text,
+                                                                    modifiers
                                                                 )
                                                             }
                                                         } else {
@@ -611,7 +712,9 @@ impl Application for AppModel {
                                                                         modifier_shift,
                                                                         modifier_control,
                                                                         modifier_alt,
-                                                                        modifier_meta
+                                                                        modifier_meta,
+                                                                        text,
+                                                                        modifiers
                                                                     )
                                                                 } else {
                                                                     Command::none()
@@ -638,7 +741,9 @@ impl Application for AppModel {
                                                                 modifier_shift,
                                                                 modifier_control,
                                                                 modifier_alt,
-                                                                modifier_meta
+                                                                modifier_meta,
+                                                                text,
+                                                                modifiers
                                                             )
                                                         } else {
                                                             Command::none()
@@ -657,7 +762,7 @@ impl Application for AppModel {
                                             }
                                             Some(PhysicalShortcut { physical_key, modifier_shift, modifier_control, modifier_alt, modifier_meta }) => {
                                                 if modifier_shift || modifier_control || modifier_alt || modifier_meta {
-                                                    self.handle_plugin_view_keyboard_event(physical_key, modifier_shift, modifier_control, modifier_alt, modifier_meta)
+                                                    self.handle_plugin_view_keyboard_event(physical_key, modifier_shift, modifier_control, modifier_alt, modifier_meta, text, modifiers)
                                                 } else {
                                                     Command::none()
                                                 }
@@ -722,20 +827,61 @@ impl Application for AppModel {
                     },
                 )
             }
-            AppMsg::ShowPluginErrorView { plugin_id, entrypoint_id, .. } => {
+            AppMsg::ShowPluginErrorView { plugin_id, entrypoint_id, message, .. } => {
                 GlobalState::error(
                     &mut self.global_state,
                     ErrorViewData::PluginError {
                         plugin_id,
                         entrypoint_id,
+                        message,
                     },
                 )
             }
+            AppMsg::ForceStopPlugin { plugin_id } => {
+                if let GlobalState::MainView { pending_plugin_view_data, pending_plugin_view_loading, .. } = &mut self.global_state {
+                    *pending_plugin_view_data = None;
+                    *pending_plugin_view_loading = false;
+                }
+
+                let mut backend_client = self.backend_api.clone();
+
+                Command::perform(async move {
+                    backend_client.request_plugin_force_stop(plugin_id)
+                        .await?;
+
+                    Ok(())
+                }, |result| handle_backend_error(result, |()| AppMsg::Noop))
+            }
+            AppMsg::ReloadPlugin { plugin_id } => {
+                let mut backend_client = self.backend_api.clone();
+
+                Command::batch([
+                    self.hide_window(),
+                    Command::perform(async move {
+                        backend_client.request_plugin_reload(plugin_id)
+                            .await?;
+
+                        Ok(())
+                    }, |result| handle_backend_error(result, |()| AppMsg::Noop))
+                ])
+            }
+            AppMsg::KeyboardEventNotCaptured { text, modifiers } => {
+                // key is not used by plugin, so it is handled the same way as without plugin
+                match &mut self.global_state {
+                    GlobalState::MainView { sub_state: MainViewState::None, search_field_id, .. } => {
+                        Self::append_prompt(&mut self.prompt, text, search_field_id.clone(), modifiers)
+                    }
+                    _ => Command::none()
+                }
+            }
             AppMsg::ShowBackendError(err) => {
                 GlobalState::error(
                     &mut self.global_state,
                     match err {
                         BackendForFrontendApiError::TimeoutError => ErrorViewData::BackendTimeout,
+                        BackendForFrontendApiError::BackendError(_) => ErrorViewData::UnknownError {
+                            display: format!("{}", err)
+                        },
                     }
                 )
             }
@@ -774,19 +920,15 @@ impl Application for AppModel {
                             Some(_) => Command::none()
                         }
                     },
+                    // view actions can only be run from inside the view, so any of them opens it
                     SearchResultEntrypointType::View => {
-                        match action_index {
-                            None => {
-                                let msg = AppMsg::OpenView {
-                                    plugin_id: search_result.plugin_id.clone(),
-                                    plugin_name: search_result.plugin_name.clone(),
-                                    entrypoint_id: search_result.entrypoint_id.clone(),
-                                    entrypoint_name: search_result.entrypoint_name.clone(),
-                                };
-                                Command::perform(async {}, |_| msg)
-                            }
-                            Some(_) => Command::none()
-                        }
+                        let msg = AppMsg::OpenView {
+                            plugin_id: search_result.plugin_id.clone(),
+                            plugin_name: search_result.plugin_name.clone(),
+                            entrypoint_id: search_result.entrypoint_id.clone(),
+                            entrypoint_name: search_result.entrypoint_name.clone(),
+                        };
+                        Command::perform(async {}, |_| msg)
                     },
                     SearchResultEntrypointType::GeneratedCommand => {
                         let msg = AppMsg::RunGeneratedCommandEvent {
@@ -1011,6 +1153,12 @@ impl Application for AppModel {
                     self.wayland,
                 )
             }
+            AppMsg::CopyInlineAnswer { text } => {
+                Command::batch([
+                    iced::clipboard::write(text),
+                    Command::perform(async {}, |_| AppMsg::ShowHud { display: "Answer copied".to_string() }),
+                ])
+            }
             AppMsg::CloseHudWindow { id } => {
                 self.hud_display = None;
 
@@ -1020,6 +1168,20 @@ impl Application for AppModel {
                     id
                 )
             }
+            AppMsg::ShowPermissionRequest => self.show_window(),
+            AppMsg::PermissionDecision { granted } => {
+                let responded = {
+                    let mut client_context = self.client_context.write().expect("lock is poisoned");
+
+                    client_context.respond_permission_request(granted)
+                };
+
+                if responded {
+                    Command::none()
+                } else {
+                    Command::perform(async {}, |_| AppMsg::ShowHud { display: "Permission request has expired".to_string() })
+                }
+            }
         }
     }
 
@@ -1059,6 +1221,60 @@ impl Application for AppModel {
             }
         }
 
+        let permission_request = {
+            let client_context = self.client_context.read().expect("lock is poisoned");
+
+            client_context.get_permission_request()
+                .map(|request| (request.plugin_name.clone(), request.kind.clone(), request.detail.clone()))
+        };
+
+        if let Some((plugin_name, kind, detail)) = permission_request {
+            let description: Element<_> = text(format!("Plugin \"{}\" requests \"{}\" permission for \"{}\"", plugin_name, kind, detail))
+                .into();
+
+            let description = container(description)
+                .width(Length::Fill)
+                .center_x()
+                .themed(ContainerStyle::PreferenceRequiredViewDescription);
+
+            let deny_button_label: Element<_> = text("Deny")
+                .into();
+
+            let deny_button: Element<_> = button(deny_button_label)
+                .on_press(AppMsg::PermissionDecision { granted: false })
+                .into();
+
+            let allow_button_label: Element<_> = text("Allow")
+                .into();
+
+            let allow_button: Element<_> = button(allow_button_label)
+                .on_press(AppMsg::PermissionDecision { granted: true })
+                .into();
+
+            let buttons: Element<_> = row([deny_button, allow_button])
+                .spacing(8.0)
+                .into();
+
+            let buttons = container(buttons)
+                .width(Length::Fill)
+                .center_x()
+                .into();
+
+            let content: Element<_> = column([
+                description,
+                buttons
+            ]).into();
+
+            let content: Element<_> = container(content)
+                .center_x()
+                .center_y()
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .themed(ContainerStyle::Main);
+
+            return content
+        }
+
 
         match &self.global_state {
             GlobalState::ErrorView { error_view } => {
@@ -1122,7 +1338,7 @@ impl Application for AppModel {
 
                         content
                     }
-                    ErrorViewData::PluginError { .. } => {
+                    ErrorViewData::PluginError { plugin_id, message, .. } => {
                         let description: Element<_> = text("Error occurred in plugin when trying to show the view")
                             .into();
 
@@ -1131,22 +1347,35 @@ impl Application for AppModel {
                             .center_x()
                             .themed(ContainerStyle::PluginErrorViewTitle);
 
-                        let sub_description: Element<_> = text("Please report this to plugin author")
-                            .into();
+                        let sub_description: Element<_> = match message {
+                            Some(message) => text(format!("{}\n\nPlease report this to plugin author", message)).into(),
+                            None => text("Please report this to plugin author").into(),
+                        };
 
                         let sub_description = container(sub_description)
                             .width(Length::Fill)
                             .center_x()
                             .themed(ContainerStyle::PluginErrorViewDescription);
 
-                        let button_label: Element<_> = text("Close")
+                        let close_button_label: Element<_> = text("Close")
                             .into();
 
-                        let button: Element<_> = button(button_label)
+                        let close_button: Element<_> = button(close_button_label)
                             .on_press(AppMsg::HideWindow)
                             .into();
 
-                        let button = container(button)
+                        let reload_button_label: Element<_> = text("Reload Plugin")
+                            .into();
+
+                        let reload_button: Element<_> = button(reload_button_label)
+                            .on_press(AppMsg::ReloadPlugin { plugin_id: plugin_id.clone() })
+                            .into();
+
+                        let buttons: Element<_> = row([reload_button, close_button])
+                            .spacing(8.0)
+                            .into();
+
+                        let buttons = container(buttons)
                             .width(Length::Fill)
                             .center_x()
                             .into();
@@ -1154,7 +1383,7 @@ impl Application for AppModel {
                         let content: Element<_> = column([
                             description,
                             sub_description,
-                            button
+                            buttons
                         ]).into();
 
                         let content: Element<_> = container(content)
@@ -1268,7 +1497,7 @@ impl Application for AppModel {
                     }
                 }
             }
-            GlobalState::MainView { focused_search_result, sub_state, search_field_id, .. } => {
+            GlobalState::MainView { focused_search_result, sub_state, search_field_id, pending_plugin_view_data, pending_plugin_view_loading, .. } => {
                 let input: Element<_> = text_input("Search...", &self.prompt)
                     .on_input(AppMsg::PromptChanged)
                     .on_submit(AppMsg::PromptSubmit)
@@ -1277,11 +1506,54 @@ impl Application for AppModel {
                     .width(Length::Fill)
                     .themed(TextInputStyle::MainSearch);
 
-                let search_list = search_list(
-                    &self.search_results,
-                    &focused_search_result,
-                    |search_result| AppMsg::RunSearchItemAction(search_result, None),
-                );
+                let search_list: Element<_> = match (&self.search_error, &self.search_suggestion, pending_plugin_view_data) {
+                    (_, _, Some(pending_plugin_view_data)) if *pending_plugin_view_loading => {
+                        let loading: Element<_> = text(format!("Loading \"{}\"...", pending_plugin_view_data.entrypoint_name))
+                            .into();
+
+                        // plugin which hangs never renders the view, so it can be stopped from here
+                        let force_stop_label: Element<_> = text("Force Stop")
+                            .into();
+
+                        let force_stop_button: Element<_> = button(force_stop_label)
+                            .on_press(AppMsg::ForceStopPlugin { plugin_id: pending_plugin_view_data.plugin_id.clone() })
+                            .into();
+
+                        let loading: Element<_> = column([loading, force_stop_button])
+                            .spacing(8.0)
+                            .align_items(Alignment::Center)
+                            .into();
+
+                        container(loading)
+                            .width(Length::Fill)
+                            .center_x()
+                            .into()
+                    }
+                    (Some(search_error), _, _) => {
+                        let error_label: Element<_> = text(search_error)
+                            .into();
+
+                        container(error_label)
+                            .width(Length::Fill)
+                            .center_x()
+                            .into()
+                    }
+                    (_, Some(suggestion), _) if self.search_results.is_empty() => {
+                        let suggestion_label: Element<_> = text(format!("Did you mean \"{}\"?", suggestion))
+                            .into();
+
+                        button(suggestion_label)
+                            .on_press(AppMsg::PromptChanged(suggestion.clone()))
+                            .into()
+                    }
+                    _ => {
+                        search_list(
+                            &self.search_results,
+                            &focused_search_result,
+                            |search_result| AppMsg::RunSearchItemAction(search_result, None),
+                        ).into()
+                    }
+                };
 
                 let search_list = container(search_list)
                     .width(Length::Fill)
@@ -1306,6 +1578,7 @@ impl Application for AppModel {
 
                 let content: Element<_> = column(vec![
                     inline_view_container(self.client_context.clone()).into(),
+                    self.inline_answer(),
                     list,
                 ]).into();
 
@@ -1633,11 +1906,22 @@ impl AppModel {
         }, |result| handle_backend_error(result, |()| AppMsg::Noop))
     }
 
-    fn run_command(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> Command<AppMsg> {
+    fn run_command(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool) -> Command<AppMsg> {
+        let mut backend_client = self.backend_api.clone();
+
+        Command::perform(async move {
+            backend_client.request_run_command(plugin_id, entrypoint_id, modifier_shift, modifier_control, modifier_alt, modifier_meta)
+                .await?;
+
+            Ok(())
+        }, handle_command_error)
+    }
+
+    fn run_fallback(&self, text: String) -> Command<AppMsg> {
         let mut backend_client = self.backend_api.clone();
 
         Command::perform(async move {
-            backend_client.request_run_command(plugin_id, entrypoint_id)
+            backend_client.request_run_fallback(text)
                 .await?;
 
             Ok(())
@@ -1652,7 +1936,7 @@ impl AppModel {
                 .await?;
 
             Ok(())
-        }, |result| handle_backend_error(result, |()| AppMsg::Noop))
+        }, handle_command_error)
     }
 
     fn handle_plugin_event(&self, widget_event: ComponentWidgetEvent, plugin_id: PluginId, render_location: UiRenderLocation) -> Command<AppMsg> {
@@ -1692,21 +1976,27 @@ impl AppModel {
         }, |result| handle_backend_error(result, |msg| msg))
     }
 
-    fn handle_main_view_keyboard_event(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, physical_key: PhysicalKey, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool) -> Command<AppMsg> {
+    fn handle_main_view_keyboard_event(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, physical_key: PhysicalKey, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool, text: Option<SmolStr>, modifiers: Modifiers) -> Command<AppMsg> {
         let mut backend_client = self.backend_api.clone();
 
         Command::perform(
             async move {
-                backend_client.send_keyboard_event(plugin_id, entrypoint_id, KeyboardEventOrigin::MainView, physical_key, modifier_shift, modifier_control, modifier_alt, modifier_meta)
+                let captured = backend_client.send_keyboard_event(plugin_id, entrypoint_id, KeyboardEventOrigin::MainView, physical_key, modifier_shift, modifier_control, modifier_alt, modifier_meta)
                     .await?;
 
-                Ok(())
+                Ok(captured)
             },
-            |result| handle_backend_error(result, |()| AppMsg::Noop),
+            |result| handle_backend_error(result, |captured| {
+                if captured {
+                    AppMsg::Noop
+                } else {
+                    AppMsg::KeyboardEventNotCaptured { text, modifiers }
+                }
+            }),
         )
     }
 
-    fn handle_plugin_view_keyboard_event(&self, physical_key: PhysicalKey, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool) -> Command<AppMsg> {
+    fn handle_plugin_view_keyboard_event(&self, physical_key: PhysicalKey, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool, text: Option<SmolStr>, modifiers: Modifiers) -> Command<AppMsg> {
         let mut backend_client = self.backend_api.clone();
 
         let (plugin_id, entrypoint_id) = {
@@ -1716,16 +2006,22 @@ impl AppModel {
 
         Command::perform(
             async move {
-                backend_client.send_keyboard_event(plugin_id, entrypoint_id, KeyboardEventOrigin::PluginView, physical_key, modifier_shift, modifier_control, modifier_alt, modifier_meta)
+                let captured = backend_client.send_keyboard_event(plugin_id, entrypoint_id, KeyboardEventOrigin::PluginView, physical_key, modifier_shift, modifier_control, modifier_alt, modifier_meta)
                     .await?;
 
-                Ok(())
+                Ok(captured)
             },
-            |result| handle_backend_error(result, |()| AppMsg::Noop),
+            |result| handle_backend_error(result, |captured| {
+                if captured {
+                    AppMsg::Noop
+                } else {
+                    AppMsg::KeyboardEventNotCaptured { text, modifiers }
+                }
+            }),
         )
     }
 
-    fn handle_inline_plugin_view_keyboard_event(&self, physical_key: PhysicalKey, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool) -> Command<AppMsg> {
+    fn handle_inline_plugin_view_keyboard_event(&self, physical_key: PhysicalKey, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool, text: Option<SmolStr>, modifiers: Modifiers) -> Command<AppMsg> {
         let mut backend_client = self.backend_api.clone();
 
         let (plugin_id, entrypoint_id) = {
@@ -1740,15 +2036,53 @@ impl AppModel {
 
         Command::perform(
             async move {
-                backend_client.send_keyboard_event(plugin_id, entrypoint_id, KeyboardEventOrigin::PluginView, physical_key, modifier_shift, modifier_control, modifier_alt, modifier_meta)
+                let captured = backend_client.send_keyboard_event(plugin_id, entrypoint_id, KeyboardEventOrigin::PluginView, physical_key, modifier_shift, modifier_control, modifier_alt, modifier_meta)
                     .await?;
 
-                Ok(())
+                Ok(captured)
             },
-            |result| handle_backend_error(result, |()| AppMsg::Noop),
+            |result| handle_backend_error(result, |captured| {
+                if captured {
+                    AppMsg::Noop
+                } else {
+                    AppMsg::KeyboardEventNotCaptured { text, modifiers }
+                }
+            }),
         )
     }
 
+    fn inline_answer(&self) -> Element<'_, AppMsg> {
+        let client_context = self.client_context.read().expect("lock is poisoned");
+
+        let Some(answer) = client_context.get_inline_answer() else {
+            return horizontal_space()
+                .into()
+        };
+
+        let value: Element<_> = text(answer.value.clone())
+            .size(24)
+            .width(Length::Fill)
+            .into();
+
+        let mut content = vec![value];
+
+        if let Some(copy_text) = &answer.copy_text {
+            let copy_button: Element<_> = button(text("Copy"))
+                .on_press(AppMsg::CopyInlineAnswer { text: copy_text.clone() })
+                .into();
+
+            content.push(copy_button);
+        }
+
+        let content: Element<_> = row(content)
+            .align_items(Alignment::Center)
+            .into();
+
+        container(content)
+            .width(Length::Fill)
+            .themed(ContainerStyle::Inline)
+    }
+
     fn search(&self, new_prompt: String, render_inline_view: bool) -> Command<AppMsg> {
         let mut backend_api = self.backend_api.clone();
 
@@ -1757,7 +2091,13 @@ impl AppModel {
                 .await?;
 
             Ok(search_results)
-        }, |result| handle_backend_error(result, |search_results| AppMsg::SetSearchResults(search_results)))
+        }, |result| {
+            match result {
+                // e.g. unknown search operator in the query
+                Err(BackendForFrontendApiError::BackendError(message)) => AppMsg::ShowSearchError { message },
+                result => handle_backend_error(result, |(results, suggestion)| AppMsg::SetSearchResults { results, suggestion })
+            }
+        })
     }
 
     fn open_settings_window_preferences(&self, plugin_id: PluginId, entrypoint_id: Option<EntrypointId>) -> Command<AppMsg> {
@@ -1810,6 +2150,14 @@ impl AppModel {
     }
 }
 
+// window is already hidden when command runs, so failure is shown in hud instead of error view
+fn handle_command_error(result: Result<(), BackendForFrontendApiError>) -> AppMsg {
+    match result {
+        Err(BackendForFrontendApiError::BackendError(message)) => AppMsg::ShowHud { display: message },
+        result => handle_backend_error(result, |()| AppMsg::Noop)
+    }
+}
+
 fn handle_backend_error<T>(result: Result<T, BackendForFrontendApiError>, convert: impl FnOnce(T) -> AppMsg) -> AppMsg {
     match result {
         Ok(val) => convert(val),
@@ -1885,13 +2233,14 @@ async fn request_loop(
                         entrypoint_preferences_required
                     }
                 }
-                UiRequestData::ShowPluginErrorView { plugin_id, entrypoint_id, render_location } => {
+                UiRequestData::ShowPluginErrorView { plugin_id, entrypoint_id, render_location, message } => {
                     responder.respond(UiResponseData::Nothing);
 
                     AppMsg::ShowPluginErrorView {
                         plugin_id,
                         entrypoint_id,
                         render_location,
+                        message,
                     }
                 }
                 UiRequestData::RequestSearchResultUpdate => {
@@ -1906,6 +2255,19 @@ async fn request_loop(
                         display
                     }
                 }
+                UiRequestData::ShowInlineAnswer { plugin_id, value, copy_text } => {
+                    client_context.set_inline_answer(plugin_id, value, copy_text);
+
+                    responder.respond(UiResponseData::Nothing);
+
+                    AppMsg::Noop // refresh ui
+                }
+                UiRequestData::RequestPermission { plugin_id: _, plugin_name, kind, detail } => {
+                    // responded when user makes a decision
+                    client_context.set_permission_request(plugin_name, kind, detail, responder);
+
+                    AppMsg::ShowPermissionRequest
+                }
             }
         };
 
@@ -72,7 +72,7 @@ impl<'a, Message> Component<Message, GauntletTheme> for SearchList<'a, Message>
                     .width(Length::Fill)
                     .into();
 
-                let sub_text: Element<_> = text(&search_result.plugin_name)
+                let sub_text: Element<_> = text(search_result.subtitle.as_ref().unwrap_or(&search_result.plugin_name))
                     .themed(TextStyle::MainListItemSubtext);
                 let sub_text: Element<_> = container(sub_text)
                     .themed(ContainerStyle::MainListItemSubText); // FIXME find a way to set padding based on whether the scroll bar is visible
@@ -26,6 +26,8 @@ pub enum GlobalState {
         client_context: Arc<StdRwLock<ClientContext>>,
         sub_state: MainViewState,
         pending_plugin_view_data: Option<PluginViewData>,
+        // view is opened but plugin haven't rendered it for some time
+        pending_plugin_view_loading: bool,
     },
     ErrorView {
         error_view: ErrorViewData,
@@ -57,6 +59,7 @@ pub enum ErrorViewData {
     PluginError {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
+        message: Option<String>,
     },
     BackendTimeout,
     UnknownError {
@@ -71,6 +74,7 @@ impl GlobalState {
             focused_search_result: ScrollHandle::new(true),
             sub_state: MainViewState::new(),
             pending_plugin_view_data: None,
+            pending_plugin_view_loading: false,
             client_context,
         }
     }
@@ -108,6 +112,22 @@ impl GlobalState {
         Command::none()
     }
 
+    // render is complete when first ReplaceView switches state to plugin view
+    pub fn show_pending_plugin_view_loading(&mut self, plugin_id: &PluginId, entrypoint_id: &EntrypointId) {
+        if let GlobalState::MainView { pending_plugin_view_data: Some(pending_plugin_view_data), pending_plugin_view_loading, .. } = self {
+            if &pending_plugin_view_data.plugin_id == plugin_id && &pending_plugin_view_data.entrypoint_id == entrypoint_id {
+                *pending_plugin_view_loading = true;
+            }
+        }
+    }
+
+    pub fn is_plugin_view_loading(&self) -> bool {
+        match self {
+            GlobalState::MainView { pending_plugin_view_data: Some(_), pending_plugin_view_loading, .. } => *pending_plugin_view_loading,
+            _ => false,
+        }
+    }
+
     pub fn plugin(prev_global_state: &mut GlobalState, plugin_view_data: PluginViewData, client_context: Arc<StdRwLock<ClientContext>>) -> Command<AppMsg> {
         *prev_global_state = GlobalState::PluginView {
             client_context,
@@ -154,6 +174,8 @@ impl Focus<SearchResult> for GlobalState {
                                     }
                                     None => Command::none()
                                 }
+                            } else if focus_list.is_empty() {
+                                Command::perform(async {}, |_| AppMsg::RunFallback)
                             } else {
                                 Command::none()
                             }
@@ -426,3 +448,41 @@ impl Focus<SearchResult> for GlobalState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin_view_data(entrypoint_id: &str) -> PluginViewData {
+        PluginViewData {
+            top_level_view: true,
+            plugin_id: PluginId::from_string("test-plugin"),
+            plugin_name: "Test Plugin".to_string(),
+            entrypoint_id: EntrypointId::from_string(entrypoint_id),
+            entrypoint_name: entrypoint_id.to_string(),
+            action_shortcuts: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn slow_render_shows_loading_until_view_is_rendered() {
+        let client_context = Arc::new(StdRwLock::new(ClientContext::new()));
+        let mut global_state = GlobalState::new(text_input::Id::unique(), client_context.clone());
+
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        if let GlobalState::MainView { pending_plugin_view_data, .. } = &mut global_state {
+            *pending_plugin_view_data = Some(plugin_view_data("slow-view"));
+        }
+
+        // delayed message of previously opened view is ignored
+        global_state.show_pending_plugin_view_loading(&plugin_id, &EntrypointId::from_string("other-view"));
+        assert!(!global_state.is_plugin_view_loading());
+
+        global_state.show_pending_plugin_view_loading(&plugin_id, &EntrypointId::from_string("slow-view"));
+        assert!(global_state.is_plugin_view_loading());
+
+        let _ = GlobalState::plugin(&mut global_state, plugin_view_data("slow-view"), client_context);
+        assert!(!global_state.is_plugin_view_loading());
+    }
+}
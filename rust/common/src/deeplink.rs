@@ -0,0 +1,136 @@
+use anyhow::anyhow;
+use percent_encoding::percent_decode_str;
+
+use crate::model::{EntrypointId, PluginId};
+
+pub const DEEPLINK_SCHEME: &str = "gauntlet";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    RunEntrypoint {
+        plugin_id: PluginId,
+        entrypoint_id: EntrypointId,
+    }
+}
+
+// plugin ids are urls themselves, so they are expected to be percent-encoded
+// e.g. gauntlet://run/file%3A%2F%2F%2Fhome%2Fuser%2Fplugin/main
+pub fn parse_deeplink(uri: &str) -> anyhow::Result<DeepLink> {
+    let rest = uri.strip_prefix(&format!("{}://", DEEPLINK_SCHEME))
+        .ok_or_else(|| anyhow!("uri '{}' doesn't use '{}' scheme", uri, DEEPLINK_SCHEME))?;
+
+    let rest = rest.trim_end_matches('/');
+
+    let (action, rest) = rest.split_once('/')
+        .ok_or_else(|| anyhow!("uri '{}' doesn't contain any action", uri))?;
+
+    match action {
+        "run" => {
+            let (plugin_id, entrypoint_id) = rest.split_once('/')
+                .ok_or_else(|| anyhow!("uri '{}' is expected to be in format {}://run/<plugin_id>/<entrypoint_id>", uri, DEEPLINK_SCHEME))?;
+
+            if entrypoint_id.contains('/') {
+                return Err(anyhow!("uri '{}' contains unexpected path segments, plugin id is expected to be percent-encoded", uri))
+            }
+
+            let plugin_id = percent_decode_str(plugin_id).decode_utf8()?;
+            let entrypoint_id = percent_decode_str(entrypoint_id).decode_utf8()?;
+
+            if plugin_id.is_empty() || entrypoint_id.is_empty() {
+                return Err(anyhow!("uri '{}' contains empty plugin or entrypoint id", uri))
+            }
+
+            Ok(DeepLink::RunEntrypoint {
+                plugin_id: PluginId::from_string(plugin_id),
+                entrypoint_id: EntrypointId::from_string(entrypoint_id),
+            })
+        }
+        _ => Err(anyhow!("uri '{}' contains unknown action '{}'", uri, action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_entrypoint(plugin_id: &str, entrypoint_id: &str) -> DeepLink {
+        DeepLink::RunEntrypoint {
+            plugin_id: PluginId::from_string(plugin_id),
+            entrypoint_id: EntrypointId::from_string(entrypoint_id),
+        }
+    }
+
+    #[test]
+    fn parses_run_uri() {
+        let deeplink = parse_deeplink("gauntlet://run/plugin/main").unwrap();
+
+        assert_eq!(deeplink, run_entrypoint("plugin", "main"));
+    }
+
+    #[test]
+    fn decodes_percent_encoded_plugin_id() {
+        let deeplink = parse_deeplink("gauntlet://run/file%3A%2F%2F%2Fhome%2Fuser%2Fplugin/main").unwrap();
+
+        assert_eq!(deeplink, run_entrypoint("file:///home/user/plugin", "main"));
+    }
+
+    #[test]
+    fn decodes_percent_encoded_entrypoint_id() {
+        let deeplink = parse_deeplink("gauntlet://run/plugin/open%20file").unwrap();
+
+        assert_eq!(deeplink, run_entrypoint("plugin", "open file"));
+    }
+
+    #[test]
+    fn ignores_trailing_slash() {
+        let deeplink = parse_deeplink("gauntlet://run/plugin/main/").unwrap();
+
+        assert_eq!(deeplink, run_entrypoint("plugin", "main"));
+    }
+
+    // whether plugin exists is checked by server when entrypoint is run
+    #[test]
+    fn parses_unknown_plugin_id() {
+        let deeplink = parse_deeplink("gauntlet://run/unknown-plugin/unknown-entrypoint").unwrap();
+
+        assert_eq!(deeplink, run_entrypoint("unknown-plugin", "unknown-entrypoint"));
+    }
+
+    #[test]
+    fn rejects_other_scheme() {
+        assert!(parse_deeplink("https://run/plugin/main").is_err());
+        assert!(parse_deeplink("run/plugin/main").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_action() {
+        assert!(parse_deeplink("gauntlet://").is_err());
+        assert!(parse_deeplink("gauntlet://run").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_action() {
+        assert!(parse_deeplink("gauntlet://open/plugin/main").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_entrypoint_id() {
+        assert!(parse_deeplink("gauntlet://run/plugin").is_err());
+        assert!(parse_deeplink("gauntlet://run/plugin/").is_err());
+    }
+
+    #[test]
+    fn rejects_empty_ids() {
+        assert!(parse_deeplink("gauntlet://run//main").is_err());
+    }
+
+    #[test]
+    fn rejects_not_encoded_plugin_id() {
+        assert!(parse_deeplink("gauntlet://run/file:///home/user/plugin/main").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_utf8() {
+        assert!(parse_deeplink("gauntlet://run/%FF/main").is_err());
+    }
+}
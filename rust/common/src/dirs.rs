@@ -38,6 +38,11 @@ impl Dirs {
         Ok(plugin_data_dir)
     }
 
+    pub fn search_index_dir(&self) -> anyhow::Result<PathBuf> {
+        let path = self.data_dir()?.join("search_index");
+        Ok(path)
+    }
+
     pub fn data_dir(&self) -> anyhow::Result<PathBuf> {
         let data_dir = if cfg!(feature = "release") || cfg!(feature = "scenario_runner") {
             self.inner.data_dir().to_path_buf()
@@ -5,6 +5,7 @@ pub mod rpc;
 pub mod scenario_convert;
 pub mod scenario_model;
 pub mod dirs;
+pub mod deeplink;
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use anyhow::anyhow;
 use gix_url::Scheme;
@@ -63,6 +64,7 @@ impl ToString for EntrypointId {
 
 #[derive(Debug, Clone)]
 pub enum DownloadStatus {
+    Pending,
     InProgress,
     Done,
     Failed {
@@ -70,13 +72,53 @@ pub enum DownloadStatus {
     },
 }
 
+#[derive(Debug, Clone)]
+pub struct GauntletMetrics {
+    pub installed_plugins: usize,
+    pub enabled_plugins: usize,
+    pub running_plugins: usize,
+    pub total_entrypoints: usize,
+    pub search_latency_p50: Option<Duration>,
+    pub search_latency_p95: Option<Duration>,
+    pub search_latency_p99: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthStatus {
+    Responsive,
+    Unresponsive,
+    NotRunning,
+}
+
+#[derive(Debug, Clone)]
+pub struct PluginResourceUsage {
+    pub heap_used_bytes: usize,
+    pub heap_total_bytes: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct PluginUpdate {
+    pub plugin_id: PluginId,
+    pub plugin_name: String,
+    pub current_version: String,
+    pub latest_version: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum UpdateResult {
+    Updated,
+    Failed {
+        message: String
+    },
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum UiRenderLocation {
     InlineView,
     View
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PhysicalShortcut {
     pub physical_key: PhysicalKey,
     pub modifier_shift: bool,
@@ -85,6 +127,40 @@ pub struct PhysicalShortcut {
     pub modifier_meta: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct PluginLogLine {
+    pub timestamp: SystemTime,
+    pub level: PluginLogLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PreferenceSearchResult {
+    pub plugin_id: PluginId,
+    pub plugin_name: String,
+    pub entrypoint_id: Option<EntrypointId>,
+    pub entrypoint_name: Option<String>,
+    pub preference_id: String,
+    pub preference_name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PluginAuditEntry {
+    pub timestamp: SystemTime,
+    pub action: String,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PluginLogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
 #[derive(Debug, Clone)]
 pub struct LocalSaveData {
     pub stdout_file_path: String,
@@ -100,6 +176,15 @@ pub struct SearchResult {
     pub entrypoint_icon: Option<String>,
     pub entrypoint_type: SearchResultEntrypointType,
     pub entrypoint_actions: Vec<SearchResultEntrypointAction>,
+    pub subtitle: Option<String>,
+    pub result_kind: SearchResultKind,
+}
+
+// what result represents from user's point of view, generated commands are shown as regular commands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchResultKind {
+    Command,
+    View,
 }
 
 #[derive(Debug, Clone)]
@@ -118,6 +203,12 @@ pub enum SearchResultEntrypointType {
 #[derive(Debug)]
 pub enum UiResponseData {
     Nothing,
+    PermissionDecision {
+        granted: bool
+    },
+    Error {
+        message: String
+    },
 }
 
 #[derive(Debug)]
@@ -145,18 +236,34 @@ pub enum UiRequestData {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
         render_location: UiRenderLocation,
+        message: Option<String>,
     },
     RequestSearchResultUpdate,
     ShowHud {
         display: String
     },
+    ShowInlineAnswer {
+        plugin_id: PluginId,
+        value: String,
+        copy_text: Option<String>,
+    },
+    RequestPermission {
+        plugin_id: PluginId,
+        plugin_name: String,
+        kind: String,
+        detail: String,
+    },
 }
 
 #[derive(Debug)]
 pub enum BackendResponseData {
     Nothing,
+    Error {
+        message: String
+    },
     Search {
-        results: Vec<SearchResult>
+        results: Vec<SearchResult>,
+        suggestion: Option<String>,
     },
     RequestViewRender {
         shortcuts: HashMap<String, PhysicalShortcut>
@@ -164,6 +271,9 @@ pub enum BackendResponseData {
     InlineViewShortcuts {
         shortcuts: HashMap<PluginId, HashMap<String, PhysicalShortcut>>
     },
+    SendKeyboardEvent {
+        captured: bool
+    },
 }
 
 #[derive(Debug)]
@@ -181,13 +291,26 @@ pub enum BackendRequestData {
     },
     RequestRunCommand {
         plugin_id: PluginId,
-        entrypoint_id: EntrypointId
+        entrypoint_id: EntrypointId,
+        modifier_shift: bool,
+        modifier_control: bool,
+        modifier_alt: bool,
+        modifier_meta: bool,
     },
     RequestRunGeneratedCommand {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
         action_index: Option<usize>
     },
+    RequestRunFallback {
+        text: String,
+    },
+    RequestPluginReload {
+        plugin_id: PluginId,
+    },
+    RequestPluginForceStop {
+        plugin_id: PluginId,
+    },
     SendViewEvent {
         plugin_id: PluginId,
         widget_id: UiWidgetId,
@@ -402,7 +525,7 @@ pub struct PreferenceEnumValue {
 
 
 // copy of iced (currently fork) PhysicalKey but without modifiers
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PhysicalKey {
     Backquote,
     Backslash,
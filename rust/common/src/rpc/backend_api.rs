@@ -6,15 +6,17 @@ use tonic::transport::Channel;
 
 use utils::channel::{RequestError, RequestSender};
 
-use crate::model::{BackendRequestData, BackendResponseData, DownloadStatus, EntrypointId, KeyboardEventOrigin, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginPreferenceUserData, SearchResult, SettingsEntrypoint, SettingsEntrypointType, SettingsPlugin, UiPropertyValue, UiWidgetId};
-use crate::rpc::grpc::{RpcDownloadPluginRequest, RpcDownloadStatus, RpcDownloadStatusRequest, RpcEntrypointTypeSettings, RpcGetGlobalShortcutRequest, RpcPingRequest, RpcPluginsRequest, RpcRemovePluginRequest, RpcSaveLocalPluginRequest, RpcSetEntrypointStateRequest, RpcSetGlobalShortcutRequest, RpcSetPluginStateRequest, RpcSetPreferenceValueRequest, RpcShowSettingsWindowRequest, RpcShowWindowRequest};
+use crate::model::{BackendRequestData, BackendResponseData, DownloadStatus, EntrypointId, KeyboardEventOrigin, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginLogLine, PluginPreferenceUserData, PluginUpdate, SearchResult, SettingsEntrypoint, SettingsEntrypointType, SettingsPlugin, UiPropertyValue, UiWidgetId, UpdateResult};
+use crate::rpc::grpc::{RpcDownloadPluginRequest, RpcDownloadStatus, RpcDownloadStatusRequest, RpcEntrypointTypeSettings, RpcGetGlobalShortcutRequest, RpcPingRequest, RpcPluginLogsRequest, RpcPluginsRequest, RpcRemovePluginRequest, RpcRunEntrypointRequest, RpcSaveLocalPluginRequest, RpcSearchRequest, RpcSetEntrypointStateRequest, RpcSetGlobalShortcutRequest, RpcSetPluginStateRequest, RpcSetPreferenceValueRequest, RpcShowSettingsWindowRequest, RpcShowWindowRequest, RpcUpdateAllPluginsRequest, RpcUpdatesAvailableRequest};
 use crate::rpc::grpc::rpc_backend_client::RpcBackendClient;
-use crate::rpc::grpc_convert::{plugin_preference_from_rpc, plugin_preference_user_data_from_rpc, plugin_preference_user_data_to_rpc};
+use crate::rpc::grpc_convert::{plugin_preference_from_rpc, plugin_preference_user_data_from_rpc, plugin_log_line_from_rpc, plugin_preference_user_data_to_rpc, search_result_from_rpc};
 
 #[derive(Error, Debug, Clone)]
 pub enum BackendForFrontendApiError {
     #[error("Frontend wasn't able to process request in a timely manner")]
     TimeoutError,
+    #[error("Backend failed to process request: {0}")]
+    BackendError(String),
 }
 
 impl From<RequestError> for BackendForFrontendApiError {
@@ -37,17 +39,17 @@ impl BackendForFrontendApi {
         }
     }
 
-    pub async fn search(&mut self, text: String, render_inline_view: bool) -> Result<Vec<SearchResult>, BackendForFrontendApiError> {
+    pub async fn search(&mut self, text: String, render_inline_view: bool) -> Result<(Vec<SearchResult>, Option<String>), BackendForFrontendApiError> {
         let request = BackendRequestData::Search {
             text,
             render_inline_view,
         };
 
-        let BackendResponseData::Search { results } = self.backend_sender.send_receive(request).await? else {
-            unreachable!()
-        };
-
-        Ok(results)
+        match self.backend_sender.send_receive(request).await? {
+            BackendResponseData::Search { results, suggestion } => Ok((results, suggestion)),
+            BackendResponseData::Error { message } => Err(BackendForFrontendApiError::BackendError(message)),
+            _ => unreachable!()
+        }
     }
 
     pub async fn request_view_render(&mut self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> Result<HashMap<String, PhysicalShortcut>, BackendForFrontendApiError> {
@@ -75,10 +77,34 @@ impl BackendForFrontendApi {
         Ok(())
     }
 
-    pub async fn request_run_command(&mut self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> Result<(), BackendForFrontendApiError> {
+    pub async fn request_run_command(
+        &mut self,
+        plugin_id: PluginId,
+        entrypoint_id: EntrypointId,
+        modifier_shift: bool,
+        modifier_control: bool,
+        modifier_alt: bool,
+        modifier_meta: bool
+    ) -> Result<(), BackendForFrontendApiError> {
         let request = BackendRequestData::RequestRunCommand {
             plugin_id,
             entrypoint_id,
+            modifier_shift,
+            modifier_control,
+            modifier_alt,
+            modifier_meta,
+        };
+
+        match self.backend_sender.send_receive(request).await? {
+            BackendResponseData::Nothing => Ok(()),
+            BackendResponseData::Error { message } => Err(BackendForFrontendApiError::BackendError(message)),
+            _ => unreachable!()
+        }
+    }
+
+    pub async fn request_plugin_reload(&mut self, plugin_id: PluginId) -> Result<(), BackendForFrontendApiError> {
+        let request = BackendRequestData::RequestPluginReload {
+            plugin_id,
         };
 
         let BackendResponseData::Nothing = self.backend_sender.send_receive(request).await? else {
@@ -88,11 +114,9 @@ impl BackendForFrontendApi {
         Ok(())
     }
 
-    pub async fn request_run_generated_command(&mut self, plugin_id: PluginId, entrypoint_id: EntrypointId, action_index: Option<usize>) -> Result<(), BackendForFrontendApiError> {
-        let request = BackendRequestData::RequestRunGeneratedCommand {
+    pub async fn request_plugin_force_stop(&mut self, plugin_id: PluginId) -> Result<(), BackendForFrontendApiError> {
+        let request = BackendRequestData::RequestPluginForceStop {
             plugin_id,
-            entrypoint_id,
-            action_index,
         };
 
         let BackendResponseData::Nothing = self.backend_sender.send_receive(request).await? else {
@@ -102,6 +126,32 @@ impl BackendForFrontendApi {
         Ok(())
     }
 
+    pub async fn request_run_fallback(&mut self, text: String) -> Result<(), BackendForFrontendApiError> {
+        let request = BackendRequestData::RequestRunFallback {
+            text,
+        };
+
+        let BackendResponseData::Nothing = self.backend_sender.send_receive(request).await? else {
+            unreachable!()
+        };
+
+        Ok(())
+    }
+
+    pub async fn request_run_generated_command(&mut self, plugin_id: PluginId, entrypoint_id: EntrypointId, action_index: Option<usize>) -> Result<(), BackendForFrontendApiError> {
+        let request = BackendRequestData::RequestRunGeneratedCommand {
+            plugin_id,
+            entrypoint_id,
+            action_index,
+        };
+
+        match self.backend_sender.send_receive(request).await? {
+            BackendResponseData::Nothing => Ok(()),
+            BackendResponseData::Error { message } => Err(BackendForFrontendApiError::BackendError(message)),
+            _ => unreachable!()
+        }
+    }
+
     pub async fn send_view_event(
         &mut self,
         plugin_id: PluginId,
@@ -133,7 +183,7 @@ impl BackendForFrontendApi {
         modifier_control: bool,
         modifier_alt: bool,
         modifier_meta: bool
-    ) -> Result<(), BackendForFrontendApiError> {
+    ) -> Result<bool, BackendForFrontendApiError> {
         let request = BackendRequestData::SendKeyboardEvent {
             plugin_id,
             entrypoint_id,
@@ -145,11 +195,11 @@ impl BackendForFrontendApi {
             modifier_meta,
         };
 
-        let BackendResponseData::Nothing = self.backend_sender.send_receive(request).await? else {
+        let BackendResponseData::SendKeyboardEvent { captured } = self.backend_sender.send_receive(request).await? else {
             unreachable!()
         };
 
-        Ok(())
+        Ok(captured)
     }
 
     pub async fn send_open_event(&mut self, plugin_id: PluginId, href: String) -> Result<(), BackendForFrontendApiError> {
@@ -263,6 +313,33 @@ impl BackendApi {
         Ok(())
     }
 
+    pub async fn run_entrypoint(&mut self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> Result<(), BackendApiError> {
+        let request = RpcRunEntrypointRequest {
+            plugin_id: plugin_id.to_string(),
+            entrypoint_id: entrypoint_id.to_string(),
+        };
+
+        self.client.run_entrypoint(Request::new(request))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn search(&mut self, text: String) -> Result<Vec<SearchResult>, BackendApiError> {
+        let request = RpcSearchRequest { text };
+
+        let results = self.client.search(Request::new(request))
+            .await?
+            .into_inner()
+            .results
+            .into_iter()
+            .map(|result| search_result_from_rpc(result))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|err| BackendApiError::Internal { display: format!("{:#}", err) })?;
+
+        Ok(results)
+    }
+
     pub async fn plugins(&mut self) -> Result<HashMap<PluginId, SettingsPlugin>, BackendApiError> {
         let plugins = self.client.plugins(Request::new(RpcPluginsRequest::default()))
             .await?
@@ -413,6 +490,7 @@ impl BackendApi {
                 let plugin_id = PluginId::from_string(plugin_id);
 
                 let status = match status.status.try_into()? {
+                    RpcDownloadStatus::Pending => DownloadStatus::Pending,
                     RpcDownloadStatus::InProgress => DownloadStatus::InProgress,
                     RpcDownloadStatus::Done => DownloadStatus::Done,
                     RpcDownloadStatus::Failed => DownloadStatus::Failed { message: status.message },
@@ -425,6 +503,45 @@ impl BackendApi {
         Ok(plugins)
     }
 
+    pub async fn updates_available(&mut self) -> Result<Vec<PluginUpdate>, BackendApiError> {
+        let updates = self.client.updates_available(Request::new(RpcUpdatesAvailableRequest::default()))
+            .await?
+            .into_inner()
+            .updates
+            .into_iter()
+            .map(|update| PluginUpdate {
+                plugin_id: PluginId::from_string(update.plugin_id),
+                plugin_name: update.plugin_name,
+                current_version: update.current_version,
+                latest_version: update.latest_version,
+            })
+            .collect();
+
+        Ok(updates)
+    }
+
+    pub async fn update_all_plugins(&mut self) -> Result<Vec<(PluginId, UpdateResult)>, BackendApiError> {
+        let results = self.client.update_all_plugins(Request::new(RpcUpdateAllPluginsRequest::default()))
+            .await?
+            .into_inner()
+            .results
+            .into_iter()
+            .map(|result| {
+                let plugin_id = PluginId::from_string(result.plugin_id);
+
+                let result = if result.updated {
+                    UpdateResult::Updated
+                } else {
+                    UpdateResult::Failed { message: result.message }
+                };
+
+                (plugin_id, result)
+            })
+            .collect();
+
+        Ok(results)
+    }
+
     pub async fn remove_plugin(&mut self, plugin_id: PluginId) -> Result<(), BackendApiError> {
         let request = RpcRemovePluginRequest { plugin_id: plugin_id.to_string() };
 
@@ -434,8 +551,26 @@ impl BackendApi {
         Ok(())
     }
 
-    pub async fn save_local_plugin(&mut self, path: String) -> Result<LocalSaveData, BackendApiError> {
-        let request = RpcSaveLocalPluginRequest { path };
+    pub async fn plugin_logs(&mut self, plugin_id: PluginId, limit: usize) -> Result<Vec<PluginLogLine>, BackendApiError> {
+        let request = RpcPluginLogsRequest {
+            plugin_id: plugin_id.to_string(),
+            limit: limit as u32,
+        };
+
+        let lines = self.client.plugin_logs(Request::new(request))
+            .await?
+            .into_inner()
+            .lines
+            .into_iter()
+            .map(|line| plugin_log_line_from_rpc(line))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|err| BackendApiError::Internal { display: format!("{:#}", err) })?;
+
+        Ok(lines)
+    }
+
+    pub async fn save_local_plugin(&mut self, path: String, linked: bool) -> Result<LocalSaveData, BackendApiError> {
+        let request = RpcSaveLocalPluginRequest { path, linked };
 
         let response = self.client.save_local_plugin(Request::new(request))
             .await?
@@ -1,26 +1,38 @@
 use std::collections::HashMap;
+use std::future::Future;
 use std::net::SocketAddr;
 use std::time::Duration;
 
+use anyhow::anyhow;
 use tokio::net::TcpStream;
 use tonic::{Request, Response, Status};
 use tonic::transport::Server;
 
-use crate::model::{DownloadStatus, EntrypointId, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginPreferenceUserData, SettingsEntrypointType, SettingsPlugin};
-use crate::rpc::grpc::{RpcDownloadPluginRequest, RpcDownloadPluginResponse, RpcDownloadStatus, RpcDownloadStatusRequest, RpcDownloadStatusResponse, RpcDownloadStatusValue, RpcEntrypoint, RpcEntrypointTypeSettings, RpcGetGlobalShortcutRequest, RpcGetGlobalShortcutResponse, RpcPingRequest, RpcPingResponse, RpcPlugin, RpcPluginsRequest, RpcPluginsResponse, RpcRemovePluginRequest, RpcRemovePluginResponse, RpcSaveLocalPluginRequest, RpcSaveLocalPluginResponse, RpcSetEntrypointStateRequest, RpcSetEntrypointStateResponse, RpcSetGlobalShortcutRequest, RpcSetGlobalShortcutResponse, RpcSetPluginStateRequest, RpcSetPluginStateResponse, RpcSetPreferenceValueRequest, RpcSetPreferenceValueResponse, RpcShowSettingsWindowRequest, RpcShowSettingsWindowResponse, RpcShowWindowRequest, RpcShowWindowResponse};
+use crate::model::{DownloadStatus, EntrypointId, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginLogLine, PluginPreferenceUserData, PluginUpdate, SearchResult, UpdateResult, SettingsEntrypointType, SettingsPlugin};
+use crate::rpc::grpc::{RpcDownloadPluginRequest, RpcDownloadPluginResponse, RpcDownloadStatus, RpcDownloadStatusRequest, RpcDownloadStatusResponse, RpcDownloadStatusValue, RpcEntrypoint, RpcEntrypointTypeSettings, RpcGetGlobalShortcutRequest, RpcGetGlobalShortcutResponse, RpcPingRequest, RpcPingResponse, RpcPlugin, RpcPluginLogsRequest, RpcPluginLogsResponse, RpcPluginUpdate, RpcPluginUpdateResult, RpcPluginsRequest, RpcPluginsResponse, RpcRemovePluginRequest, RpcRemovePluginResponse, RpcRunEntrypointRequest, RpcRunEntrypointResponse, RpcSaveLocalPluginRequest, RpcSaveLocalPluginResponse, RpcSearchRequest, RpcSearchResponse, RpcSetEntrypointStateRequest, RpcSetEntrypointStateResponse, RpcSetGlobalShortcutRequest, RpcSetGlobalShortcutResponse, RpcSetPluginStateRequest, RpcSetPluginStateResponse, RpcSetPreferenceValueRequest, RpcSetPreferenceValueResponse, RpcShowSettingsWindowRequest, RpcShowSettingsWindowResponse, RpcShowWindowRequest, RpcShowWindowResponse, RpcUpdateAllPluginsRequest, RpcUpdateAllPluginsResponse, RpcUpdatesAvailableRequest, RpcUpdatesAvailableResponse};
 use crate::rpc::grpc::rpc_backend_server::{RpcBackend, RpcBackendServer};
-use crate::rpc::grpc_convert::{plugin_preference_to_rpc, plugin_preference_user_data_from_rpc, plugin_preference_user_data_to_rpc};
+use crate::rpc::grpc_convert::{plugin_preference_to_rpc, plugin_preference_user_data_from_rpc, plugin_log_line_to_rpc, plugin_preference_user_data_to_rpc, search_result_to_rpc};
 
-pub async fn wait_for_backend_server() {
-    loop {
-        let addr: SocketAddr = "127.0.0.1:42320".parse().unwrap();
+pub async fn wait_for_backend_server(timeout: Duration) -> anyhow::Result<()> {
+    let wait = async {
+        loop {
+            let addr: SocketAddr = "127.0.0.1:42320".parse().unwrap();
 
-        if TcpStream::connect(addr).await.is_ok() {
-            return;
+            if TcpStream::connect(addr).await.is_ok() {
+                return;
+            }
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
         }
+    };
 
-        tokio::time::sleep(Duration::from_millis(100)).await;
-    }
+    wait_with_timeout(timeout, wait).await
+}
+
+async fn wait_with_timeout(timeout: Duration, wait: impl Future<Output=()>) -> anyhow::Result<()> {
+    tokio::time::timeout(timeout, wait)
+        .await
+        .map_err(|_| anyhow!("Backend server didn't start within {:?}, check server logs for errors", timeout))
 }
 
 pub async fn start_backend_server(server: Box<dyn BackendServer + Sync + Send>) {
@@ -51,6 +63,14 @@ pub trait BackendServer {
 
     async fn show_settings_window(&self) -> anyhow::Result<()>;
 
+    async fn run_entrypoint(
+        &self,
+        plugin_id: PluginId,
+        entrypoint_id: EntrypointId
+    ) -> anyhow::Result<()>;
+
+    async fn search(&self, text: String) -> anyhow::Result<Vec<SearchResult>>;
+
     async fn plugins(&self) -> anyhow::Result<Vec<SettingsPlugin>>;
 
     async fn set_plugin_state(
@@ -87,9 +107,15 @@ pub trait BackendServer {
 
     async fn download_status(&self) -> anyhow::Result<HashMap<PluginId, DownloadStatus>>;
 
+    async fn updates_available(&self) -> anyhow::Result<Vec<PluginUpdate>>;
+
+    async fn update_all_plugins(&self) -> anyhow::Result<Vec<(PluginId, UpdateResult)>>;
+
     async fn remove_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()>;
 
-    async fn save_local_plugin(&self, path: String) -> anyhow::Result<LocalSaveData>;
+    async fn plugin_logs(&self, plugin_id: PluginId, limit: usize) -> anyhow::Result<Vec<PluginLogLine>>;
+
+    async fn save_local_plugin(&self, path: String, linked: bool) -> anyhow::Result<LocalSaveData>;
 }
 
 
@@ -115,6 +141,31 @@ impl RpcBackend for RpcBackendServerImpl {
         Ok(Response::new(RpcShowSettingsWindowResponse::default()))
     }
 
+    async fn run_entrypoint(&self, request: Request<RpcRunEntrypointRequest>) -> Result<Response<RpcRunEntrypointResponse>, Status> {
+        let request = request.into_inner();
+        let plugin_id = PluginId::from_string(request.plugin_id);
+        let entrypoint_id = EntrypointId::from_string(request.entrypoint_id);
+
+        self.server.run_entrypoint(plugin_id, entrypoint_id)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?;
+
+        Ok(Response::new(RpcRunEntrypointResponse::default()))
+    }
+
+    async fn search(&self, request: Request<RpcSearchRequest>) -> Result<Response<RpcSearchResponse>, Status> {
+        let request = request.into_inner();
+
+        let results = self.server.search(request.text)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?
+            .into_iter()
+            .map(|result| search_result_to_rpc(result))
+            .collect();
+
+        Ok(Response::new(RpcSearchResponse { results }))
+    }
+
     async fn plugins(&self, _: Request<RpcPluginsRequest>) -> Result<Response<RpcPluginsResponse>, Status> {
         let plugins = self.server.plugins()
             .await
@@ -270,6 +321,7 @@ impl RpcBackend for RpcBackendServerImpl {
             .into_iter()
             .map(|(plugin_id, status)| {
                 let (status, message) = match status {
+                    DownloadStatus::Pending => (RpcDownloadStatus::Pending, "".to_owned()),
                     DownloadStatus::InProgress => (RpcDownloadStatus::InProgress, "".to_owned()),
                     DownloadStatus::Done => (RpcDownloadStatus::Done, "".to_owned()),
                     DownloadStatus::Failed { message } => (RpcDownloadStatus::Failed, message),
@@ -286,6 +338,44 @@ impl RpcBackend for RpcBackendServerImpl {
         Ok(Response::new(response))
     }
 
+    async fn updates_available(&self, _: Request<RpcUpdatesAvailableRequest>) -> Result<Response<RpcUpdatesAvailableResponse>, Status> {
+        let updates = self.server.updates_available()
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?
+            .into_iter()
+            .map(|update| RpcPluginUpdate {
+                plugin_id: update.plugin_id.to_string(),
+                plugin_name: update.plugin_name,
+                current_version: update.current_version,
+                latest_version: update.latest_version,
+            })
+            .collect();
+
+        Ok(Response::new(RpcUpdatesAvailableResponse { updates }))
+    }
+
+    async fn update_all_plugins(&self, _: Request<RpcUpdateAllPluginsRequest>) -> Result<Response<RpcUpdateAllPluginsResponse>, Status> {
+        let results = self.server.update_all_plugins()
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?
+            .into_iter()
+            .map(|(plugin_id, result)| {
+                let (updated, message) = match result {
+                    UpdateResult::Updated => (true, "".to_owned()),
+                    UpdateResult::Failed { message } => (false, message),
+                };
+
+                RpcPluginUpdateResult {
+                    plugin_id: plugin_id.to_string(),
+                    updated,
+                    message,
+                }
+            })
+            .collect();
+
+        Ok(Response::new(RpcUpdateAllPluginsResponse { results }))
+    }
+
     async fn remove_plugin(&self, request: Request<RpcRemovePluginRequest>) -> Result<Response<RpcRemovePluginResponse>, Status> {
         let request = request.into_inner();
         let plugin_id = request.plugin_id;
@@ -299,11 +389,26 @@ impl RpcBackend for RpcBackendServerImpl {
         Ok(Response::new(RpcRemovePluginResponse::default()))
     }
 
+    async fn plugin_logs(&self, request: Request<RpcPluginLogsRequest>) -> Result<Response<RpcPluginLogsResponse>, Status> {
+        let request = request.into_inner();
+        let plugin_id = PluginId::from_string(request.plugin_id);
+
+        let lines = self.server.plugin_logs(plugin_id, request.limit as usize)
+            .await
+            .map_err(|err| Status::internal(format!("{:#}", err)))?
+            .into_iter()
+            .map(|line| plugin_log_line_to_rpc(line))
+            .collect();
+
+        Ok(Response::new(RpcPluginLogsResponse { lines }))
+    }
+
     async fn save_local_plugin(&self, request: Request<RpcSaveLocalPluginRequest>) -> Result<Response<RpcSaveLocalPluginResponse>, Status> {
         let request = request.into_inner();
         let path = request.path;
+        let linked = request.linked;
 
-        let local_save_data = self.server.save_local_plugin(path)
+        let local_save_data = self.server.save_local_plugin(path, linked)
             .await
             .map_err(|err| Status::internal(format!("{:#}", err)))?;
 
@@ -313,3 +418,22 @@ impl RpcBackend for RpcBackendServerImpl {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn waiting_for_server_which_never_starts_times_out() {
+        let result = wait_with_timeout(Duration::from_millis(10), std::future::pending()).await;
+
+        assert!(result.unwrap_err().to_string().contains("didn't start within"));
+    }
+
+    #[tokio::test]
+    async fn waiting_for_started_server_succeeds() {
+        let result = wait_with_timeout(Duration::from_secs(1), async {}).await;
+
+        assert!(result.is_ok());
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use thiserror::Error;
 use utils::channel::{RequestError, RequestSender};
 
@@ -7,6 +9,10 @@ use crate::model::{EntrypointId, PluginId, UiRenderLocation, UiRequestData, UiRe
 pub enum FrontendApiError {
     #[error("Frontend wasn't able to process request in a timely manner")]
     TimeoutError,
+    #[error("Frontend returned unexpected response")]
+    UnexpectedResponse,
+    #[error("Frontend failed to process request: {0}")]
+    FrontendError(String),
 }
 
 impl From<RequestError> for FrontendApiError {
@@ -17,6 +23,9 @@ impl From<RequestError> for FrontendApiError {
     }
 }
 
+// user has to notice the prompt and decide, so waiting is a lot longer than for other requests
+const PERMISSION_PROMPT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
 #[derive(Debug, Clone)]
 pub struct FrontendApi {
     frontend_sender: RequestSender<UiRequestData, UiResponseData>,
@@ -29,8 +38,23 @@ impl FrontendApi {
         }
     }
 
+    // error response is turned into error, so callers only have to handle responses they expect
+    async fn send_receive(&mut self, request: UiRequestData) -> Result<UiResponseData, FrontendApiError> {
+        match self.frontend_sender.send_receive(request).await? {
+            UiResponseData::Error { message } => Err(FrontendApiError::FrontendError(message)),
+            response => Ok(response),
+        }
+    }
+
+    async fn send_receive_with_timeout(&self, request: UiRequestData, timeout: Duration) -> Result<UiResponseData, FrontendApiError> {
+        match self.frontend_sender.send_receive_with_timeout(request, timeout).await? {
+            UiResponseData::Error { message } => Err(FrontendApiError::FrontendError(message)),
+            response => Ok(response),
+        }
+    }
+
     pub async fn request_search_results_update(&mut self) -> Result<(), FrontendApiError> {
-        let _ = self.frontend_sender.send_receive(UiRequestData::RequestSearchResultUpdate).await;
+        let _ = self.send_receive(UiRequestData::RequestSearchResultUpdate).await;
 
         Ok(())
     }
@@ -55,9 +79,10 @@ impl FrontendApi {
             container,
         };
 
-        let UiResponseData::Nothing = self.frontend_sender.send_receive(request).await?;
-
-        Ok(())
+        match self.send_receive(request).await? {
+            UiResponseData::Nothing => Ok(()),
+            _ => Err(FrontendApiError::UnexpectedResponse),
+        }
     }
 
     pub async fn clear_inline_view(&mut self, plugin_id: PluginId) -> Result<(), FrontendApiError> {
@@ -65,15 +90,17 @@ impl FrontendApi {
             plugin_id,
         };
 
-        let UiResponseData::Nothing = self.frontend_sender.send_receive(request).await?;
-
-        Ok(())
+        match self.send_receive(request).await? {
+            UiResponseData::Nothing => Ok(()),
+            _ => Err(FrontendApiError::UnexpectedResponse),
+        }
     }
 
     pub async fn show_window(&self) -> Result<(), FrontendApiError> {
-        let UiResponseData::Nothing = self.frontend_sender.send_receive(UiRequestData::ShowWindow).await?;
-
-        Ok(())
+        match self.send_receive(UiRequestData::ShowWindow).await? {
+            UiResponseData::Nothing => Ok(()),
+            _ => Err(FrontendApiError::UnexpectedResponse),
+        }
     }
 
     pub async fn show_preference_required_view(
@@ -90,9 +117,10 @@ impl FrontendApi {
             entrypoint_preferences_required,
         };
 
-        let UiResponseData::Nothing = self.frontend_sender.send_receive(request).await?;
-
-        Ok(())
+        match self.send_receive(request).await? {
+            UiResponseData::Nothing => Ok(()),
+            _ => Err(FrontendApiError::UnexpectedResponse),
+        }
     }
 
     pub async fn show_plugin_error_view(
@@ -100,16 +128,19 @@ impl FrontendApi {
         plugin_id: PluginId,
         entrypoint_id: EntrypointId,
         render_location: UiRenderLocation,
+        message: Option<String>,
     ) -> Result<(), FrontendApiError> {
         let request = UiRequestData::ShowPluginErrorView {
             plugin_id,
             entrypoint_id,
             render_location,
+            message,
         };
 
-        let UiResponseData::Nothing = self.frontend_sender.send_receive(request).await?;
-
-        Ok(())
+        match self.send_receive(request).await? {
+            UiResponseData::Nothing => Ok(()),
+            _ => Err(FrontendApiError::UnexpectedResponse),
+        }
     }
 
     pub async fn show_hud(
@@ -120,8 +151,92 @@ impl FrontendApi {
             display,
         };
 
-        let UiResponseData::Nothing = self.frontend_sender.send_receive(request).await?;
+        match self.send_receive(request).await? {
+            UiResponseData::Nothing => Ok(()),
+            _ => Err(FrontendApiError::UnexpectedResponse),
+        }
+    }
 
-        Ok(())
+    pub async fn show_inline_answer(
+        &mut self,
+        plugin_id: PluginId,
+        value: String,
+        copy_text: Option<String>,
+    ) -> Result<(), FrontendApiError> {
+        let request = UiRequestData::ShowInlineAnswer {
+            plugin_id,
+            value,
+            copy_text,
+        };
+
+        match self.send_receive(request).await? {
+            UiResponseData::Nothing => Ok(()),
+            _ => Err(FrontendApiError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn request_permission(
+        &mut self,
+        plugin_id: PluginId,
+        plugin_name: String,
+        kind: String,
+        detail: String,
+    ) -> Result<bool, FrontendApiError> {
+        let request = UiRequestData::RequestPermission {
+            plugin_id,
+            plugin_name,
+            kind,
+            detail,
+        };
+
+        match self.send_receive_with_timeout(request, PERMISSION_PROMPT_TIMEOUT).await? {
+            UiResponseData::PermissionDecision { granted } => Ok(granted),
+            _ => Err(FrontendApiError::UnexpectedResponse),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn inline_answer_is_sent_as_typed_request() {
+        let (sender, mut receiver) = utils::channel::channel::<UiRequestData, UiResponseData>();
+        let mut frontend_api = FrontendApi::new(sender);
+
+        let frontend = tokio::spawn(async move {
+            let (request, responder) = receiver.recv().await;
+            responder.respond(UiResponseData::Nothing);
+            request
+        });
+
+        frontend_api.show_inline_answer(PluginId::from_string("calculator"), "4".to_string(), Some("4".to_string()))
+            .await
+            .unwrap();
+
+        match frontend.await.unwrap() {
+            UiRequestData::ShowInlineAnswer { plugin_id, value, copy_text } => {
+                assert_eq!(plugin_id, PluginId::from_string("calculator"));
+                assert_eq!(value, "4");
+                assert_eq!(copy_text, Some("4".to_string()));
+            }
+            request @ _ => panic!("unexpected request {:?}", request),
+        }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn error_response_is_returned_as_error() {
+        let (sender, mut receiver) = utils::channel::channel::<UiRequestData, UiResponseData>();
+        let mut frontend_api = FrontendApi::new(sender);
+
+        tokio::spawn(async move {
+            let (_, responder) = receiver.recv().await;
+            responder.respond(UiResponseData::Error { message: "request is not supported".to_string() });
+        });
+
+        let result = frontend_api.show_hud("display".to_string()).await;
+
+        assert!(matches!(result, Err(FrontendApiError::FrontendError(message)) if message == "request is not supported"));
+    }
+}
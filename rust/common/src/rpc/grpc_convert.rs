@@ -1,6 +1,8 @@
-use crate::model::{PluginPreference, PluginPreferenceUserData, PreferenceEnumValue};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::model::{EntrypointId, PhysicalKey, PhysicalShortcut, PluginId, PluginLogLevel, PluginLogLine, PluginPreference, PluginPreferenceUserData, PreferenceEnumValue, SearchResult, SearchResultEntrypointAction, SearchResultEntrypointType, SearchResultKind};
 use crate::rpc::grpc::rpc_ui_property_value::Value;
-use crate::rpc::grpc::{RpcEnumValue, RpcPluginPreference, RpcPluginPreferenceUserData, RpcPluginPreferenceValueType, RpcUiPropertyValue};
+use crate::rpc::grpc::{RpcEntrypointTypeSearchResult, RpcEnumValue, RpcPhysicalShortcut, RpcPluginLogLevel, RpcPluginLogLine, RpcPluginPreference, RpcPluginPreferenceUserData, RpcPluginPreferenceValueType, RpcSearchResult, RpcSearchResultAction, RpcSearchResultKind, RpcUiPropertyValue};
 
 pub fn plugin_preference_user_data_from_rpc(value: RpcPluginPreferenceUserData) -> PluginPreferenceUserData {
     let value_type: RpcPluginPreferenceValueType = value.r#type.try_into().unwrap();
@@ -404,3 +406,141 @@ pub fn plugin_preference_from_rpc(value: RpcPluginPreference) -> PluginPreferenc
     }
 }
 
+pub fn search_result_to_rpc(value: SearchResult) -> RpcSearchResult {
+    let entrypoint_type = match value.entrypoint_type {
+        SearchResultEntrypointType::Command => RpcEntrypointTypeSearchResult::SrCommand,
+        SearchResultEntrypointType::View => RpcEntrypointTypeSearchResult::SrView,
+        SearchResultEntrypointType::GeneratedCommand => RpcEntrypointTypeSearchResult::SrGeneratedCommand,
+    };
+
+    let result_kind = match value.result_kind {
+        SearchResultKind::Command => RpcSearchResultKind::SrKindCommand,
+        SearchResultKind::View => RpcSearchResultKind::SrKindView,
+    };
+
+    let entrypoint_actions = value.entrypoint_actions
+        .into_iter()
+        .map(|action| RpcSearchResultAction {
+            label: action.label,
+            shortcut: action.shortcut.map(|shortcut| physical_shortcut_to_rpc(shortcut)),
+        })
+        .collect();
+
+    RpcSearchResult {
+        plugin_id: value.plugin_id.to_string(),
+        plugin_name: value.plugin_name,
+        entrypoint_id: value.entrypoint_id.to_string(),
+        entrypoint_name: value.entrypoint_name,
+        entrypoint_type: entrypoint_type.into(),
+        entrypoint_icon_path: value.entrypoint_icon.unwrap_or_default(),
+        entrypoint_actions,
+        subtitle: value.subtitle.unwrap_or_default(),
+        result_kind: result_kind.into(),
+    }
+}
+
+pub fn search_result_from_rpc(value: RpcSearchResult) -> anyhow::Result<SearchResult> {
+    let entrypoint_type: RpcEntrypointTypeSearchResult = value.entrypoint_type.try_into()?;
+
+    let entrypoint_type = match entrypoint_type {
+        RpcEntrypointTypeSearchResult::SrCommand => SearchResultEntrypointType::Command,
+        RpcEntrypointTypeSearchResult::SrView => SearchResultEntrypointType::View,
+        RpcEntrypointTypeSearchResult::SrGeneratedCommand => SearchResultEntrypointType::GeneratedCommand,
+    };
+
+    let entrypoint_icon = match value.entrypoint_icon_path.is_empty() {
+        true => None,
+        false => Some(value.entrypoint_icon_path),
+    };
+
+    let result_kind: RpcSearchResultKind = value.result_kind.try_into()?;
+
+    let result_kind = match result_kind {
+        RpcSearchResultKind::SrKindCommand => SearchResultKind::Command,
+        RpcSearchResultKind::SrKindView => SearchResultKind::View,
+    };
+
+    let subtitle = match value.subtitle.is_empty() {
+        true => None,
+        false => Some(value.subtitle),
+    };
+
+    let entrypoint_actions = value.entrypoint_actions
+        .into_iter()
+        .map(|action| SearchResultEntrypointAction {
+            label: action.label,
+            shortcut: action.shortcut.map(|shortcut| physical_shortcut_from_rpc(shortcut)),
+        })
+        .collect();
+
+    Ok(SearchResult {
+        plugin_id: PluginId::from_string(value.plugin_id),
+        plugin_name: value.plugin_name,
+        entrypoint_id: EntrypointId::from_string(value.entrypoint_id),
+        entrypoint_name: value.entrypoint_name,
+        entrypoint_icon,
+        entrypoint_type,
+        entrypoint_actions,
+        subtitle,
+        result_kind,
+    })
+}
+
+pub fn physical_shortcut_to_rpc(value: PhysicalShortcut) -> RpcPhysicalShortcut {
+    RpcPhysicalShortcut {
+        physical_key: value.physical_key.to_value(),
+        modifier_shift: value.modifier_shift,
+        modifier_control: value.modifier_control,
+        modifier_alt: value.modifier_alt,
+        modifier_meta: value.modifier_meta,
+    }
+}
+
+pub fn physical_shortcut_from_rpc(value: RpcPhysicalShortcut) -> PhysicalShortcut {
+    PhysicalShortcut {
+        physical_key: PhysicalKey::from_value(value.physical_key),
+        modifier_shift: value.modifier_shift,
+        modifier_control: value.modifier_control,
+        modifier_alt: value.modifier_alt,
+        modifier_meta: value.modifier_meta,
+    }
+}
+
+pub fn plugin_log_line_to_rpc(value: PluginLogLine) -> RpcPluginLogLine {
+    let level = match value.level {
+        PluginLogLevel::Trace => RpcPluginLogLevel::LogTrace,
+        PluginLogLevel::Debug => RpcPluginLogLevel::LogDebug,
+        PluginLogLevel::Info => RpcPluginLogLevel::LogInfo,
+        PluginLogLevel::Warn => RpcPluginLogLevel::LogWarn,
+        PluginLogLevel::Error => RpcPluginLogLevel::LogError,
+    };
+
+    let timestamp_ms = value.timestamp
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+
+    RpcPluginLogLine {
+        timestamp_ms,
+        level: level.into(),
+        message: value.message,
+    }
+}
+
+pub fn plugin_log_line_from_rpc(value: RpcPluginLogLine) -> anyhow::Result<PluginLogLine> {
+    let level: RpcPluginLogLevel = value.level.try_into()?;
+
+    let level = match level {
+        RpcPluginLogLevel::LogTrace => PluginLogLevel::Trace,
+        RpcPluginLogLevel::LogDebug => PluginLogLevel::Debug,
+        RpcPluginLogLevel::LogInfo => PluginLogLevel::Info,
+        RpcPluginLogLevel::LogWarn => PluginLogLevel::Warn,
+        RpcPluginLogLevel::LogError => PluginLogLevel::Error,
+    };
+
+    Ok(PluginLogLine {
+        timestamp: UNIX_EPOCH + Duration::from_millis(value.timestamp_ms),
+        level,
+        message: value.message,
+    })
+}
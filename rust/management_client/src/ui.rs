@@ -9,7 +9,7 @@ use iced_aw::core::icons;
 use iced_aw::floating_element::{Anchor, Offset};
 use itertools::Itertools;
 
-use common::model::{DownloadStatus, PhysicalShortcut, PluginId};
+use common::model::{DownloadStatus, PhysicalShortcut, PluginId, PluginUpdate, UpdateResult};
 use common::rpc::backend_api::{BackendApi, BackendApiError};
 
 use crate::theme::{Element, GauntletSettingsTheme};
@@ -35,6 +35,7 @@ struct ManagementAppModel {
     error_view: Option<ErrorView>,
     downloads_info: HashMap<PluginId, DownloadInfo>,
     download_info_shown: bool,
+    updates_available: Vec<PluginUpdate>,
     current_settings_view: SettingsView,
     general_state: ManagementAppGeneralState,
     plugins_state: ManagementAppPluginsState
@@ -48,6 +49,9 @@ enum ManagementAppMsg {
     Plugin(ManagementAppPluginMsgIn),
     SwitchView(SettingsView),
     DownloadStatus { plugins: HashMap<PluginId, DownloadStatus> },
+    UpdatesAvailable { updates: Vec<PluginUpdate> },
+    UpdateAllPlugins,
+    UpdateAllPluginsFinished { results: Vec<(PluginId, UpdateResult)> },
     HandleBackendError(BackendApiError),
     CheckDownloadStatus,
     DownloadPlugin { plugin_id: PluginId },
@@ -98,6 +102,7 @@ impl Application for ManagementAppModel {
                 error_view: None,
                 downloads_info: HashMap::new(),
                 download_info_shown: false,
+                updates_available: vec![],
                 current_settings_view: SettingsView::Plugins,
                 general_state: ManagementAppGeneralState::new(backend_api.clone()),
                 plugins_state: ManagementAppPluginsState::new(backend_api.clone()),
@@ -108,6 +113,43 @@ impl Application for ManagementAppModel {
                     async {},
                     |plugins| ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::RequestPluginReload)
                 ),
+                Command::perform(
+                    {
+                        let backend_api = backend_api.clone();
+
+                        // downloads interrupted by restart are reported by server as failed
+                        async move {
+                            match backend_api {
+                                Some(mut backend_api) => Some(backend_api.download_status().await),
+                                None => None
+                            }
+                        }
+                    },
+                    |plugins| {
+                        match plugins {
+                            None => ManagementAppMsg::Noop,
+                            Some(plugins) => handle_backend_error(plugins, |plugins| ManagementAppMsg::DownloadStatus { plugins })
+                        }
+                    }
+                ),
+                Command::perform(
+                    {
+                        let backend_api = backend_api.clone();
+
+                        async move {
+                            match backend_api {
+                                Some(mut backend_api) => Some(backend_api.updates_available().await),
+                                None => None
+                            }
+                        }
+                    },
+                    |updates| {
+                        match updates {
+                            None => ManagementAppMsg::Noop,
+                            Some(updates) => handle_backend_error(updates, |updates| ManagementAppMsg::UpdatesAvailable { updates })
+                        }
+                    }
+                ),
                 Command::perform(
                     async {
                         match backend_api {
@@ -204,7 +246,7 @@ impl Application for ManagementAppModel {
             ManagementAppMsg::DownloadStatus { plugins } => {
                 for (plugin, status) in plugins {
                     match status {
-                        DownloadStatus::InProgress => {
+                        DownloadStatus::Pending | DownloadStatus::InProgress => {
                             self.downloads_info.insert(plugin.clone(), DownloadInfo::InProgress);
                         }
                         DownloadStatus::Done => {
@@ -265,6 +307,43 @@ impl Application for ManagementAppModel {
                     )
                 }
             }
+            ManagementAppMsg::UpdatesAvailable { updates } => {
+                self.updates_available = updates;
+
+                Command::none()
+            }
+            ManagementAppMsg::UpdateAllPlugins => {
+                let mut backend_client = backend_api.clone();
+
+                for update in self.updates_available.drain(..) {
+                    self.downloads_info.insert(update.plugin_id, DownloadInfo::InProgress);
+                }
+
+                Command::perform(
+                    async move {
+                        let results = backend_client.update_all_plugins()
+                            .await?;
+
+                        Ok(results)
+                    },
+                    |result| handle_backend_error(result, |results| ManagementAppMsg::UpdateAllPluginsFinished { results })
+                )
+            }
+            ManagementAppMsg::UpdateAllPluginsFinished { results } => {
+                for (plugin_id, result) in results {
+                    let info = match result {
+                        UpdateResult::Updated => DownloadInfo::Successful,
+                        UpdateResult::Failed { message } => DownloadInfo::Error { message },
+                    };
+
+                    self.downloads_info.insert(plugin_id, info);
+                }
+
+                Command::perform(
+                    async {},
+                    |()| ManagementAppMsg::Plugin(ManagementAppPluginMsgIn::RequestPluginReload)
+                )
+            }
             ManagementAppMsg::Noop => Command::none(),
             ManagementAppMsg::ToggleDownloadInfo => {
                 self.download_info_shown = !self.download_info_shown;
@@ -545,6 +624,29 @@ impl Application for ManagementAppModel {
 
                 download_info_icons.push(icon);
             }
+            if !self.updates_available.is_empty() {
+                let icon: Element<_> = text(icons::Bootstrap::ArrowUpCircleFill)
+                    .font(icons::BOOTSTRAP_FONT)
+                    .height(Length::Fill)
+                    .vertical_alignment(alignment::Vertical::Center)
+                    .size(16)
+                    .into();
+
+                let icon: Element<_> = container(icon)
+                    .height(Length::Fill)
+                    .into();
+
+                let text: Element<_> = text(self.updates_available.len())
+                    .height(Length::Fill)
+                    .vertical_alignment(alignment::Vertical::Center)
+                    .into();
+
+                let icon: Element<_> = row(vec![text, icon])
+                    .spacing(8.0)
+                    .into();
+
+                download_info_icons.push(icon);
+            }
 
             if download_info_icons.is_empty() {
                 horizontal_space()
@@ -719,9 +821,67 @@ impl Application for ManagementAppModel {
                         }
                     }
                 })
+                .chain(self.updates_available.iter().map(|update| {
+                    let kind_text: Element<_> = text(format!("Update available for {}", update.plugin_name))
+                        .into();
+
+                    let kind_text: Element<_> = container(kind_text)
+                        .padding(Padding::from([16, 0, 8, 0]))
+                        .into();
+
+                    let plugin_id: Element<_> = text(update.plugin_id.to_string())
+                        .size(14)
+                        .style(TextStyle::Subtitle)
+                        .into();
+
+                    let plugin_id: Element<_> = container(plugin_id)
+                        .padding(Padding::from([0, 0, 16, 0]))
+                        .into();
+
+                    let icon: Element<_> = text(icons::Bootstrap::ArrowUpCircleFill)
+                        .size(32)
+                        .vertical_alignment(alignment::Vertical::Center)
+                        .font(icons::BOOTSTRAP_FONT)
+                        .into();
+
+                    let icon: Element<_> = container(icon)
+                        .padding(16)
+                        .into();
+
+                    let content: Element<_> = column(vec![kind_text, plugin_id])
+                        .into();
+
+                    let content: Element<_> = row(vec![icon, content])
+                        .into();
+
+                    container(content)
+                        .width(Length::Fill)
+                        .into()
+                }))
                 .intersperse_with(|| horizontal_rule(1).into())
                 .collect();
 
+            let downloads = if self.updates_available.is_empty() {
+                downloads
+            } else {
+                let update_all_button_label: Element<_> = text("Update all")
+                    .into();
+
+                let update_all_button: Element<_> = button(update_all_button_label)
+                    .on_press(ManagementAppMsg::UpdateAllPlugins)
+                    .into();
+
+                let update_all_button: Element<_> = container(update_all_button)
+                    .width(Length::Fill)
+                    .align_x(alignment::Horizontal::Right)
+                    .padding(8)
+                    .into();
+
+                let mut downloads = downloads;
+                downloads.insert(0, update_all_button);
+                downloads
+            };
+
             let downloads: Element<_> = column(downloads)
                 .into();
 
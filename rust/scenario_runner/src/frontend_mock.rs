@@ -1,5 +1,6 @@
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 
 use common::model::{BackendRequestData, BackendResponseData, EntrypointId, PluginId, UiRequestData, UiResponseData};
 use common::rpc::backend_api::{BackendApi, BackendForFrontendApi};
@@ -10,6 +11,8 @@ use utils::channel::{RequestReceiver, RequestSender};
 
 use crate::model::ScenarioBackendEvent;
 
+const BACKEND_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
 pub async fn start_scenario_runner_frontend(
     request_receiver: RequestReceiver<UiRequestData, UiResponseData>,
     backend_sender: RequestSender<BackendRequestData, BackendResponseData>
@@ -52,7 +55,7 @@ pub async fn start_scenario_runner_frontend(
 
     println!("waiting for backend");
 
-    wait_for_backend_server().await;
+    wait_for_backend_server(BACKEND_STARTUP_TIMEOUT).await?;
 
     println!("backend started");
 
@@ -61,7 +64,7 @@ pub async fn start_scenario_runner_frontend(
 
     println!("saving local plugin");
 
-    backend_client.save_local_plugin(scenario_plugin_dir.clone()).await?;
+    backend_client.save_local_plugin(scenario_plugin_dir.clone(), false).await?;
 
     println!("local plugin saved");
 
@@ -152,12 +155,20 @@ async fn request_loop(mut request_receiver: RequestReceiver<UiRequestData, UiRes
         let (request_data, responder) = request_receiver.recv().await;
 
         match request_data {
-            UiRequestData::ShowWindow | UiRequestData::ClearInlineView { .. } => {
-                unreachable!()
+            UiRequestData::ShowWindow | UiRequestData::ClearInlineView { .. } | UiRequestData::ShowInlineAnswer { .. } | UiRequestData::ShowHud { .. } => {
+                responder.respond(UiResponseData::Error { message: "request is not supported when running scenarios".to_string() });
+
+                continue
             }
             UiRequestData::RequestSearchResultUpdate => {
                 // noop
             }
+            UiRequestData::RequestPermission { .. } => {
+                // scenarios are run without user, so all runtime permission requests are denied
+                responder.respond(UiResponseData::PermissionDecision { granted: false });
+
+                continue
+            }
             UiRequestData::ReplaceView { plugin_id: _, plugin_name: _, entrypoint_id, entrypoint_name: _, render_location, top_level_view, container } => {
                 let event = ScenarioFrontendEvent::ReplaceView {
                     entrypoint_id: entrypoint_id.to_string(),
@@ -170,7 +181,7 @@ async fn request_loop(mut request_receiver: RequestReceiver<UiRequestData, UiRes
                     .await
                     .expect("send failed")
             }
-            UiRequestData::ShowPluginErrorView { plugin_id: _, entrypoint_id, render_location } => {
+            UiRequestData::ShowPluginErrorView { plugin_id: _, entrypoint_id, render_location, message: _ } => {
                 let event = ScenarioFrontendEvent::ShowPluginErrorView {
                     entrypoint_id: entrypoint_id.to_string(),
                     render_location: ui_render_location_to_scenario(render_location)
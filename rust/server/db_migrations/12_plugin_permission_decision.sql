@@ -0,0 +1,9 @@
+CREATE TABLE plugin_permission_decision
+(
+    plugin_id TEXT    NOT NULL REFERENCES plugin (id) ON DELETE CASCADE,
+    kind      TEXT    NOT NULL,
+    detail    TEXT    NOT NULL,
+    granted   BOOLEAN NOT NULL,
+
+    PRIMARY KEY (plugin_id, kind, detail)
+);
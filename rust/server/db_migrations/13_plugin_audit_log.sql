@@ -0,0 +1,10 @@
+CREATE TABLE plugin_audit_log
+(
+    id        INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    plugin_id TEXT    NOT NULL,
+    timestamp INTEGER NOT NULL, -- milliseconds since unix epoch
+    action    TEXT    NOT NULL,
+    target    TEXT    NOT NULL
+);
+
+CREATE INDEX plugin_audit_log_plugin_id ON plugin_audit_log (plugin_id);
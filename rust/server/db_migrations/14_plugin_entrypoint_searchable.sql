@@ -0,0 +1 @@
+ALTER TABLE plugin_entrypoint ADD COLUMN searchable BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1 @@
+ALTER TABLE plugin ADD COLUMN version TEXT;
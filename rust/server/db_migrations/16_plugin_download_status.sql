@@ -0,0 +1,6 @@
+CREATE TABLE plugin_download_status
+(
+    plugin_id TEXT NOT NULL PRIMARY KEY,
+    status    TEXT NOT NULL,
+    message   TEXT NOT NULL
+);
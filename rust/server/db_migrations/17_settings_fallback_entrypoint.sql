@@ -0,0 +1 @@
+ALTER TABLE settings_data ADD COLUMN fallback_entrypoint JSON;
@@ -0,0 +1,8 @@
+CREATE TABLE plugin_entrypoint_view_state
+(
+    plugin_id     TEXT NOT NULL,
+    entrypoint_id TEXT NOT NULL,
+    state         JSON NOT NULL,
+
+    PRIMARY KEY (plugin_id, entrypoint_id)
+);
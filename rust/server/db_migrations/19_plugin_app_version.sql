@@ -0,0 +1,2 @@
+ALTER TABLE plugin ADD COLUMN min_app_version INTEGER;
+ALTER TABLE plugin ADD COLUMN max_app_version INTEGER;
@@ -0,0 +1 @@
+ALTER TABLE plugin ADD COLUMN bundle_hash TEXT;
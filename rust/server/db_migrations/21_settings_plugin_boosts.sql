@@ -0,0 +1 @@
+ALTER TABLE settings_data ADD COLUMN plugin_boosts JSON;
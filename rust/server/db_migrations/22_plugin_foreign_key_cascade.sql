@@ -0,0 +1,46 @@
+-- rows left from removed plugins before foreign keys were enforced
+DELETE FROM plugin_entrypoint WHERE plugin_id NOT IN (SELECT id FROM plugin);
+DELETE FROM plugin_asset_data WHERE plugin_id NOT IN (SELECT id FROM plugin);
+DELETE FROM plugin_permission_decision WHERE plugin_id NOT IN (SELECT id FROM plugin);
+DELETE FROM plugin_entrypoint_frecency_stats WHERE plugin_id NOT IN (SELECT id FROM plugin);
+DELETE FROM plugin_entrypoint_view_state WHERE plugin_id NOT IN (SELECT id FROM plugin);
+
+-- sqlite doesn't support adding foreign key to existing table, so tables are recreated
+-- references plugin and not plugin_entrypoint, because entrypoints are saved with INSERT OR REPLACE
+-- which would delete stats of existing entrypoints on every plugin save
+CREATE TABLE plugin_entrypoint_frecency_stats_new
+(
+    entrypoint_id  TEXT    NOT NULL,
+    plugin_id      TEXT    NOT NULL REFERENCES plugin (id) ON DELETE CASCADE,
+
+    reference_time REAL    NOT NULL,
+    half_life      REAL    NOT NULL,
+    last_accessed  REAL    NOT NULL,
+    frecency       REAL    NOT NULL,
+    num_accesses   INTEGER NOT NULL,
+
+    PRIMARY KEY (entrypoint_id, plugin_id)
+);
+
+INSERT INTO plugin_entrypoint_frecency_stats_new (entrypoint_id, plugin_id, reference_time, half_life, last_accessed, frecency, num_accesses)
+    SELECT entrypoint_id, plugin_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_frecency_stats;
+
+DROP TABLE plugin_entrypoint_frecency_stats;
+
+ALTER TABLE plugin_entrypoint_frecency_stats_new RENAME TO plugin_entrypoint_frecency_stats;
+
+CREATE TABLE plugin_entrypoint_view_state_new
+(
+    plugin_id     TEXT NOT NULL REFERENCES plugin (id) ON DELETE CASCADE,
+    entrypoint_id TEXT NOT NULL,
+    state         JSON NOT NULL,
+
+    PRIMARY KEY (plugin_id, entrypoint_id)
+);
+
+INSERT INTO plugin_entrypoint_view_state_new (plugin_id, entrypoint_id, state)
+    SELECT plugin_id, entrypoint_id, state FROM plugin_entrypoint_view_state;
+
+DROP TABLE plugin_entrypoint_view_state;
+
+ALTER TABLE plugin_entrypoint_view_state_new RENAME TO plugin_entrypoint_view_state;
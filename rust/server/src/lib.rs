@@ -1,15 +1,21 @@
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
+use anyhow::anyhow;
 use vergen_pretty::vergen_pretty_env;
 use client::{open_window, start_client};
+use common::dirs::Dirs;
 use common::model::{BackendRequestData, BackendResponseData, UiRequestData, UiResponseData};
 use common::rpc::backend_api::BackendApi;
 use common::rpc::backend_server::start_backend_server;
 use common::{settings_env_data_to_string, SettingsEnvData};
 use utils::channel::{channel, RequestReceiver, RequestSender};
 use crate::plugins::ApplicationManager;
+use crate::plugins::applications_watcher::ApplicationsWatcher;
+use crate::plugins::config_watcher::ConfigWatcher;
+use crate::plugins::loader::{PluginLoader, PluginManifestLintSeverity};
 use crate::rpc::BackendServerImpl;
-use crate::search::SearchIndex;
+use crate::search::{SearchIndex, SearchQueryError};
 
 pub mod rpc;
 pub(in crate) mod search;
@@ -26,6 +32,8 @@ pub fn start(minimized: bool) {
         }
     }
 
+    plugins::apply_timezone(&Dirs::new());
+
     #[cfg(feature = "scenario_runner")]
     run_scenario_runner();
 
@@ -36,16 +44,70 @@ pub fn start(minimized: bool) {
         } else {
             let (frontend_sender, frontend_receiver) = channel::<UiRequestData, UiResponseData>();
             let (backend_sender, backend_receiver) = channel::<BackendRequestData, BackendResponseData>();
+            let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
 
-            std::thread::spawn(|| {
-                start_server(frontend_sender, backend_receiver);
+            let server_thread = std::thread::spawn(|| {
+                start_server(frontend_sender, backend_receiver, shutdown_receiver);
             });
 
-            start_client(minimized, frontend_receiver, backend_sender)
+            // kept until server is shut down, so that request loop doesn't see closed channel after client exits
+            let _backend_sender = backend_sender.clone();
+
+            start_client(minimized, frontend_receiver, backend_sender);
+
+            let _ = shutdown_sender.send(());
+
+            if server_thread.join().is_err() {
+                tracing::error!("server thread panicked during shutdown");
+            }
         }
     }
 }
 
+pub fn scaffold_plugin(plugin_name: &str) -> anyhow::Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    let plugin_dir = plugins::scaffold::scaffold_plugin(&current_dir, plugin_name)?;
+
+    println!("Created plugin at {:?}", plugin_dir);
+    println!("Run 'npm install' and 'npm run dev' inside of it to start developing");
+
+    Ok(())
+}
+
+// returns error if manifest has issues with error severity
+pub fn lint_plugin(plugin_dir: &Path) -> anyhow::Result<()> {
+    let issues = PluginLoader::lint_manifest_in_dir(plugin_dir);
+
+    if issues.is_empty() {
+        println!("No issues found in plugin manifest at {:?}", plugin_dir);
+        return Ok(())
+    }
+
+    for issue in &issues {
+        let severity = match issue.severity {
+            PluginManifestLintSeverity::Error => "error",
+            PluginManifestLintSeverity::Warning => "warning",
+        };
+
+        println!("{}: {}", severity, issue.message);
+    }
+
+    let error_count = issues.iter()
+        .filter(|issue| issue.severity == PluginManifestLintSeverity::Error)
+        .count();
+
+    let warning_count = issues.len() - error_count;
+
+    println!("{} error(s), {} warning(s)", error_count, warning_count);
+
+    if error_count > 0 {
+        return Err(anyhow!("Plugin manifest at {:?} has errors", plugin_dir))
+    }
+
+    Ok(())
+}
+
 #[cfg(feature = "scenario_runner")]
 fn run_scenario_runner() {
     let runner_type = std::env::var("GAUNTLET_SCENARIO_RUNNER_TYPE")
@@ -64,9 +126,10 @@ fn run_scenario_runner() {
         "scenario_runner" => {
             let (frontend_sender, frontend_receiver) = channel::<UiRequestData, UiResponseData>();
             let (backend_sender, backend_receiver) = channel::<BackendRequestData, BackendResponseData>();
+            let (_shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
 
             std::thread::spawn(|| {
-                start_server(frontend_sender, backend_receiver)
+                start_server(frontend_sender, backend_receiver, shutdown_receiver)
             });
 
             start_frontend_mock(frontend_receiver, backend_sender)
@@ -94,15 +157,24 @@ fn is_server_running() -> bool {
         })
 }
 
-fn start_server(request_sender: RequestSender<UiRequestData, UiResponseData>, backend_receiver: RequestReceiver<BackendRequestData, BackendResponseData>) {
-    tokio::runtime::Builder::new_multi_thread()
+fn start_server(
+    request_sender: RequestSender<UiRequestData, UiResponseData>,
+    backend_receiver: RequestReceiver<BackendRequestData, BackendResponseData>,
+    shutdown_receiver: tokio::sync::oneshot::Receiver<()>,
+) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
-        .expect("unable to start server tokio runtime")
+        .expect("unable to start server tokio runtime");
+
+    runtime
         .block_on(async {
-            run_server(request_sender, backend_receiver).await
+            run_server(request_sender, backend_receiver, shutdown_receiver).await
         })
         .unwrap();
+
+    // blocking tasks, e.g. file watchers, are not waited for, process is exiting anyway
+    runtime.shutdown_background();
 }
 
 #[cfg(feature = "scenario_runner")]
@@ -120,13 +192,21 @@ fn start_frontend_mock(
         .unwrap();
 }
 
-async fn run_server(frontend_sender: RequestSender<UiRequestData, UiResponseData>, mut backend_receiver: RequestReceiver<BackendRequestData, BackendResponseData>) -> anyhow::Result<()> {
+async fn run_server(
+    frontend_sender: RequestSender<UiRequestData, UiResponseData>,
+    mut backend_receiver: RequestReceiver<BackendRequestData, BackendResponseData>,
+    shutdown_receiver: tokio::sync::oneshot::Receiver<()>,
+) -> anyhow::Result<()> {
     let application_manager = ApplicationManager::create(frontend_sender).await?;
 
     let mut application_manager = Arc::new(application_manager);
 
     application_manager.clear_all_icon_cache_dir()?;
 
+    if let Err(err) = application_manager.prune_pending_plugins().await {
+        tracing::error!("error pruning pending plugins: {:?}", err);
+    }
+
     #[cfg(not(feature = "scenario_runner"))]
     if let Err(err) = application_manager.load_bundled_plugins().await {
         tracing::error!("error loading bundled plugin(s): {:?}", err);
@@ -138,13 +218,30 @@ async fn run_server(frontend_sender: RequestSender<UiRequestData, UiResponseData
         let plugin_path = std::fs::canonicalize(plugin_path).expect("valid path");
         let plugin_path = plugin_path.to_str().expect("valid utf8");
 
-        if let Err(err) = application_manager.save_local_plugin(plugin_path).await {
+        if let Err(err) = application_manager.save_local_plugin(plugin_path, false).await {
             tracing::error!("error loading dev plugin: {:?}", err);
         }
     }
 
     application_manager.reload_all_plugins().await?; // TODO do not fail here ?
 
+    // kept alive for the lifetime of the server, dropping it stops the watch
+    let _config_watcher = match ConfigWatcher::start(application_manager.clone()) {
+        Ok(config_watcher) => Some(config_watcher),
+        Err(err) => {
+            tracing::error!("unable to watch config file: {:?}", err);
+            None
+        }
+    };
+
+    let _applications_watcher = match ApplicationsWatcher::start(application_manager.clone()) {
+        Ok(applications_watcher) => Some(applications_watcher),
+        Err(err) => {
+            tracing::error!("unable to watch application directories: {:?}", err);
+            None
+        }
+    };
+
     tokio::spawn({
         let application_manager = application_manager.clone();
 
@@ -153,8 +250,25 @@ async fn run_server(frontend_sender: RequestSender<UiRequestData, UiResponseData
         }
     });
 
+    // sender can be dropped without sending, e.g. in scenario runner, server then runs until process exits
+    let shutdown = async move {
+        if shutdown_receiver.await.is_err() {
+            std::future::pending::<()>().await
+        }
+    };
+
+    tokio::pin!(shutdown);
+
     loop {
-        let (request_data, responder) = backend_receiver.recv().await;
+        let (request_data, responder) = tokio::select! {
+            biased;
+            () = &mut shutdown => {
+                application_manager.shutdown().await;
+
+                return Ok(())
+            }
+            payload = backend_receiver.recv() => payload,
+        };
 
         let response_data = handle_request(application_manager.clone(), request_data)
             .await
@@ -167,10 +281,20 @@ async fn run_server(frontend_sender: RequestSender<UiRequestData, UiResponseData
 async fn handle_request(application_manager: Arc<ApplicationManager>, request_data: BackendRequestData) -> anyhow::Result<BackendResponseData> {
     let response_data = match request_data {
         BackendRequestData::Search { text, render_inline_view } => {
-            let results = application_manager.search(&text, render_inline_view)?;
-
-            BackendResponseData::Search {
-                results,
+            match application_manager.search(&text, render_inline_view) {
+                Ok((results, suggestion)) => {
+                    BackendResponseData::Search {
+                        results,
+                        suggestion,
+                    }
+                }
+                // invalid operator is a user error, it is shown in place of results
+                Err(err) if err.is::<SearchQueryError>() => {
+                    BackendResponseData::Error {
+                        message: format!("{}", err),
+                    }
+                }
+                Err(err) => return Err(err),
             }
         }
         BackendRequestData::RequestViewRender { plugin_id, entrypoint_id } => {
@@ -186,16 +310,63 @@ async fn handle_request(application_manager: Arc<ApplicationManager>, request_da
 
             BackendResponseData::Nothing
         }
-        BackendRequestData::RequestRunCommand { plugin_id, entrypoint_id } => {
-            application_manager.handle_run_command(plugin_id, entrypoint_id)
+        BackendRequestData::RequestRunCommand { plugin_id, entrypoint_id, modifier_shift, modifier_control, modifier_alt, modifier_meta } => {
+            let result = application_manager.handle_run_command(plugin_id, entrypoint_id, modifier_shift, modifier_control, modifier_alt, modifier_meta)
                 .await;
 
-            BackendResponseData::Nothing
+            match result {
+                Ok(()) => BackendResponseData::Nothing,
+                Err(err) => {
+                    tracing::warn!(target = "rpc", "error occurred when handling 'run_command' request {:?}", err);
+
+                    BackendResponseData::Error {
+                        message: format!("{:#}", err)
+                    }
+                }
+            }
         }
         BackendRequestData::RequestRunGeneratedCommand { plugin_id, entrypoint_id, action_index } => {
-            application_manager.handle_run_generated_command(plugin_id, entrypoint_id, action_index)
+            let result = application_manager.handle_run_generated_command(plugin_id, entrypoint_id, action_index)
+                .await;
+
+            match result {
+                Ok(()) => BackendResponseData::Nothing,
+                Err(err) => {
+                    tracing::warn!(target = "rpc", "error occurred when handling 'run_generated_command' request {:?}", err);
+
+                    BackendResponseData::Error {
+                        message: format!("{:#}", err)
+                    }
+                }
+            }
+        }
+        BackendRequestData::RequestPluginReload { plugin_id } => {
+            let result = application_manager.reload_plugin(plugin_id)
                 .await;
 
+            if let Err(err) = &result {
+                tracing::warn!(target = "rpc", "error occurred when handling 'plugin_reload' request {:?}", err)
+            }
+
+            BackendResponseData::Nothing
+        }
+        BackendRequestData::RequestPluginForceStop { plugin_id } => {
+            let result = application_manager.force_stop_plugin(plugin_id);
+
+            if let Err(err) = &result {
+                tracing::warn!(target = "rpc", "error occurred when handling 'plugin_force_stop' request {:?}", err)
+            }
+
+            BackendResponseData::Nothing
+        }
+        BackendRequestData::RequestRunFallback { text } => {
+            let result = application_manager.run_fallback(&text)
+                .await;
+
+            if let Err(err) = &result {
+                tracing::warn!(target = "rpc", "error occurred when handling 'run_fallback' request {:?}", err)
+            }
+
             BackendResponseData::Nothing
         }
         BackendRequestData::SendViewEvent { plugin_id, widget_id, event_name, event_arguments } => {
@@ -204,7 +375,7 @@ async fn handle_request(application_manager: Arc<ApplicationManager>, request_da
             BackendResponseData::Nothing
         }
         BackendRequestData::SendKeyboardEvent { plugin_id, entrypoint_id, origin, key, modifier_shift, modifier_control, modifier_alt, modifier_meta } => {
-            application_manager.handle_keyboard_event(
+            let result = application_manager.handle_keyboard_event(
                 plugin_id,
                 entrypoint_id,
                 origin,
@@ -213,9 +384,20 @@ async fn handle_request(application_manager: Arc<ApplicationManager>, request_da
                 modifier_control,
                 modifier_alt,
                 modifier_meta,
-            );
+            ).await;
+
+            // not captured on error, so that client falls back to default navigation
+            let captured = match result {
+                Ok(captured) => captured,
+                Err(err) => {
+                    tracing::warn!(target = "rpc", "error occurred when handling 'keyboard_event' request {:?}", err);
+                    false
+                }
+            };
 
-            BackendResponseData::Nothing
+            BackendResponseData::SendKeyboardEvent {
+                captured
+            }
         }
         BackendRequestData::SendOpenEvent { plugin_id: _, href } => {
             application_manager.handle_open(href);
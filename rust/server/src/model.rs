@@ -23,6 +23,7 @@ pub enum JsUiRequestData {
     ShowPluginErrorView {
         entrypoint_id: EntrypointId,
         render_location: JsUiRenderLocation,
+        message: Option<String>,
     },
     ShowPreferenceRequiredView {
         entrypoint_id: EntrypointId,
@@ -32,6 +33,10 @@ pub enum JsUiRequestData {
     ShowHud {
         display: String
     },
+    ShowInlineAnswer {
+        value: String,
+        copy_text: Option<String>,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
@@ -50,7 +55,14 @@ pub enum JsUiEvent {
     CloseView,
     RunCommand {
         #[serde(rename = "entrypointId")]
-        entrypoint_id: String
+        entrypoint_id: String,
+        #[serde(rename = "actionId")]
+        action_id: Option<String>
+    },
+    RunFallbackCommand {
+        #[serde(rename = "entrypointId")]
+        entrypoint_id: String,
+        text: String,
     },
     RunGeneratedCommand {
         #[serde(rename = "entrypointId")]
@@ -129,7 +141,12 @@ pub enum IntermediateUiEvent {
     },
     CloseView,
     RunCommand {
-        entrypoint_id: String
+        entrypoint_id: String,
+        action_id: Option<String>
+    },
+    RunFallbackCommand {
+        entrypoint_id: String,
+        text: String,
     },
     RunGeneratedCommand {
         entrypoint_id: String,
@@ -154,6 +171,7 @@ pub enum IntermediateUiEvent {
     },
     ReloadSearchIndex,
     RefreshSearchIndex,
+    Ping,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -57,9 +57,17 @@ fn find_application_dirs() -> Option<Vec<PathBuf>> {
     Some(res)
 }
 
-pub fn get_apps() -> Vec<DesktopEntry> {
-    let app_dirs = find_application_dirs()
+pub fn application_dirs() -> Vec<PathBuf> {
+    find_application_dirs()
         .unwrap_or_default()
+}
+
+pub fn get_apps() -> Vec<DesktopEntry> {
+    get_apps_in_dirs(application_dirs())
+}
+
+fn get_apps_in_dirs(app_dirs: Vec<PathBuf>) -> Vec<DesktopEntry> {
+    let app_dirs = app_dirs
         .into_iter()
         .filter(|dir| dir.exists())
         .collect::<Vec<_>>();
@@ -197,3 +205,28 @@ fn create_app_entry(path: PathBuf) -> Option<DesktopEntry> {
         command,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newly_added_application_is_listed_after_rescan() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let app_dirs = vec![temp_dir.path().to_path_buf()];
+
+        std::fs::write(temp_dir.path().join("editor.desktop"), "[Desktop Entry]\nName=Editor\n").unwrap();
+
+        let names = |apps: Vec<DesktopEntry>| {
+            let mut names = apps.into_iter().map(|app| app.name).collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        assert_eq!(names(get_apps_in_dirs(app_dirs.clone())), vec!["Editor"]);
+
+        std::fs::write(temp_dir.path().join("browser.desktop"), "[Desktop Entry]\nName=Browser\n").unwrap();
+
+        assert_eq!(names(get_apps_in_dirs(app_dirs)), vec!["Browser", "Editor"]);
+    }
+}
@@ -29,6 +29,16 @@ pub fn get_apps() -> Vec<DesktopEntry> {
         .collect()
 }
 
+pub fn application_dirs() -> Vec<PathBuf> {
+    let file_manager = FileManager::default();
+
+    [SearchPathDomainMask::User, SearchPathDomainMask::Local, SearchPathDomainMask::Domain]
+        .into_iter()
+        .filter_map(|mask| file_manager.get_directory(SearchPathDirectory::Applications, mask).ok())
+        .flat_map(|url| url.to_file_path())
+        .collect()
+}
+
 fn get_applications(file_manager: &FileManager) -> Vec<DesktopEntry> {
 
     let finder_application = vec![PathBuf::from("/System/Library/CoreServices/Finder.app")];
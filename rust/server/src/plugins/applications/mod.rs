@@ -4,17 +4,17 @@ use image::imageops::FilterType;
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "linux")]
-pub use linux::get_apps;
+pub use linux::{get_apps, application_dirs};
 
 #[cfg(target_os = "macos")]
 mod macos;
 #[cfg(target_os = "macos")]
-pub use macos::get_apps;
+pub use macos::{get_apps, application_dirs};
 
 #[cfg(all(not(target_os = "linux"), not(target_os = "macos")))]
 mod other;
 #[cfg(all(not(target_os = "linux"), not(target_os = "macos")))]
-pub use other::get_apps;
+pub use other::{get_apps, application_dirs};
 
 use serde::Serialize;
 
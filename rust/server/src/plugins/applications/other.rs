@@ -1,5 +1,11 @@
+use std::path::PathBuf;
+
 use crate::plugins::applications::DesktopEntry;
 
 pub fn get_apps() -> Vec<DesktopEntry> {
     vec![]
-}
\ No newline at end of file
+}
+
+pub fn application_dirs() -> Vec<PathBuf> {
+    vec![]
+}
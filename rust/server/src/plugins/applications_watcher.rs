@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio_util::sync::CancellationToken;
+
+use crate::plugins::applications::application_dirs;
+use crate::plugins::ApplicationManager;
+
+// installers usually create a lot of files in quick succession
+const DEBOUNCE_DURATION: Duration = Duration::from_secs(2);
+
+pub struct ApplicationsWatcher {
+    _watcher: RecommendedWatcher,
+    cancellation_token: CancellationToken,
+}
+
+impl ApplicationsWatcher {
+    pub fn start(application_manager: Arc<ApplicationManager>) -> anyhow::Result<Self> {
+        Self::watch(application_dirs(), move || application_manager.refresh_applications())
+    }
+
+    fn watch<F>(dirs: Vec<PathBuf>, on_change: F) -> anyhow::Result<Self>
+    where
+        F: Fn() + Send + 'static,
+    {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<()>();
+
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            match event {
+                Ok(event) => {
+                    if matches!(event.kind, EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_)) {
+                        let _ = sender.send(());
+                    }
+                }
+                Err(err) => tracing::warn!("error occurred when watching application directories {:?}", err)
+            }
+        })?;
+
+        // directories which don't exist on this system are skipped
+        for dir in dirs {
+            if dir.is_dir() {
+                if let Err(err) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                    tracing::warn!("unable to watch application directory {:?}: {:?}", dir, err)
+                }
+            }
+        }
+
+        let cancellation_token = CancellationToken::new();
+
+        tokio::spawn({
+            let cancellation_token = cancellation_token.clone();
+
+            async move {
+                loop {
+                    tokio::select! {
+                        _ = cancellation_token.cancelled() => break,
+                        event = receiver.recv() => {
+                            if event.is_none() {
+                                break
+                            }
+
+                            tokio::time::sleep(DEBOUNCE_DURATION).await;
+
+                            while let Ok(()) = receiver.try_recv() { }
+
+                            tracing::info!("Installed applications changed, refreshing");
+
+                            on_change();
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            _watcher: watcher,
+            cancellation_token,
+        })
+    }
+}
+
+impl Drop for ApplicationsWatcher {
+    fn drop(&mut self) {
+        self.cancellation_token.cancel()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn added_application_triggers_refresh() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let applications_dir = temp_dir.path().canonicalize().unwrap();
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<()>();
+
+        let _applications_watcher = ApplicationsWatcher::watch(vec![applications_dir.clone(), applications_dir.join("missing")], move || {
+            let _ = sender.send(());
+        }).unwrap();
+
+        std::fs::write(applications_dir.join("editor.desktop"), "[Desktop Entry]\nName=Editor\n").unwrap();
+        std::fs::write(applications_dir.join("browser.desktop"), "[Desktop Entry]\nName=Browser\n").unwrap();
+
+        tokio::time::timeout(DEBOUNCE_DURATION * 4, receiver.recv())
+            .await
+            .expect("applications weren't refreshed in time")
+            .unwrap();
+
+        // both applications were installed inside of debounce window, so they result in a single refresh
+        tokio::time::sleep(DEBOUNCE_DURATION * 2).await;
+
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use std::time::SystemTime;
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use common::model::PluginId;
+
+use crate::plugins::data_db_repository::DataDbRepository;
+
+// only actions which go through gauntlet ops are recorded,
+// deno builtins like global fetch(), Deno.writeFile() or Deno.Command are checked by deno permissions but not recorded
+struct AuditEntry {
+    plugin_id: String,
+    timestamp: i64,
+    action: String,
+    target: String,
+}
+
+// entries are written by task on server runtime, so they are not lost when plugin runtime is stopped
+#[derive(Clone)]
+pub struct PluginAuditLog {
+    sender: UnboundedSender<AuditEntry>,
+}
+
+impl PluginAuditLog {
+    pub fn new(db_repository: DataDbRepository) -> Self {
+        let (sender, receiver) = unbounded_channel();
+
+        tokio::spawn(write_audit_entries(db_repository, receiver));
+
+        Self {
+            sender,
+        }
+    }
+
+    // audit log is append-only and written in background to not slow down ops
+    pub fn record(&self, plugin_id: &PluginId, action: &str, target: &str) {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0);
+
+        let entry = AuditEntry {
+            plugin_id: plugin_id.to_string(),
+            timestamp,
+            action: action.to_string(),
+            target: target.to_string(),
+        };
+
+        if self.sender.send(entry).is_err() {
+            tracing::warn!(target = "plugin", "Unable to save audit log entry, writer is stopped");
+        }
+    }
+}
+
+async fn write_audit_entries(db_repository: DataDbRepository, mut receiver: UnboundedReceiver<AuditEntry>) {
+    while let Some(entry) = receiver.recv().await {
+        if let Err(err) = db_repository.save_audit_entry(&entry.plugin_id, entry.timestamp, &entry.action, &entry.target).await {
+            tracing::warn!(target = "plugin", "Unable to save audit log entry: {:?}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn entries_are_saved_after_plugin_runtime_is_stopped() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let audit_log = PluginAuditLog::new(repository.clone());
+
+        // plugin runtime runs on its own thread with its own tokio runtime
+        let plugin_audit_log = audit_log.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+
+            runtime.block_on(async {
+                plugin_audit_log.record(&PluginId::from_string("test-plugin"), "fetch", "https://example.com/");
+            });
+
+            drop(runtime);
+        }).join().unwrap();
+
+        let mut entries = vec![];
+        for _ in 0..50 {
+            entries = repository.list_audit_entries("test-plugin").await.unwrap();
+
+            if !entries.is_empty() {
+                break
+            }
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, "fetch");
+        assert_eq!(entries[0].target, "https://example.com/");
+    }
+}
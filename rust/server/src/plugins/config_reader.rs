@@ -1,3 +1,6 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
 use serde::Deserialize;
 
 use common::dirs::Dirs;
@@ -16,58 +19,242 @@ impl ConfigReader {
         }
     }
 
-    pub async fn reload_config(&self) -> anyhow::Result<()> {
-        let config = self.read_config();
+    pub async fn reload_config(&self) -> anyhow::Result<ConfigReloadResult> {
+        let (config, errors) = self.read_config()?;
 
-        for plugin in config.plugins {
+        for plugin in &config.plugins {
             let exists = self.repository.does_plugin_exist(&plugin.id).await?;
             if !exists {
                 let pending = self.repository.is_plugin_pending(&plugin.id).await?;
                 if !pending {
                     let pending_plugin = DbWritePendingPlugin {
-                        id: plugin.id
+                        id: plugin.id.clone()
                     };
                     self.repository.save_pending_plugin(pending_plugin).await?
                 }
             }
         }
 
-        Ok(())
+        Ok(ConfigReloadResult {
+            plugins: config.plugins,
+            errors,
+        })
+    }
+
+    pub fn read_plugin_entry(&self, plugin_id: &str) -> anyhow::Result<Option<PluginEntryConfig>> {
+        let (config, _) = self.read_config()?;
+
+        Ok(config.into_plugin_entry(plugin_id))
+    }
+
+    // None if some plugin entries are malformed, in that case it is not known which plugins are in config
+    pub fn read_plugin_ids(&self) -> anyhow::Result<Option<Vec<String>>> {
+        let (config, errors) = self.read_config()?;
+
+        if !errors.is_empty() {
+            return Ok(None)
+        }
+
+        let plugin_ids = config.plugins
+            .into_iter()
+            .map(|plugin| plugin.id)
+            .collect();
+
+        Ok(Some(plugin_ids))
+    }
+
+    // invalid config shouldn't prevent plugins from starting, so system settings are used instead
+    pub fn read_locale_config(&self) -> LocaleConfig {
+        match self.read_config() {
+            Ok((config, _)) => config.locale,
+            Err(err) => {
+                tracing::warn!("Unable to read locale config, using system settings: {:?}", err);
+
+                LocaleConfig::default()
+            }
+        }
+    }
+
+    fn read_config(&self) -> anyhow::Result<(ApplicationConfig, Vec<String>)> {
+        read_config(&self.dirs)
+    }
+}
+
+// time zone is needed before server is started, when database is not yet available
+pub fn read_timezone_config(dirs: &Dirs) -> Option<String> {
+    match read_config(dirs) {
+        Ok((config, _)) => config.locale.timezone,
+        Err(err) => {
+            tracing::warn!("Unable to read time zone config, using system settings: {:?}", err);
+
+            None
+        }
     }
+}
+
+fn read_config(dirs: &Dirs) -> anyhow::Result<(ApplicationConfig, Vec<String>)> {
+    let config_file = dirs.config_file();
+    let config_content = std::fs::read_to_string(config_file);
+
+    match config_content {
+        Ok(config_content) => parse_config(&config_content, &dirs.home_dir()),
+        Err(_) => {
+            tracing::info!("No config found, using default configuration");
 
-    fn read_config(&self) -> ApplicationConfig {
-        let config_file = self.dirs.config_file();
-        let config_content = std::fs::read_to_string(config_file);
+            Ok((ApplicationConfig::default(), vec![]))
+        }
+    }
+}
 
-        match config_content {
-            Ok(config_content) => {
-                toml::from_str(&config_content)
-                    .unwrap_or_else(|err| {
-                        tracing::error!("Unable to parse config, error: {:?}", err);
+// errors in separate plugin entries do not prevent other entries from being applied,
+// but if the file itself is not a valid toml, whole config is rejected
+fn parse_config(config_content: &str, home_dir: &Path) -> anyhow::Result<(ApplicationConfig, Vec<String>)> {
+    let raw_config: RawApplicationConfig = toml::from_str(config_content)
+        .context("Unable to parse config")?;
 
-                        ApplicationConfig::default()
+    let mut errors = vec![];
+
+    let plugins = raw_config.plugins
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, value)| {
+            let plugin = PluginEntryConfig::deserialize(value)
+                .map_err(|err| anyhow!("{}", err.message()))
+                .and_then(|plugin| {
+                    Ok(PluginEntryConfig {
+                        id: expand_variables(&plugin.id, home_dir)?,
+                        git_ref: plugin.git_ref,
                     })
+                });
+
+            match plugin {
+                Ok(plugin) => Some(plugin),
+                Err(err) => {
+                    tracing::error!("Unable to parse plugin entry #{} in config, error: {:?}", index + 1, err);
+
+                    errors.push(format!("Plugin entry #{}: {}", index + 1, err));
+
+                    None
+                }
             }
-            Err(_) => {
-                tracing::info!("No config found, using default configuration");
+        })
+        .collect();
+
+    let locale = LocaleConfig {
+        locale: raw_config.locale,
+        timezone: raw_config.timezone,
+    };
+
+    Ok((ApplicationConfig { plugins, locale }, errors))
+}
+
+// expands "~" at the start of the value (or right after "file://") and $VAR or ${VAR} anywhere in the value
+fn expand_variables(value: &str, home_dir: &Path) -> anyhow::Result<String> {
+    let (prefix, value) = match value.strip_prefix("file://") {
+        Some(value) => ("file://", value),
+        None => ("", value),
+    };
+
+    let value = if value == "~" || value.starts_with("~/") {
+        let home_dir = home_dir.to_str()
+            .ok_or(anyhow!("home directory path is not a valid utf-8"))?;
+
+        format!("{}{}", home_dir, &value[1..])
+    } else {
+        value.to_owned()
+    };
+
+    let mut result = String::from(prefix);
+    let mut chars = value.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        if char != '$' {
+            result.push(char);
+            continue
+        }
 
-                ApplicationConfig::default()
+        let name = if chars.peek() == Some(&'{') {
+            chars.next();
+
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some('}') => break,
+                    Some(char) => name.push(char),
+                    None => return Err(anyhow!("unclosed '${{' in '{}'", value)),
+                }
+            }
+            name
+        } else {
+            let mut name = String::new();
+            while let Some(char) = chars.peek() {
+                if char.is_ascii_alphanumeric() || *char == '_' {
+                    name.push(*char);
+                    chars.next();
+                } else {
+                    break
+                }
             }
+            name
+        };
+
+        if name.is_empty() {
+            result.push('$');
+            continue
         }
+
+        let variable = std::env::var(&name)
+            .map_err(|_| anyhow!("environment variable '{}' referenced in '{}' is not set", name, value))?;
+
+        result.push_str(&variable);
     }
+
+    Ok(result)
+}
+
+pub struct ConfigReloadResult {
+    pub plugins: Vec<PluginEntryConfig>,
+    pub errors: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
-pub struct ApplicationConfig {
-    // #[serde(default)] // TODO
-    // configuration_mode: ConfigurationModeConfig,
+struct RawApplicationConfig {
     #[serde(default)]
+    plugins: Vec<toml::Value>,
+    // BCP 47 language tag, e.g. "en-US"
+    locale: Option<String>,
+    // IANA time zone name, e.g. "Europe/Berlin"
+    timezone: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct ApplicationConfig {
+    // configuration_mode: ConfigurationModeConfig, // TODO
     plugins: Vec<PluginEntryConfig>,
+    locale: LocaleConfig,
+}
+
+// if not specified, system settings are used
+#[derive(Debug, Default, Clone)]
+pub struct LocaleConfig {
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+}
+
+impl ApplicationConfig {
+    fn into_plugin_entry(self, plugin_id: &str) -> Option<PluginEntryConfig> {
+        self.plugins
+            .into_iter()
+            .find(|plugin| plugin.id == plugin_id)
+    }
 }
 
 #[derive(Debug, Deserialize)]
-struct PluginEntryConfig {
-    id: String,
+pub struct PluginEntryConfig {
+    pub id: String,
+    // branch, tag or commit, if not specified "gauntlet/release" branch is used
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
 }
 
 // #[derive(Deserialize, Debug, Default)]
@@ -78,3 +265,142 @@ struct PluginEntryConfig {
 //     #[serde(rename = "config_and_state")]
 //     ConfigAndState
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_plugin_entry_does_not_discard_valid_one() {
+        let config = r#"
+[[plugins]]
+id = "https://github.com/user/valid-plugin"
+
+[[plugins]]
+id = 5
+"#;
+
+        let (config, errors) = parse_config(config, Path::new("/home/user")).unwrap();
+
+        assert_eq!(config.plugins.len(), 1);
+        assert_eq!(config.plugins[0].id, "https://github.com/user/valid-plugin");
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Plugin entry #2"));
+    }
+
+    #[test]
+    fn invalid_toml_rejects_whole_config() {
+        assert!(parse_config("[[plugins]\nid = ", Path::new("/home/user")).is_err());
+    }
+
+    #[test]
+    fn locale_and_timezone_are_read_from_config() {
+        let config = r#"
+locale = "de-DE"
+timezone = "Europe/Berlin"
+"#;
+
+        let (config, _) = parse_config(config, Path::new("/home/user")).unwrap();
+
+        assert_eq!(config.locale.locale.as_deref(), Some("de-DE"));
+        assert_eq!(config.locale.timezone.as_deref(), Some("Europe/Berlin"));
+    }
+
+    #[test]
+    fn missing_locale_and_timezone_fall_back_to_system_settings() {
+        let (config, _) = parse_config("", Path::new("/home/user")).unwrap();
+
+        assert_eq!(config.locale.locale, None);
+        assert_eq!(config.locale.timezone, None);
+    }
+
+    #[test]
+    fn expands_dollar_variable() {
+        std::env::set_var("GAUNTLET_TEST_EXPAND_PLAIN", "plugin");
+
+        let value = expand_variables("https://github.com/user/$GAUNTLET_TEST_EXPAND_PLAIN", Path::new("/home/user")).unwrap();
+
+        assert_eq!(value, "https://github.com/user/plugin");
+    }
+
+    #[test]
+    fn expands_braced_variable() {
+        std::env::set_var("GAUNTLET_TEST_EXPAND_BRACED", "user");
+
+        let value = expand_variables("https://github.com/${GAUNTLET_TEST_EXPAND_BRACED}/plugin", Path::new("/home/user")).unwrap();
+
+        assert_eq!(value, "https://github.com/user/plugin");
+    }
+
+    #[test]
+    fn expands_home_dir() {
+        assert_eq!(expand_variables("~/plugin", Path::new("/home/user")).unwrap(), "/home/user/plugin");
+        assert_eq!(expand_variables("file://~/plugin", Path::new("/home/user")).unwrap(), "file:///home/user/plugin");
+    }
+
+    #[test]
+    fn does_not_expand_tilde_in_the_middle() {
+        assert_eq!(expand_variables("file:///plugins/~/plugin", Path::new("/home/user")).unwrap(), "file:///plugins/~/plugin");
+    }
+
+    #[test]
+    fn unset_variable_is_an_error() {
+        std::env::remove_var("GAUNTLET_TEST_EXPAND_UNSET");
+
+        assert!(expand_variables("file:///$GAUNTLET_TEST_EXPAND_UNSET/plugin", Path::new("/home/user")).is_err());
+        assert!(expand_variables("file:///${GAUNTLET_TEST_EXPAND_UNSET}/plugin", Path::new("/home/user")).is_err());
+    }
+
+    #[test]
+    fn unclosed_brace_is_an_error() {
+        assert!(expand_variables("file:///${HOME/plugin", Path::new("/home/user")).is_err());
+    }
+
+    #[test]
+    fn unset_variable_is_reported_as_entry_error() {
+        std::env::remove_var("GAUNTLET_TEST_EXPAND_UNSET_ENTRY");
+
+        let config = r#"
+[[plugins]]
+id = "file:///$GAUNTLET_TEST_EXPAND_UNSET_ENTRY/plugin"
+"#;
+
+        let (config, errors) = parse_config(config, Path::new("/home/user")).unwrap();
+
+        assert!(config.plugins.is_empty());
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn only_requested_plugin_entry_is_read() {
+        let config = r#"
+[[plugins]]
+id = "https://github.com/user/first-plugin"
+ref = "v1"
+
+[[plugins]]
+id = "https://github.com/user/second-plugin"
+ref = "v2"
+"#;
+
+        let (config, _) = parse_config(config, Path::new("/home/user")).unwrap();
+
+        let plugin = config.into_plugin_entry("https://github.com/user/second-plugin").unwrap();
+
+        assert_eq!(plugin.id, "https://github.com/user/second-plugin");
+        assert_eq!(plugin.git_ref.as_deref(), Some("v2"));
+    }
+
+    #[test]
+    fn plugin_entry_missing_from_config_is_not_read() {
+        let config = r#"
+[[plugins]]
+id = "https://github.com/user/first-plugin"
+"#;
+
+        let (config, _) = parse_config(config, Path::new("/home/user")).unwrap();
+
+        assert!(config.into_plugin_entry("https://github.com/user/second-plugin").is_none());
+    }
+}
@@ -0,0 +1,159 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio_util::sync::CancellationToken;
+
+use crate::plugins::ApplicationManager;
+
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(500);
+
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    cancellation_token: CancellationToken,
+}
+
+impl ConfigWatcher {
+    pub fn start(application_manager: Arc<ApplicationManager>) -> anyhow::Result<Self> {
+        let config_file = application_manager.dirs.config_file();
+        let config_dir = application_manager.dirs.config_dir();
+
+        Self::watch(config_dir, config_file, move || {
+            let application_manager = application_manager.clone();
+
+            async move {
+                if let Err(err) = application_manager.reload_all_plugins().await {
+                    tracing::error!("error occurred when reloading config {:?}", err)
+                }
+            }
+        })
+    }
+
+    fn watch<F, Fut>(config_dir: PathBuf, config_file: PathBuf, on_change: F) -> anyhow::Result<Self>
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        std::fs::create_dir_all(&config_dir)
+            .context("Unable to create config directory")?;
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<()>();
+
+        // editors usually replace file instead of modifying it, so whole directory is watched
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            match event {
+                Ok(event) => {
+                    if matches!(event.kind, EventKind::Access(_)) {
+                        return;
+                    }
+
+                    if event.paths.iter().any(|path| path == &config_file) {
+                        let _ = sender.send(());
+                    }
+                }
+                Err(err) => tracing::warn!("error occurred when watching config file {:?}", err)
+            }
+        })?;
+
+        watcher.watch(&config_dir, RecursiveMode::NonRecursive)?;
+
+        let cancellation_token = CancellationToken::new();
+
+        tokio::spawn({
+            let cancellation_token = cancellation_token.clone();
+
+            async move {
+                loop {
+                    tokio::select! {
+                        _ = cancellation_token.cancelled() => break,
+                        event = receiver.recv() => {
+                            if event.is_none() {
+                                break
+                            }
+
+                            tokio::time::sleep(DEBOUNCE_DURATION).await;
+
+                            while let Ok(()) = receiver.try_recv() { }
+
+                            tracing::info!("Config file changed, reloading");
+
+                            on_change().await;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            _watcher: watcher,
+            cancellation_token,
+        })
+    }
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.cancellation_token.cancel()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn config_change_triggers_reload_after_debounce() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_dir = temp_dir.path().canonicalize().unwrap();
+        let config_file = config_dir.join("config.toml");
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<()>();
+
+        let _config_watcher = ConfigWatcher::watch(config_dir.clone(), config_file.clone(), move || {
+            let sender = sender.clone();
+
+            async move {
+                let _ = sender.send(());
+            }
+        }).unwrap();
+
+        std::fs::write(&config_file, "[[plugins]]\nid = \"first\"\n").unwrap();
+        std::fs::write(&config_file, "[[plugins]]\nid = \"second\"\n").unwrap();
+
+        tokio::time::timeout(DEBOUNCE_DURATION * 4, receiver.recv())
+            .await
+            .expect("config wasn't reloaded in time")
+            .unwrap();
+
+        // both writes happened inside of debounce window, so they result in a single reload
+        tokio::time::sleep(DEBOUNCE_DURATION * 2).await;
+
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn change_of_other_file_does_not_trigger_reload() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_dir = temp_dir.path().canonicalize().unwrap();
+        let config_file = config_dir.join("config.toml");
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<()>();
+
+        let _config_watcher = ConfigWatcher::watch(config_dir.clone(), config_file, move || {
+            let sender = sender.clone();
+
+            async move {
+                let _ = sender.send(());
+            }
+        }).unwrap();
+
+        std::fs::write(config_dir.join("other.toml"), "").unwrap();
+
+        let result = tokio::time::timeout(DEBOUNCE_DURATION * 2, receiver.recv()).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -1,22 +1,21 @@
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context};
-use deno_core::error::AnyError;
-use deno_core::futures;
-use deno_core::futures::{StreamExt, TryStreamExt};
+use deno_core::futures::StreamExt;
 use deno_core::futures::future::join_all;
 use serde::{Deserialize, Serialize};
 use sqlx::{Error, Executor, Pool, Row, Sqlite, SqlitePool};
 use sqlx::migrate::Migrator;
 use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::types::Json;
+use thiserror::Error;
 use typed_path::TypedPathBuf;
 use uuid::Uuid;
 use common::model::{PhysicalKey, PhysicalShortcut, PluginId};
 use common::dirs::Dirs;
 use crate::model::ActionShortcutKey;
-use crate::plugins::frecency::{FrecencyItemStats, FrecencyMetaParams};
+use crate::plugins::frecency::{FrecencyItemStats, FrecencyMetaParams, FrecencyWriteBuffer, PendingFrecencyMarks};
 use crate::plugins::loader::PluginManifestActionShortcutKey;
 
 static MIGRATOR: Migrator = sqlx::migrate!("./db_migrations");
@@ -24,6 +23,21 @@ static MIGRATOR: Migrator = sqlx::migrate!("./db_migrations");
 #[derive(Clone)]
 pub struct DataDbRepository {
     pool: Pool<Sqlite>,
+    frecency_buffer: FrecencyWriteBuffer,
+}
+
+// returned by methods which expect row to exist, e.g. plugin was removed while request was in flight
+#[derive(Error, Debug, Clone)]
+pub enum DataDbNotFoundError {
+    #[error("Plugin with id '{plugin_id}' doesn't exist")]
+    Plugin {
+        plugin_id: String,
+    },
+    #[error("Entrypoint with id '{entrypoint_id}' doesn't exist in plugin '{plugin_id}'")]
+    Entrypoint {
+        plugin_id: String,
+        entrypoint_id: String,
+    },
 }
 
 #[derive(sqlx::FromRow)]
@@ -43,6 +57,10 @@ pub struct DbReadPlugin {
     pub preferences: HashMap<String, DbPluginPreference>,
     #[sqlx(json)]
     pub preferences_user_data: HashMap<String, DbPluginPreferenceUserData>,
+    // commit id of downloaded revision, not set for local and bundled plugins
+    pub version: Option<String>,
+    pub min_app_version: Option<u32>,
+    pub max_app_version: Option<u32>,
 }
 
 #[derive(sqlx::FromRow)]
@@ -53,6 +71,7 @@ pub struct DbReadPluginEntrypoint {
     pub name: String,
     pub description: String,
     pub enabled: bool,
+    pub searchable: bool,
     pub icon_path: Option<String>,
     #[sqlx(rename = "type")]
     pub entrypoint_type: String,
@@ -69,6 +88,9 @@ pub struct DbReadPluginEntrypoint {
 #[derive(Deserialize, Serialize)]
 pub struct DbCode {
     pub js: HashMap<String, String>,
+    // set for linked local plugins, js is read from this directory on each start instead of being stored
+    #[serde(default)]
+    pub linked_path: Option<String>,
 }
 
 pub struct DbWritePlugin {
@@ -82,6 +104,9 @@ pub struct DbWritePlugin {
     pub permissions: DbPluginPermissions,
     pub plugin_type: String,
     pub preferences: HashMap<String, DbPluginPreference>,
+    pub version: Option<String>,
+    pub min_app_version: Option<u32>,
+    pub max_app_version: Option<u32>,
 }
 
 pub struct DbWritePluginEntrypoint {
@@ -112,6 +137,7 @@ pub enum DbPluginType {
     Normal,
     Config,
     Bundled,
+    Linked,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -130,6 +156,12 @@ pub struct DbPluginPermissions {
     pub clipboard: Vec<DbPluginClipboardPermissions>,
     #[serde(default)]
     pub main_search_bar: Vec<DbPluginMainSearchBarPermissions>,
+    #[serde(default)]
+    pub high_resolution_time: bool,
+    #[serde(default)]
+    pub list_entrypoints: bool,
+    #[serde(default)]
+    pub ffi: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -221,6 +253,37 @@ pub struct DbSettingsData {
     pub global_shortcut: DbSettingsGlobalShortcutData,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DbSettingsFallbackEntrypointData {
+    pub plugin_id: String,
+    pub entrypoint_id: String,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct DbReadPluginAssetData {
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct DbReadPluginDownloadStatus {
+    pub plugin_id: String,
+    pub status: String,
+    pub message: String,
+}
+
+pub enum DbDownloadStatus {
+    InProgress,
+    Failed,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct DbReadPluginAuditEntry {
+    pub timestamp: i64,
+    pub action: String,
+    pub target: String,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct DbSettingsGlobalShortcutData {
     pub physical_key: String,
@@ -230,7 +293,7 @@ pub struct DbSettingsGlobalShortcutData {
     pub modifier_meta: bool
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum DbPluginActionShortcutKind {
     #[serde(rename = "main")]
     Main,
@@ -287,6 +350,23 @@ pub enum DbPluginPreference {
     }
 }
 
+impl DbPluginPreference {
+    // id is shown in place of name for preferences saved before names were introduced
+    pub fn name_and_description<'a>(&'a self, id: &'a str) -> (&'a str, &'a str) {
+        let (name, description) = match self {
+            DbPluginPreference::Number { name, description, .. } => (name, description),
+            DbPluginPreference::String { name, description, .. } => (name, description),
+            DbPluginPreference::Enum { name, description, .. } => (name, description),
+            DbPluginPreference::Bool { name, description, .. } => (name, description),
+            DbPluginPreference::ListOfStrings { name, description, .. } => (name, description),
+            DbPluginPreference::ListOfNumbers { name, description, .. } => (name, description),
+            DbPluginPreference::ListOfEnums { name, description, .. } => (name, description),
+        };
+
+        (name.as_deref().unwrap_or(id), description)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct DbPreferenceEnumValue {
     pub label: String,
@@ -294,6 +374,16 @@ pub struct DbPreferenceEnumValue {
 }
 
 
+pub struct DbPreferenceSearchResult {
+    pub plugin_id: String,
+    pub plugin_name: String,
+    pub entrypoint_id: Option<String>,
+    pub entrypoint_name: Option<String>,
+    pub preference_id: String,
+    pub preference_name: String,
+    pub description: String,
+}
+
 #[derive(sqlx::FromRow)]
 pub struct DbReadPendingPlugin {
     pub id: String,
@@ -317,9 +407,15 @@ pub struct DbPluginEntrypointFrecencyStats {
 
 impl DataDbRepository {
     pub async fn new(dirs: Dirs) -> anyhow::Result<Self> {
+        Self::open(&dirs.data_db_file()?).await
+    }
+
+    pub async fn open(db_file: &Path) -> anyhow::Result<Self> {
+        // foreign keys are required for rows of removed plugin to be deleted by ON DELETE CASCADE
         let conn = SqliteConnectOptions::new()
-            .filename(dirs.data_db_file()?)
-            .create_if_missing(true);
+            .filename(db_file)
+            .create_if_missing(true)
+            .foreign_keys(true);
 
         let pool = SqlitePool::connect_with(conn)
             .await
@@ -330,7 +426,7 @@ impl DataDbRepository {
             .await
             .context("Unable apply database migration")?;
 
-        let db_repository = Self { pool };
+        let db_repository = Self { pool, frecency_buffer: FrecencyWriteBuffer::new() };
 
         db_repository.apply_uuid_default_value().await?;
         db_repository.remove_legacy_bundled_plugins().await?;
@@ -387,20 +483,136 @@ impl DataDbRepository {
         Ok(plugins)
     }
 
+    // case-insensitive match on preference id, name or description, empty text matches everything
+    pub async fn search_preferences(&self, text: &str) -> anyhow::Result<Vec<DbPreferenceSearchResult>> {
+        let text = text.to_lowercase();
+
+        let matches = |id: &str, preference: &DbPluginPreference| {
+            let (name, description) = preference.name_and_description(id);
+
+            [id, name, description]
+                .iter()
+                .any(|value| value.to_lowercase().contains(&text))
+        };
+
+        let mut result = vec![];
+
+        for (plugin, entrypoints) in self.list_plugins_and_entrypoints().await? {
+            for (preference_id, preference) in &plugin.preferences {
+                if matches(preference_id, preference) {
+                    let (name, description) = preference.name_and_description(preference_id);
+
+                    result.push(DbPreferenceSearchResult {
+                        plugin_id: plugin.id.clone(),
+                        plugin_name: plugin.name.clone(),
+                        entrypoint_id: None,
+                        entrypoint_name: None,
+                        preference_id: preference_id.clone(),
+                        preference_name: name.to_string(),
+                        description: description.to_string(),
+                    })
+                }
+            }
+
+            for entrypoint in entrypoints {
+                for (preference_id, preference) in &entrypoint.preferences {
+                    if matches(preference_id, preference) {
+                        let (name, description) = preference.name_and_description(preference_id);
+
+                        result.push(DbPreferenceSearchResult {
+                            plugin_id: plugin.id.clone(),
+                            plugin_name: plugin.name.clone(),
+                            entrypoint_id: Some(entrypoint.id.clone()),
+                            entrypoint_name: Some(entrypoint.name.clone()),
+                            preference_id: preference_id.clone(),
+                            preference_name: name.to_string(),
+                            description: description.to_string(),
+                        })
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
     pub async fn list_plugins_and_entrypoints(&self) -> anyhow::Result<Vec<(DbReadPlugin, Vec<DbReadPluginEntrypoint>)>> {
-        // language=SQLite
         let plugins = self.list_plugins().await?;
 
-        let result = futures::stream::iter(plugins)
-            .then(|plugin| async move {
-                let entrypoints = self.get_entrypoints_by_plugin_id(&plugin.id).await?;
+        // language=SQLite
+        let entrypoints = sqlx::query_as::<_, DbReadPluginEntrypoint>("SELECT * FROM plugin_entrypoint")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(group_entrypoints_by_plugin(plugins, entrypoints))
+    }
 
-                Ok::<(DbReadPlugin, Vec<DbReadPluginEntrypoint>), AnyError>((plugin, entrypoints))
-            })
-            .try_collect::<Vec<(DbReadPlugin, Vec<DbReadPluginEntrypoint>)>>()
+    // plugins are ordered by id, so pages are stable between calls
+    pub async fn list_plugins_and_entrypoints_page(&self, offset: u32, limit: u32) -> anyhow::Result<Vec<(DbReadPlugin, Vec<DbReadPluginEntrypoint>)>> {
+        // both queries see the same snapshot, so plugin added or removed in between doesn't shift entrypoints to another page
+        let mut tx = self.pool.begin().await?;
+
+        // language=SQLite
+        let plugins = sqlx::query_as::<_, DbReadPlugin>("SELECT * FROM plugin ORDER BY id LIMIT ?1 OFFSET ?2")
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&mut *tx)
             .await?;
 
-        Ok(result)
+        if plugins.is_empty() {
+            tx.commit().await?;
+
+            return Ok(vec![])
+        }
+
+        let placeholders = (1..=plugins.len())
+            .map(|index| format!("?{}", index))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // language=SQLite
+        let sql = format!("SELECT * FROM plugin_entrypoint WHERE plugin_id IN ({})", placeholders);
+
+        let mut query = sqlx::query_as::<_, DbReadPluginEntrypoint>(&sql);
+
+        for plugin in &plugins {
+            query = query.bind(&plugin.id);
+        }
+
+        let entrypoints = query
+            .fetch_all(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(group_entrypoints_by_plugin(plugins, entrypoints))
+    }
+
+    pub async fn count_plugins(&self) -> anyhow::Result<usize> {
+        // language=SQLite
+        let (count, ) = sqlx::query_as::<_, (i64, )>("SELECT COUNT(*) FROM plugin")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count as usize)
+    }
+
+    pub async fn count_enabled_plugins(&self) -> anyhow::Result<usize> {
+        // language=SQLite
+        let (count, ) = sqlx::query_as::<_, (i64, )>("SELECT COUNT(*) FROM plugin WHERE enabled = TRUE")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count as usize)
+    }
+
+    pub async fn count_entrypoints(&self) -> anyhow::Result<usize> {
+        // language=SQLite
+        let (count, ) = sqlx::query_as::<_, (i64, )>("SELECT COUNT(*) FROM plugin_entrypoint")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count as usize)
     }
 
     pub async fn get_plugin_by_id(&self, plugin_id: &str) -> anyhow::Result<DbReadPlugin> {
@@ -411,13 +623,9 @@ impl DataDbRepository {
         where
             E: Executor<'a, Database=Sqlite>,
     {
-        // language=SQLite
-        let result = sqlx::query_as::<_, DbReadPlugin>("SELECT * FROM plugin WHERE id = ?1")
-            .bind(plugin_id)
-            .fetch_one(executor)
-            .await?;
-
-        Ok(result)
+        self.get_plugin_by_id_option_with_executor(plugin_id, executor)
+            .await?
+            .ok_or_else(|| DataDbNotFoundError::Plugin { plugin_id: plugin_id.to_string() }.into())
     }
 
     pub async fn get_plugin_by_id_option(&self, plugin_id: &str) -> anyhow::Result<Option<DbReadPlugin>> {
@@ -437,6 +645,16 @@ impl DataDbRepository {
         Ok(result)
     }
 
+    pub async fn list_entrypoints_by_type(&self, entrypoint_type: DbPluginEntrypointType) -> anyhow::Result<Vec<DbReadPluginEntrypoint>> {
+        // language=SQLite
+        let result = sqlx::query_as::<_, DbReadPluginEntrypoint>("SELECT * FROM plugin_entrypoint WHERE type = ?1")
+            .bind(db_entrypoint_to_str(entrypoint_type))
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(result)
+    }
+
     pub async fn get_entrypoints_by_plugin_id(&self, plugin_id: &str) -> anyhow::Result<Vec<DbReadPluginEntrypoint>> {
         self.get_entrypoints_by_plugin_id_with_executor(plugin_id, &self.pool).await
     }
@@ -462,14 +680,9 @@ impl DataDbRepository {
         where
             E: Executor<'a, Database=Sqlite>,
     {
-        // language=SQLite
-        let result = sqlx::query_as::<_, DbReadPluginEntrypoint>("SELECT * FROM plugin_entrypoint WHERE id = ?1 AND plugin_id = ?2")
-            .bind(entrypoint_id)
-            .bind(plugin_id)
-            .fetch_one(executor)
-            .await?;
-
-        Ok(result)
+        self.get_entrypoint_by_id_option_with_executor(plugin_id, entrypoint_id, executor)
+            .await?
+            .ok_or_else(|| DataDbNotFoundError::Entrypoint { plugin_id: plugin_id.to_string(), entrypoint_id: entrypoint_id.to_string() }.into())
     }
 
     pub async fn get_entrypoint_by_id_option(&self, plugin_id: &str, entrypoint_id: &str) -> anyhow::Result<Option<DbReadPluginEntrypoint>> {
@@ -638,6 +851,26 @@ impl DataDbRepository {
         Ok(plugins)
     }
 
+    // pending plugin is kept only if it is still in config and wasn't installed yet, returns ids of removed rows
+    pub async fn prune_pending_plugins(&self, config_plugin_ids: &[String]) -> anyhow::Result<Vec<String>> {
+        let mut pruned = vec![];
+
+        for pending_plugin in self.list_pending_plugins().await? {
+            let in_config = config_plugin_ids.contains(&pending_plugin.id);
+            let installed = self.does_plugin_exist(&pending_plugin.id).await?;
+
+            if in_config && !installed {
+                continue
+            }
+
+            self.remove_pending_plugin(&pending_plugin.id).await?;
+
+            pruned.push(pending_plugin.id);
+        }
+
+        Ok(pruned)
+    }
+
     pub async fn is_plugin_pending(&self, plugin_id: &str) -> anyhow::Result<bool> {
         // language=SQLite
         let result = sqlx::query_as::<_, (u8, )>("SELECT 1 FROM pending_plugin WHERE id = ?1")
@@ -667,12 +900,40 @@ impl DataDbRepository {
         // language=SQLite
         let result = sqlx::query_as::<_, DbReadPluginEnabled>("SELECT enabled FROM plugin WHERE id = ?1")
             .bind(plugin_id)
-            .fetch_one(&self.pool)
-            .await?;
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| DataDbNotFoundError::Plugin { plugin_id: plugin_id.to_string() })?;
+
+        Ok(result.enabled)
+    }
+
+    pub async fn is_plugin_entrypoint_enabled(&self, plugin_id: &str, entrypoint_id: &str) -> anyhow::Result<bool> {
+        #[derive(sqlx::FromRow)]
+        struct DbReadEntrypointEnabled {
+            pub enabled: bool,
+        }
+
+        // language=SQLite
+        let result = sqlx::query_as::<_, DbReadEntrypointEnabled>("SELECT enabled FROM plugin_entrypoint WHERE id = ?1 AND plugin_id = ?2")
+            .bind(entrypoint_id)
+            .bind(plugin_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| DataDbNotFoundError::Entrypoint { plugin_id: plugin_id.to_string(), entrypoint_id: entrypoint_id.to_string() })?;
 
         Ok(result.enabled)
     }
 
+    pub async fn list_asset_data(&self, plugin_id: &str) -> anyhow::Result<Vec<DbReadPluginAssetData>> {
+        // language=SQLite
+        let result = sqlx::query_as::<_, DbReadPluginAssetData>("SELECT path, data FROM plugin_asset_data WHERE plugin_id = ?1")
+            .bind(plugin_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(result)
+    }
+
     pub async fn get_asset_data(&self, plugin_id: &str, path: &str) -> anyhow::Result<Vec<u8>> {
         #[derive(sqlx::FromRow)]
         struct DbReadPluginAssetData {
@@ -724,13 +985,78 @@ impl DataDbRepository {
             .collect()
     }
 
-    pub async fn mark_entrypoint_frecency(&self, plugin_id: &str, entrypoint_id: &str) -> anyhow::Result<()> {
+    // written to db on next flush, reads of frecency include not yet written marks
+    pub fn mark_entrypoint_frecency(&self, plugin_id: &str, entrypoint_id: &str) {
+        self.frecency_buffer.mark(plugin_id, entrypoint_id)
+    }
+
+    pub async fn flush_frecency(&self) -> anyhow::Result<()> {
+        let _write_guard = self.frecency_buffer.lock_writes().await;
+
+        let pending = self.frecency_buffer.take();
+
+        if pending.is_empty() {
+            return Ok(())
+        }
+
+        if let Err(err) = self.write_frecency(&pending).await {
+            self.frecency_buffer.restore(pending);
+            return Err(err)
+        }
+
+        Ok(())
+    }
+
+    async fn write_frecency(&self, pending: &HashMap<(String, String), PendingFrecencyMarks>) -> anyhow::Result<()> {
         let mut tx = self.pool.begin().await?;
 
         // TODO reset time after 5 half lives
         //  https://github.com/camdencheek/fre/blob/6574ee7045061957de24855567e0abf05f2778d9/src/main.rs#L23
         //  why? dunno
 
+        let meta_params = Self::frecency_meta_params(&mut *tx).await?;
+
+        for ((plugin_id, entrypoint_id), marks) in pending {
+            // language=SQLite
+            let stats = sqlx::query_as::<_, DbPluginEntrypointFrecencyStats>("SELECT plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_frecency_stats WHERE plugin_id = ?1 and entrypoint_id = ?2")
+                .bind(plugin_id)
+                .bind(entrypoint_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+            let mut new_stats = frecency_item_stats(stats, &meta_params);
+
+            new_stats.mark_used_times(marks.times, marks.last_accessed);
+
+            // plugin could have been removed after entrypoint was used, such marks are skipped
+            // language=SQLite
+            let sql = r#"
+                INSERT OR REPLACE INTO plugin_entrypoint_frecency_stats (plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses)
+                    SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7
+                        WHERE EXISTS (SELECT 1 FROM plugin WHERE id = ?1)
+            "#;
+
+            sqlx::query(sql)
+                .bind(plugin_id)
+                .bind(entrypoint_id)
+                .bind(new_stats.reference_time)
+                .bind(new_stats.half_life)
+                .bind(new_stats.last_accessed)
+                .bind(new_stats.frecency)
+                .bind(new_stats.num_accesses)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn frecency_meta_params<'a, E>(executor: E) -> anyhow::Result<FrecencyMetaParams>
+        where
+            E: Executor<'a, Database=Sqlite>,
+    {
         #[derive(sqlx::FromRow)]
         struct DbFrecencyMetaParams {
             pub reference_time: f64,
@@ -739,7 +1065,7 @@ impl DataDbRepository {
 
         // language=SQLite
         let meta_params = sqlx::query_as::<_, DbFrecencyMetaParams>("SELECT reference_time, half_life FROM plugin_entrypoint_frecency_stats")
-            .fetch_optional(&mut *tx)
+            .fetch_optional(executor)
             .await?;
 
         let meta_params = match meta_params {
@@ -750,96 +1076,238 @@ impl DataDbRepository {
             }
         };
 
+        Ok(meta_params)
+    }
+
+    pub async fn remove_entrypoint_frecency(&self, plugin_id: &str, entrypoint_id: &str) -> anyhow::Result<()> {
+        let _write_guard = self.frecency_buffer.lock_writes().await;
+
+        self.frecency_buffer.forget(plugin_id, entrypoint_id);
+
         // language=SQLite
-        let stats = sqlx::query_as::<_, DbPluginEntrypointFrecencyStats>("SELECT plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_frecency_stats WHERE plugin_id = ?1 and entrypoint_id = ?2")
+        sqlx::query("DELETE FROM plugin_entrypoint_frecency_stats WHERE plugin_id = ?1 AND entrypoint_id = ?2")
             .bind(plugin_id)
             .bind(entrypoint_id)
-            .fetch_optional(&mut *tx)
+            .execute(&self.pool)
             .await?;
 
-        let mut new_stats = match stats {
-            None => {
-                FrecencyItemStats::new(meta_params.reference_time, meta_params.half_life)
-            }
-            Some(stats) => {
-                FrecencyItemStats {
-                    half_life: stats.half_life,
-                    reference_time: stats.reference_time,
-                    last_accessed: stats.last_accessed,
-                    frecency: stats.frecency,
-                    num_accesses: stats.num_accesses,
-                }
+        Ok(())
+    }
+
+    pub async fn remove_all_frecency(&self) -> anyhow::Result<()> {
+        let _write_guard = self.frecency_buffer.lock_writes().await;
+
+        self.frecency_buffer.clear();
+
+        // language=SQLite
+        sqlx::query("DELETE FROM plugin_entrypoint_frecency_stats")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_frecency_for_plugin(&self, plugin_id: &str) -> anyhow::Result<HashMap<String, f64>> {
+        // language=SQLite
+        let stats = sqlx::query_as::<_, DbPluginEntrypointFrecencyStats>("SELECT plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses FROM plugin_entrypoint_frecency_stats WHERE plugin_id = ?1")
+            .bind(plugin_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut pending = self.frecency_buffer.pending_for_plugin(plugin_id);
+
+        let mut result: HashMap<_, _> = stats.into_iter()
+            .map(|stats| {
+                let entrypoint_id = stats.entrypoint_id.clone();
+
+                let frecency = match pending.remove(&entrypoint_id) {
+                    None => stats.frecency,
+                    Some(marks) => {
+                        let mut stats = frecency_item_stats(Some(stats), &FrecencyMetaParams::default());
+                        stats.mark_used_times(marks.times, marks.last_accessed);
+                        stats.frecency
+                    }
+                };
+
+                (entrypoint_id, frecency)
+            })
+            .collect();
+
+        if !pending.is_empty() {
+            let meta_params = Self::frecency_meta_params(&self.pool).await?;
+
+            for (entrypoint_id, marks) in pending {
+                let mut stats = frecency_item_stats(None, &meta_params);
+                stats.mark_used_times(marks.times, marks.last_accessed);
+                result.insert(entrypoint_id, stats.frecency);
             }
-        };
+        }
 
-        new_stats.mark_used();
+        Ok(result)
+    }
 
+    pub async fn save_view_state(&self, plugin_id: &str, entrypoint_id: &str, state: serde_json::Value) -> anyhow::Result<()> {
         // language=SQLite
         let sql = r#"
-            INSERT OR REPLACE INTO plugin_entrypoint_frecency_stats (plugin_id, entrypoint_id, reference_time, half_life, last_accessed, frecency, num_accesses)
-                VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            INSERT INTO plugin_entrypoint_view_state (plugin_id, entrypoint_id, state)
+                VALUES(?1, ?2, ?3)
+                    ON CONFLICT (plugin_id, entrypoint_id)
+                        DO UPDATE SET state = ?3
         "#;
 
         sqlx::query(sql)
             .bind(plugin_id)
             .bind(entrypoint_id)
-            .bind(new_stats.reference_time)
-            .bind(new_stats.half_life)
-            .bind(new_stats.last_accessed)
-            .bind(new_stats.frecency)
-            .bind(new_stats.num_accesses)
-            .execute(&mut *tx)
+            .bind(Json(state))
+            .execute(&self.pool)
             .await?;
 
-        tx.commit().await?;
-
         Ok(())
     }
 
-    pub async fn get_frecency_for_plugin(&self, plugin_id: &str) -> anyhow::Result<HashMap<String, f64>> {
+    pub async fn get_view_state(&self, plugin_id: &str, entrypoint_id: &str) -> anyhow::Result<Option<serde_json::Value>> {
         // language=SQLite
-        let result = sqlx::query_as::<_, (String, f64)>("SELECT entrypoint_id, frecency FROM plugin_entrypoint_frecency_stats WHERE plugin_id = ?1")
+        let result = sqlx::query_as::<_, (Json<serde_json::Value>, )>("SELECT state FROM plugin_entrypoint_view_state WHERE plugin_id = ?1 AND entrypoint_id = ?2")
             .bind(plugin_id)
-            .fetch_all(&self.pool)
-            .await?
-            .into_iter()
-            .collect();
+            .bind(entrypoint_id)
+            .fetch_optional(&self.pool)
+            .await?;
 
-        Ok(result)
+        Ok(result.map(|(state, )| state.0))
     }
 
-    pub async fn set_plugin_enabled(&self, plugin_id: &str, enabled: bool) -> anyhow::Result<()> {
+    pub async fn get_permission_decision(&self, plugin_id: &str, kind: &str, detail: &str) -> anyhow::Result<Option<bool>> {
         // language=SQLite
-        sqlx::query("UPDATE plugin SET enabled = ?1 WHERE id = ?2")
-            .bind(enabled)
+        let result = sqlx::query_as::<_, (bool, )>("SELECT granted FROM plugin_permission_decision WHERE plugin_id = ?1 AND kind = ?2 AND detail = ?3")
+            .bind(plugin_id)
+            .bind(kind)
+            .bind(detail)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(result.map(|(granted, )| granted))
+    }
+
+    pub async fn save_permission_decision(&self, plugin_id: &str, kind: &str, detail: &str, granted: bool) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("INSERT OR REPLACE INTO plugin_permission_decision (plugin_id, kind, detail, granted) VALUES (?1, ?2, ?3, ?4)")
             .bind(plugin_id)
+            .bind(kind)
+            .bind(detail)
+            .bind(granted)
             .execute(&self.pool)
             .await?;
 
         Ok(())
     }
 
-    pub async fn set_plugin_entrypoint_enabled(&self, plugin_id: &str, entrypoint_id: &str, enabled: bool) -> anyhow::Result<()> {
+    pub async fn save_audit_entry(&self, plugin_id: &str, timestamp: i64, action: &str, target: &str) -> anyhow::Result<()> {
         // language=SQLite
-        sqlx::query("UPDATE plugin_entrypoint SET enabled = ?1 WHERE id = ?2 AND plugin_id = ?3")
-            .bind(enabled)
-            .bind(entrypoint_id)
+        sqlx::query("INSERT INTO plugin_audit_log (plugin_id, timestamp, action, target) VALUES (?1, ?2, ?3, ?4)")
             .bind(plugin_id)
+            .bind(timestamp)
+            .bind(action)
+            .bind(target)
             .execute(&self.pool)
             .await?;
 
         Ok(())
     }
 
-    pub async fn set_global_shortcut(&self, shortcut: PhysicalShortcut) -> anyhow::Result<()> {
+    pub async fn list_audit_entries(&self, plugin_id: &str) -> anyhow::Result<Vec<DbReadPluginAuditEntry>> {
         // language=SQLite
-        let sql = r#"
-            INSERT INTO settings_data (id, global_shortcut)
-                VALUES(?1, ?2)
-                    ON CONFLICT (id)
-                        DO UPDATE SET global_shortcut = ?2
-        "#;
-
+        let entries = sqlx::query_as::<_, DbReadPluginAuditEntry>("SELECT timestamp, action, target FROM plugin_audit_log WHERE plugin_id = ?1 ORDER BY id")
+            .bind(plugin_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(entries)
+    }
+
+    pub async fn list_download_statuses(&self) -> anyhow::Result<Vec<DbReadPluginDownloadStatus>> {
+        // language=SQLite
+        let statuses = sqlx::query_as::<_, DbReadPluginDownloadStatus>("SELECT * FROM plugin_download_status")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(statuses)
+    }
+
+    pub async fn save_download_status(&self, plugin_id: &str, status: &str, message: &str) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("INSERT OR REPLACE INTO plugin_download_status (plugin_id, status, message) VALUES (?1, ?2, ?3)")
+            .bind(plugin_id)
+            .bind(status)
+            .bind(message)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_download_status(&self, plugin_id: &str) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("DELETE FROM plugin_download_status WHERE plugin_id = ?1")
+            .bind(plugin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_all_download_statuses(&self) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("DELETE FROM plugin_download_status")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_plugin_enabled(&self, plugin_id: &str, enabled: bool) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("UPDATE plugin SET enabled = ?1 WHERE id = ?2")
+            .bind(enabled)
+            .bind(plugin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_plugin_entrypoint_enabled(&self, plugin_id: &str, entrypoint_id: &str, enabled: bool) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("UPDATE plugin_entrypoint SET enabled = ?1 WHERE id = ?2 AND plugin_id = ?3")
+            .bind(enabled)
+            .bind(entrypoint_id)
+            .bind(plugin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_plugin_entrypoint_searchable(&self, plugin_id: &str, entrypoint_id: &str, searchable: bool) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("UPDATE plugin_entrypoint SET searchable = ?1 WHERE id = ?2 AND plugin_id = ?3")
+            .bind(searchable)
+            .bind(entrypoint_id)
+            .bind(plugin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_global_shortcut(&self, shortcut: PhysicalShortcut) -> anyhow::Result<()> {
+        // language=SQLite
+        let sql = r#"
+            INSERT INTO settings_data (id, global_shortcut)
+                VALUES(?1, ?2)
+                    ON CONFLICT (id)
+                        DO UPDATE SET global_shortcut = ?2
+        "#;
+
         let id = "settings_data"; // only one row in the table
 
         let shortcut_data = DbSettingsGlobalShortcutData {
@@ -900,7 +1368,95 @@ impl DataDbRepository {
         }
     }
 
+    pub async fn set_fallback_entrypoint(&self, fallback: Option<DbSettingsFallbackEntrypointData>) -> anyhow::Result<()> {
+        // language=SQLite
+        let sql = r#"
+            INSERT INTO settings_data (id, global_shortcut, fallback_entrypoint)
+                VALUES(?1, ?2, ?3)
+                    ON CONFLICT (id)
+                        DO UPDATE SET fallback_entrypoint = ?3
+        "#;
+
+        let id = "settings_data"; // only one row in the table
+
+        // used only if row doesn't exist yet
+        let shortcut = self.get_global_shortcut().await?;
+
+        let shortcut_data = DbSettingsGlobalShortcutData {
+            physical_key: shortcut.physical_key.to_value(),
+            modifier_shift: shortcut.modifier_shift,
+            modifier_control: shortcut.modifier_control,
+            modifier_alt: shortcut.modifier_alt,
+            modifier_meta: shortcut.modifier_meta,
+        };
+
+        sqlx::query(sql)
+            .bind(id)
+            .bind(Json(shortcut_data))
+            .bind(fallback.map(|fallback| Json(fallback)))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_fallback_entrypoint(&self) -> anyhow::Result<Option<DbSettingsFallbackEntrypointData>> {
+        // language=SQLite
+        let result = sqlx::query_as::<_, (Option<Json<DbSettingsFallbackEntrypointData>>, )>("SELECT fallback_entrypoint FROM settings_data")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(result.and_then(|(fallback, )| fallback).map(|fallback| fallback.0))
+    }
+
+    pub async fn set_plugin_boosts(&self, plugin_boosts: HashMap<String, f64>) -> anyhow::Result<()> {
+        // language=SQLite
+        let sql = r#"
+            INSERT INTO settings_data (id, global_shortcut, plugin_boosts)
+                VALUES(?1, ?2, ?3)
+                    ON CONFLICT (id)
+                        DO UPDATE SET plugin_boosts = ?3
+        "#;
+
+        let id = "settings_data"; // only one row in the table
+
+        // used only if row doesn't exist yet
+        let shortcut = self.get_global_shortcut().await?;
+
+        let shortcut_data = DbSettingsGlobalShortcutData {
+            physical_key: shortcut.physical_key.to_value(),
+            modifier_shift: shortcut.modifier_shift,
+            modifier_control: shortcut.modifier_control,
+            modifier_alt: shortcut.modifier_alt,
+            modifier_meta: shortcut.modifier_meta,
+        };
+
+        sqlx::query(sql)
+            .bind(id)
+            .bind(Json(shortcut_data))
+            .bind(Json(plugin_boosts))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_plugin_boosts(&self) -> anyhow::Result<HashMap<String, f64>> {
+        // language=SQLite
+        let result = sqlx::query_as::<_, (Option<Json<HashMap<String, f64>>>, )>("SELECT plugin_boosts FROM settings_data")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(result.and_then(|(plugin_boosts, )| plugin_boosts).map(|plugin_boosts| plugin_boosts.0).unwrap_or_default())
+    }
+
     pub async fn set_preference_value(&self, plugin_id: String, entrypoint_id: Option<String>, preference_id: String, value: DbPluginPreferenceUserData) -> anyhow::Result<()> {
+        self.set_preference_values(plugin_id, entrypoint_id, HashMap::from([(preference_id, value)]))
+            .await
+    }
+
+    // values not in the map are kept as is, either all values are saved or none
+    pub async fn set_preference_values(&self, plugin_id: String, entrypoint_id: Option<String>, values: HashMap<String, DbPluginPreferenceUserData>) -> anyhow::Result<()> {
         let mut tx = self.pool.begin().await?;
 
         match entrypoint_id {
@@ -909,7 +1465,7 @@ impl DataDbRepository {
                     .await?
                     .preferences_user_data;
 
-                user_data.insert(preference_id, value);
+                user_data.extend(values);
 
                 // language=SQLite
                 sqlx::query("UPDATE plugin SET preferences_user_data = ?1 WHERE id = ?2")
@@ -923,7 +1479,7 @@ impl DataDbRepository {
                     .await?
                     .preferences_user_data;
 
-                user_data.insert(preference_id, value);
+                user_data.extend(values);
 
                 // language=SQLite
                 sqlx::query("UPDATE plugin_entrypoint SET preferences_user_data = ?1 WHERE id = ?2 AND plugin_id = ?3")
@@ -940,6 +1496,32 @@ impl DataDbRepository {
         Ok(())
     }
 
+    pub async fn clear_preference_values(&self, plugin_id: &str, entrypoint_id: Option<&str>) -> anyhow::Result<()> {
+        let empty_user_data: HashMap<String, DbPluginPreferenceUserData> = HashMap::new();
+
+        match entrypoint_id {
+            None => {
+                // language=SQLite
+                sqlx::query("UPDATE plugin SET preferences_user_data = ?1 WHERE id = ?2")
+                    .bind(Json(empty_user_data))
+                    .bind(plugin_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            Some(entrypoint_id) => {
+                // language=SQLite
+                sqlx::query("UPDATE plugin_entrypoint SET preferences_user_data = ?1 WHERE id = ?2 AND plugin_id = ?3")
+                    .bind(Json(empty_user_data))
+                    .bind(entrypoint_id)
+                    .bind(plugin_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn save_pending_plugin(&self, plugin: DbWritePendingPlugin) -> anyhow::Result<()> {
         // language=SQLite
         sqlx::query("INSERT INTO pending_plugin VALUES(?1)")
@@ -950,6 +1532,61 @@ impl DataDbRepository {
         Ok(())
     }
 
+    pub async fn remove_pending_plugin(&self, plugin_id: &str) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("DELETE FROM pending_plugin WHERE id = ?1")
+            .bind(plugin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_plugin_bundle_hash(&self, plugin_id: &str) -> anyhow::Result<Option<String>> {
+        // language=SQLite
+        let result = sqlx::query_as::<_, (Option<String>, )>("SELECT bundle_hash FROM plugin WHERE id = ?1")
+            .bind(plugin_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .and_then(|(bundle_hash, )| bundle_hash);
+
+        Ok(result)
+    }
+
+    pub async fn set_plugin_bundle_hash(&self, plugin_id: &str, bundle_hash: &str) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("UPDATE plugin SET bundle_hash = ?1 WHERE id = ?2")
+            .bind(bundle_hash)
+            .bind(plugin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // git ref from config entry which was used to download config plugin
+    pub async fn get_plugin_git_ref(&self, plugin_id: &str) -> anyhow::Result<Option<String>> {
+        // language=SQLite
+        let result = sqlx::query_as::<_, (Option<String>, )>("SELECT git_ref FROM plugin WHERE id = ?1")
+            .bind(plugin_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .and_then(|(git_ref, )| git_ref);
+
+        Ok(result)
+    }
+
+    pub async fn set_plugin_git_ref(&self, plugin_id: &str, git_ref: Option<&str>) -> anyhow::Result<()> {
+        // language=SQLite
+        sqlx::query("UPDATE plugin SET git_ref = ?1 WHERE id = ?2")
+            .bind(git_ref)
+            .bind(plugin_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn remove_plugin(&self, plugin_id: &str) -> anyhow::Result<()> {
         // language=SQLite
         sqlx::query("DELETE FROM plugin WHERE id = ?1")
@@ -966,12 +1603,14 @@ impl DataDbRepository {
             .map(|plugin| (plugin.uuid, plugin.enabled, plugin.preferences_user_data))
             .unwrap_or((Uuid::new_v4().to_string(), new_plugin.enabled, HashMap::new()));
 
+        let preferences_user_data = migrate_preferences_user_data(&new_plugin.id, None, &new_plugin.preferences, preferences_user_data);
+
         // language=SQLite
         let sql = r#"
-            INSERT INTO plugin (id, name, enabled, code, permissions, preferences, preferences_user_data, description, type, uuid)
-                VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            INSERT INTO plugin (id, name, enabled, code, permissions, preferences, preferences_user_data, description, type, uuid, version, min_app_version, max_app_version)
+                VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
                     ON CONFLICT (id)
-                        DO UPDATE SET name = ?2, enabled = ?3, code = ?4, permissions = ?5, preferences = ?6, preferences_user_data = ?7, description = ?8, type = ?9, uuid = ?10
+                        DO UPDATE SET name = ?2, enabled = ?3, code = ?4, permissions = ?5, preferences = ?6, preferences_user_data = ?7, description = ?8, type = ?9, uuid = ?10, version = ?11, min_app_version = ?12, max_app_version = ?13
         "#;
 
         sqlx::query(sql)
@@ -985,6 +1624,9 @@ impl DataDbRepository {
             .bind(new_plugin.description)
             .bind(new_plugin.plugin_type)
             .bind(uuid)
+            .bind(new_plugin.version)
+            .bind(new_plugin.min_app_version)
+            .bind(new_plugin.max_app_version)
             .execute(&mut *tx)
             .await?;
 
@@ -996,12 +1638,14 @@ impl DataDbRepository {
         for new_entrypoint in new_plugin.entrypoints {
             old_entrypoint_ids.remove(&new_entrypoint.id);
 
-            let (uuid, preferences_user_data, actions_user_data, enabled) = self.get_entrypoint_by_id_option_with_executor(&new_plugin.id, &new_entrypoint.id, &mut *tx).await?
-                .map(|entrypoint| (entrypoint.uuid, entrypoint.preferences_user_data, entrypoint.actions_user_data, entrypoint.enabled))
-                .unwrap_or((Uuid::new_v4().to_string(), HashMap::new(), vec![], true));
+            let (uuid, preferences_user_data, actions_user_data, enabled, searchable) = self.get_entrypoint_by_id_option_with_executor(&new_plugin.id, &new_entrypoint.id, &mut *tx).await?
+                .map(|entrypoint| (entrypoint.uuid, entrypoint.preferences_user_data, entrypoint.actions_user_data, entrypoint.enabled, entrypoint.searchable))
+                .unwrap_or((Uuid::new_v4().to_string(), HashMap::new(), vec![], true, true));
+
+            let preferences_user_data = migrate_preferences_user_data(&new_plugin.id, Some(&new_entrypoint.id), &new_entrypoint.preferences, preferences_user_data);
 
             // language=SQLite
-            sqlx::query("INSERT OR REPLACE INTO plugin_entrypoint (id, plugin_id, name, enabled, type, preferences, preferences_user_data, description, actions, actions_user_data, icon_path, uuid) VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)")
+            sqlx::query("INSERT OR REPLACE INTO plugin_entrypoint (id, plugin_id, name, enabled, type, preferences, preferences_user_data, description, actions, actions_user_data, icon_path, uuid, searchable) VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)")
                 .bind(&new_entrypoint.id)
                 .bind(&new_plugin.id)
                 .bind(new_entrypoint.name)
@@ -1014,6 +1658,7 @@ impl DataDbRepository {
                 .bind(Json(actions_user_data))
                 .bind(new_entrypoint.icon_path)
                 .bind(uuid)
+                .bind(searchable)
                 .execute(&mut *tx)
                 .await?;
         }
@@ -1057,6 +1702,93 @@ impl DataDbRepository {
 }
 
 
+// when plugin is updated, values of removed preferences are dropped,
+// values with type not matching the new preference type are dropped too, so that default is used instead
+fn migrate_preferences_user_data(
+    plugin_id: &str,
+    entrypoint_id: Option<&str>,
+    preferences: &HashMap<String, DbPluginPreference>,
+    preferences_user_data: HashMap<String, DbPluginPreferenceUserData>,
+) -> HashMap<String, DbPluginPreferenceUserData> {
+    preferences_user_data.into_iter()
+        .filter(|(preference_id, user_data)| {
+            let Some(preference) = preferences.get(preference_id) else {
+                tracing::debug!("Dropping value of removed preference {:?}, plugin id: {:?}, entrypoint id: {:?}", preference_id, plugin_id, entrypoint_id);
+                return false
+            };
+
+            let compatible = is_preference_user_data_compatible(preference, user_data);
+
+            if !compatible {
+                tracing::warn!("Preference {:?} changed type or allowed values, dropping incompatible value, plugin id: {:?}, entrypoint id: {:?}", preference_id, plugin_id, entrypoint_id);
+            }
+
+            compatible
+        })
+        .collect()
+}
+
+fn is_preference_user_data_compatible(preference: &DbPluginPreference, user_data: &DbPluginPreferenceUserData) -> bool {
+    let is_enum_value = |enum_values: &[DbPreferenceEnumValue], value: &str| {
+        enum_values.iter().any(|enum_value| enum_value.value == value)
+    };
+
+    match (preference, user_data) {
+        (DbPluginPreference::Number { .. }, DbPluginPreferenceUserData::Number { .. }) => true,
+        (DbPluginPreference::String { .. }, DbPluginPreferenceUserData::String { .. }) => true,
+        (DbPluginPreference::Bool { .. }, DbPluginPreferenceUserData::Bool { .. }) => true,
+        (DbPluginPreference::ListOfStrings { .. }, DbPluginPreferenceUserData::ListOfStrings { .. }) => true,
+        (DbPluginPreference::ListOfNumbers { .. }, DbPluginPreferenceUserData::ListOfNumbers { .. }) => true,
+        (DbPluginPreference::Enum { enum_values, .. }, DbPluginPreferenceUserData::Enum { value }) => {
+            value.as_ref()
+                .map(|value| is_enum_value(enum_values, value))
+                .unwrap_or(true)
+        }
+        (DbPluginPreference::ListOfEnums { enum_values, .. }, DbPluginPreferenceUserData::ListOfEnums { value }) => {
+            value.as_ref()
+                .map(|value| value.iter().all(|value| is_enum_value(enum_values, value)))
+                .unwrap_or(true)
+        }
+        _ => false
+    }
+}
+
+fn group_entrypoints_by_plugin(plugins: Vec<DbReadPlugin>, entrypoints: Vec<DbReadPluginEntrypoint>) -> Vec<(DbReadPlugin, Vec<DbReadPluginEntrypoint>)> {
+    let mut entrypoints_by_plugin: HashMap<String, Vec<DbReadPluginEntrypoint>> = HashMap::new();
+
+    for entrypoint in entrypoints {
+        entrypoints_by_plugin.entry(entrypoint.plugin_id.clone())
+            .or_default()
+            .push(entrypoint);
+    }
+
+    plugins.into_iter()
+        .map(|plugin| {
+            let entrypoints = entrypoints_by_plugin.remove(&plugin.id)
+                .unwrap_or_default();
+
+            (plugin, entrypoints)
+        })
+        .collect()
+}
+
+fn frecency_item_stats(stats: Option<DbPluginEntrypointFrecencyStats>, meta_params: &FrecencyMetaParams) -> FrecencyItemStats {
+    match stats {
+        None => {
+            FrecencyItemStats::new(meta_params.reference_time, meta_params.half_life)
+        }
+        Some(stats) => {
+            FrecencyItemStats {
+                half_life: stats.half_life,
+                reference_time: stats.reference_time,
+                last_accessed: stats.last_accessed,
+                frecency: stats.frecency,
+                num_accesses: stats.num_accesses,
+            }
+        }
+    }
+}
+
 pub fn db_entrypoint_to_str(value: DbPluginEntrypointType) -> &'static str {
     match value {
         DbPluginEntrypointType::Command => "command",
@@ -1081,7 +1813,23 @@ pub fn db_plugin_type_to_str(value: DbPluginType) -> &'static str {
     match value {
         DbPluginType::Normal => "normal",
         DbPluginType::Config => "config",
-        DbPluginType::Bundled => "bundled"
+        DbPluginType::Bundled => "bundled",
+        DbPluginType::Linked => "linked",
+    }
+}
+
+pub fn db_download_status_to_str(value: DbDownloadStatus) -> &'static str {
+    match value {
+        DbDownloadStatus::InProgress => "in_progress",
+        DbDownloadStatus::Failed => "failed",
+    }
+}
+
+pub fn db_download_status_from_str(value: &str) -> DbDownloadStatus {
+    match value {
+        "in_progress" => DbDownloadStatus::InProgress,
+        "failed" => DbDownloadStatus::Failed,
+        _ => panic!("illegal download status: {}", value)
     }
 }
 
@@ -1090,6 +1838,479 @@ pub fn db_plugin_type_from_str(value: &str) -> DbPluginType {
         "normal" => DbPluginType::Normal,
         "config" => DbPluginType::Config,
         "bundled" => DbPluginType::Bundled,
+        "linked" => DbPluginType::Linked,
         _ => panic!("illegal plugin_type: {}", value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn open_repository(dir: &tempfile::TempDir) -> DataDbRepository {
+        DataDbRepository::open(&dir.path().join("data.db"))
+            .await
+            .unwrap()
+    }
+
+    fn test_entrypoint(entrypoint_id: &str, entrypoint_type: DbPluginEntrypointType) -> DbWritePluginEntrypoint {
+        DbWritePluginEntrypoint {
+            id: entrypoint_id.to_string(),
+            name: entrypoint_id.to_string(),
+            description: String::new(),
+            icon_path: None,
+            entrypoint_type: db_entrypoint_to_str(entrypoint_type).to_string(),
+            preferences: HashMap::new(),
+            actions: vec![],
+        }
+    }
+
+    fn test_plugin(plugin_id: &str) -> DbWritePlugin {
+        DbWritePlugin {
+            id: plugin_id.to_string(),
+            name: "Test Plugin".to_string(),
+            description: String::new(),
+            enabled: true,
+            code: DbCode {
+                js: HashMap::new(),
+                linked_path: None,
+            },
+            entrypoints: vec![
+                test_entrypoint("command", DbPluginEntrypointType::Command)
+            ],
+            asset_data: vec![
+                DbWritePluginAssetData {
+                    path: "icon.png".to_string(),
+                    data: vec![1, 2, 3],
+                }
+            ],
+            permissions: serde_json::from_str("{}").unwrap(),
+            plugin_type: db_plugin_type_to_str(DbPluginType::Normal).to_string(),
+            preferences: HashMap::new(),
+            version: None,
+            min_app_version: None,
+            max_app_version: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn entrypoints_are_listed_by_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        let mut first_plugin = test_plugin("first-plugin");
+        first_plugin.entrypoints = vec![
+            test_entrypoint("command", DbPluginEntrypointType::Command),
+            test_entrypoint("inline-view", DbPluginEntrypointType::InlineView),
+        ];
+
+        let mut second_plugin = test_plugin("second-plugin");
+        second_plugin.entrypoints = vec![
+            test_entrypoint("view", DbPluginEntrypointType::View),
+            test_entrypoint("inline-view", DbPluginEntrypointType::InlineView),
+        ];
+
+        repository.save_plugin(first_plugin).await.unwrap();
+        repository.save_plugin(second_plugin).await.unwrap();
+
+        let mut entrypoints = repository.list_entrypoints_by_type(DbPluginEntrypointType::InlineView)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entrypoint| (entrypoint.plugin_id, entrypoint.id))
+            .collect::<Vec<_>>();
+
+        entrypoints.sort();
+
+        assert_eq!(entrypoints, vec![
+            ("first-plugin".to_string(), "inline-view".to_string()),
+            ("second-plugin".to_string(), "inline-view".to_string()),
+        ]);
+    }
+
+    fn plugin_and_entrypoint_ids(plugins: Vec<(DbReadPlugin, Vec<DbReadPluginEntrypoint>)>) -> Vec<(String, Vec<String>)> {
+        plugins.into_iter()
+            .map(|(plugin, entrypoints)| {
+                let mut entrypoint_ids = entrypoints.into_iter()
+                    .map(|entrypoint| entrypoint.id)
+                    .collect::<Vec<_>>();
+
+                entrypoint_ids.sort();
+
+                (plugin.id, entrypoint_ids)
+            })
+            .collect()
+    }
+
+    async fn save_numbered_plugins(repository: &DataDbRepository) {
+        for index in 0..5 {
+            let mut plugin = test_plugin(&format!("plugin-{}", index));
+            plugin.entrypoints = (0..=index)
+                .map(|entrypoint_index| test_entrypoint(&format!("entrypoint-{}", entrypoint_index), DbPluginEntrypointType::Command))
+                .collect();
+
+            repository.save_plugin(plugin).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn plugins_page_returns_slice_ordered_by_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        save_numbered_plugins(&repository).await;
+
+        let page = repository.list_plugins_and_entrypoints_page(1, 2).await.unwrap();
+
+        assert_eq!(plugin_and_entrypoint_ids(page), vec![
+            ("plugin-1".to_string(), vec!["entrypoint-0".to_string(), "entrypoint-1".to_string()]),
+            ("plugin-2".to_string(), vec!["entrypoint-0".to_string(), "entrypoint-1".to_string(), "entrypoint-2".to_string()]),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn joined_entrypoints_match_entrypoints_fetched_per_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        save_numbered_plugins(&repository).await;
+
+        let mut per_plugin = vec![];
+        for plugin in repository.list_plugins().await.unwrap() {
+            let entrypoints = repository.get_entrypoints_by_plugin_id(&plugin.id).await.unwrap();
+            per_plugin.push((plugin, entrypoints));
+        }
+
+        let mut joined = plugin_and_entrypoint_ids(repository.list_plugins_and_entrypoints().await.unwrap());
+        let mut per_plugin = plugin_and_entrypoint_ids(per_plugin);
+
+        joined.sort();
+        per_plugin.sort();
+
+        assert_eq!(joined, per_plugin);
+    }
+
+    #[tokio::test]
+    async fn cleared_preference_values_are_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        repository.save_plugin(test_plugin("test-plugin")).await.unwrap();
+
+        for preference_id in ["first", "second", "third"] {
+            let value = DbPluginPreferenceUserData::String { value: Some(preference_id.to_string()) };
+            repository.set_preference_value("test-plugin".to_string(), None, preference_id.to_string(), value).await.unwrap();
+
+            let value = DbPluginPreferenceUserData::String { value: Some(preference_id.to_string()) };
+            repository.set_preference_value("test-plugin".to_string(), Some("command".to_string()), preference_id.to_string(), value).await.unwrap();
+        }
+
+        repository.clear_preference_values("test-plugin", Some("command")).await.unwrap();
+
+        let entrypoint = repository.get_entrypoint_by_id("test-plugin", "command").await.unwrap();
+        assert!(entrypoint.preferences_user_data.is_empty());
+
+        // plugin preferences are not affected by entrypoint reset
+        let plugin = repository.get_plugin_by_id("test-plugin").await.unwrap();
+        assert_eq!(plugin.preferences_user_data.len(), 3);
+
+        repository.clear_preference_values("test-plugin", None).await.unwrap();
+
+        let plugin = repository.get_plugin_by_id("test-plugin").await.unwrap();
+        assert!(plugin.preferences_user_data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rapid_frecency_marks_are_written_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        repository.save_plugin(test_plugin("test-plugin")).await.unwrap();
+
+        for _ in 0..5 {
+            repository.mark_entrypoint_frecency("test-plugin", "command");
+        }
+
+        // language=SQLite
+        let count_rows = "SELECT COUNT(*) FROM plugin_entrypoint_frecency_stats";
+
+        let (rows, ) = sqlx::query_as::<_, (i64, )>(count_rows).fetch_one(&repository.pool).await.unwrap();
+        assert_eq!(rows, 0);
+
+        let buffered_frecency = repository.get_frecency_for_plugin("test-plugin").await.unwrap()["command"];
+
+        repository.flush_frecency().await.unwrap();
+
+        let (rows, ) = sqlx::query_as::<_, (i64, )>(count_rows).fetch_one(&repository.pool).await.unwrap();
+        assert_eq!(rows, 1);
+
+        // language=SQLite
+        let (num_accesses, ) = sqlx::query_as::<_, (i32, )>("SELECT num_accesses FROM plugin_entrypoint_frecency_stats")
+            .fetch_one(&repository.pool)
+            .await
+            .unwrap();
+        assert_eq!(num_accesses, 5);
+
+        let written_frecency = repository.get_frecency_for_plugin("test-plugin").await.unwrap()["command"];
+        assert!((written_frecency - buffered_frecency).abs() <= buffered_frecency * 1e-9);
+    }
+
+    #[tokio::test]
+    async fn forgotten_entrypoint_has_no_frecency() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        let mut plugin = test_plugin("test-plugin");
+        plugin.entrypoints.push(test_entrypoint("other-command", DbPluginEntrypointType::Command));
+        repository.save_plugin(plugin).await.unwrap();
+
+        repository.mark_entrypoint_frecency("test-plugin", "command");
+        repository.mark_entrypoint_frecency("test-plugin", "other-command");
+        repository.flush_frecency().await.unwrap();
+
+        // not yet written marks are forgotten too
+        repository.mark_entrypoint_frecency("test-plugin", "command");
+
+        repository.remove_entrypoint_frecency("test-plugin", "command").await.unwrap();
+
+        let frecency = repository.get_frecency_for_plugin("test-plugin").await.unwrap();
+
+        assert_eq!(frecency.get("command").copied().unwrap_or(0.0), 0.0);
+        assert!(frecency["other-command"] > 0.0);
+
+        repository.flush_frecency().await.unwrap();
+
+        let frecency = repository.get_frecency_for_plugin("test-plugin").await.unwrap();
+
+        assert_eq!(frecency.get("command").copied().unwrap_or(0.0), 0.0);
+    }
+
+    #[tokio::test]
+    async fn preference_is_found_by_description_with_its_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        let string_preference = |name: &str, description: &str| DbPluginPreference::String {
+            name: Some(name.to_string()),
+            default: None,
+            description: description.to_string(),
+        };
+
+        let mut plugin = test_plugin("test-plugin");
+        plugin.preferences.insert("theme".to_string(), string_preference("Theme", "Color theme of views"));
+        plugin.entrypoints[0].preferences.insert("engine".to_string(), string_preference("Engine", "Default Search Engine"));
+        repository.save_plugin(plugin).await.unwrap();
+
+        let result = repository.search_preferences("search engine").await.unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].plugin_id, "test-plugin");
+        assert_eq!(result[0].entrypoint_id.as_deref(), Some("command"));
+        assert_eq!(result[0].entrypoint_name.as_deref(), Some("command"));
+        assert_eq!(result[0].preference_id, "engine");
+        assert_eq!(result[0].preference_name, "Engine");
+    }
+
+    #[tokio::test]
+    async fn view_state_is_restored_after_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        repository.save_plugin(test_plugin("test-plugin")).await.unwrap();
+
+        assert_eq!(repository.get_view_state("test-plugin", "command").await.unwrap(), None);
+
+        repository.save_view_state("test-plugin", "command", serde_json::json!({ "query": "first", "selected": 1 })).await.unwrap();
+        repository.save_view_state("test-plugin", "command", serde_json::json!({ "query": "second", "selected": 2 })).await.unwrap();
+
+        let reopened_repository = open_repository(&dir).await;
+
+        let view_state = reopened_repository.get_view_state("test-plugin", "command").await.unwrap();
+
+        assert_eq!(view_state, Some(serde_json::json!({ "query": "second", "selected": 2 })));
+    }
+
+    #[tokio::test]
+    async fn stale_preference_values_are_dropped_on_plugin_update() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        let number_preference = || DbPluginPreference::Number { name: None, default: None, description: String::new() };
+        let string_preference = || DbPluginPreference::String { name: None, default: None, description: String::new() };
+
+        let mut plugin = test_plugin("test-plugin");
+        plugin.preferences.insert("limit".to_string(), number_preference());
+        plugin.preferences.insert("greeting".to_string(), string_preference());
+        plugin.preferences.insert("removed".to_string(), string_preference());
+        repository.save_plugin(plugin).await.unwrap();
+
+        repository.set_preference_value("test-plugin".to_string(), None, "limit".to_string(), DbPluginPreferenceUserData::Number { value: Some(5.0) }).await.unwrap();
+        repository.set_preference_value("test-plugin".to_string(), None, "greeting".to_string(), DbPluginPreferenceUserData::String { value: Some("hello".to_string()) }).await.unwrap();
+        repository.set_preference_value("test-plugin".to_string(), None, "removed".to_string(), DbPluginPreferenceUserData::String { value: Some("value".to_string()) }).await.unwrap();
+
+        // new version changes type of "limit" and removes "removed"
+        let mut plugin = test_plugin("test-plugin");
+        plugin.preferences.insert("limit".to_string(), string_preference());
+        plugin.preferences.insert("greeting".to_string(), string_preference());
+        repository.save_plugin(plugin).await.unwrap();
+
+        let plugin = repository.get_plugin_by_id("test-plugin").await.unwrap();
+
+        let mut preference_ids = plugin.preferences_user_data.keys().cloned().collect::<Vec<_>>();
+        preference_ids.sort();
+
+        assert_eq!(preference_ids, vec!["greeting".to_string()]);
+        assert!(matches!(plugin.preferences_user_data.get("greeting"), Some(DbPluginPreferenceUserData::String { value: Some(value) }) if value == "hello"));
+    }
+
+    #[tokio::test]
+    async fn missing_plugin_and_entrypoint_are_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        repository.save_plugin(test_plugin("test-plugin")).await.unwrap();
+
+        let err = repository.get_plugin_by_id("missing-plugin").await.err().unwrap();
+        assert!(matches!(err.downcast_ref::<DataDbNotFoundError>(), Some(DataDbNotFoundError::Plugin { plugin_id }) if plugin_id == "missing-plugin"));
+
+        let err = repository.is_plugin_enabled("missing-plugin").await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<DataDbNotFoundError>(), Some(DataDbNotFoundError::Plugin { .. })));
+
+        let err = repository.get_entrypoint_by_id("test-plugin", "missing-entrypoint").await.err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<DataDbNotFoundError>(),
+            Some(DataDbNotFoundError::Entrypoint { plugin_id, entrypoint_id }) if plugin_id == "test-plugin" && entrypoint_id == "missing-entrypoint"
+        ));
+
+        assert!(repository.get_entrypoint_by_id("test-plugin", "command").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn counts_match_saved_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        assert_eq!(repository.count_plugins().await.unwrap(), 0);
+        assert_eq!(repository.count_entrypoints().await.unwrap(), 0);
+
+        let mut first_plugin = test_plugin("first-plugin");
+        first_plugin.entrypoints = vec![
+            test_entrypoint("command", DbPluginEntrypointType::Command),
+            test_entrypoint("view", DbPluginEntrypointType::View),
+        ];
+
+        let mut second_plugin = test_plugin("second-plugin");
+        second_plugin.enabled = false;
+
+        repository.save_plugin(first_plugin).await.unwrap();
+        repository.save_plugin(second_plugin).await.unwrap();
+
+        assert_eq!(repository.count_plugins().await.unwrap(), 2);
+        assert_eq!(repository.count_enabled_plugins().await.unwrap(), 1);
+        assert_eq!(repository.count_entrypoints().await.unwrap(), 3);
+    }
+
+    async fn count_rows(repository: &DataDbRepository, table: &str, plugin_id: &str) -> i64 {
+        let (count, ) = sqlx::query_as::<_, (i64, )>(&format!("SELECT COUNT(*) FROM {} WHERE plugin_id = ?1", table))
+            .bind(plugin_id)
+            .fetch_one(&repository.pool)
+            .await
+            .unwrap();
+
+        count
+    }
+
+    #[tokio::test]
+    async fn removing_plugin_removes_its_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        repository.save_plugin(test_plugin("test-plugin")).await.unwrap();
+        repository.save_view_state("test-plugin", "command", serde_json::json!({ "selected": 1 })).await.unwrap();
+        repository.mark_entrypoint_frecency("test-plugin", "command");
+        repository.flush_frecency().await.unwrap();
+
+        let tables = ["plugin_entrypoint", "plugin_asset_data", "plugin_entrypoint_view_state", "plugin_entrypoint_frecency_stats"];
+
+        for table in tables {
+            assert_eq!(count_rows(&repository, table, "test-plugin").await, 1, "table: {}", table);
+        }
+
+        repository.remove_plugin("test-plugin").await.unwrap();
+
+        for table in tables {
+            assert_eq!(count_rows(&repository, table, "test-plugin").await, 0, "table: {}", table);
+        }
+    }
+
+    fn string_value(value: &str) -> DbPluginPreferenceUserData {
+        DbPluginPreferenceUserData::String { value: Some(value.to_string()) }
+    }
+
+    #[tokio::test]
+    async fn preference_values_are_set_together() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        repository.save_plugin(test_plugin("test-plugin")).await.unwrap();
+
+        let values = (1..=5)
+            .map(|index| (format!("preference{}", index), string_value(&index.to_string())))
+            .collect::<HashMap<_, _>>();
+
+        repository.set_preference_values("test-plugin".to_string(), None, values).await.unwrap();
+
+        let user_data = repository.get_plugin_by_id("test-plugin").await.unwrap().preferences_user_data;
+
+        assert_eq!(user_data.len(), 5);
+
+        for index in 1..=5 {
+            assert!(matches!(user_data.get(&format!("preference{}", index)), Some(DbPluginPreferenceUserData::String { value: Some(value) }) if *value == index.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn preference_values_of_unknown_entrypoint_are_not_set() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        repository.save_plugin(test_plugin("test-plugin")).await.unwrap();
+
+        let values = HashMap::from([("preference".to_string(), string_value("value"))]);
+
+        let result = repository.set_preference_values("test-plugin".to_string(), Some("unknown".to_string()), values).await;
+
+        assert!(result.is_err());
+
+        let entrypoint = repository.get_entrypoint_by_id("test-plugin", "command").await.unwrap();
+
+        assert!(entrypoint.preferences_user_data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stale_pending_plugins_are_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(&dir).await;
+
+        let pending_plugin = |id: &str| DbWritePendingPlugin { id: id.to_string() };
+
+        // removed from config
+        repository.save_pending_plugin(pending_plugin("removed-plugin")).await.unwrap();
+        // installed, but pending row wasn't removed
+        repository.save_plugin(test_plugin("installed-plugin")).await.unwrap();
+        repository.save_pending_plugin(pending_plugin("installed-plugin")).await.unwrap();
+        // still being downloaded
+        repository.save_pending_plugin(pending_plugin("downloading-plugin")).await.unwrap();
+
+        let config_plugin_ids = vec!["installed-plugin".to_string(), "downloading-plugin".to_string()];
+
+        let mut pruned = repository.prune_pending_plugins(&config_plugin_ids).await.unwrap();
+        pruned.sort();
+
+        assert_eq!(pruned, vec!["installed-plugin".to_string(), "removed-plugin".to_string()]);
+
+        assert!(!repository.is_plugin_pending("removed-plugin").await.unwrap());
+        assert!(!repository.is_plugin_pending("installed-plugin").await.unwrap());
+        assert!(repository.is_plugin_pending("downloading-plugin").await.unwrap());
+    }
+}
@@ -4,24 +4,57 @@ use std::time::Duration;
 
 use common::model::{DownloadStatus, PluginId};
 
+use crate::plugins::data_db_repository::{DataDbRepository, db_download_status_from_str, db_download_status_to_str, DbDownloadStatus};
+
 pub struct DownloadStatusHolder {
-    running_downloads: Arc<Mutex<HashMap<PluginId, DownloadStatus>>>
+    running_downloads: Arc<Mutex<HashMap<PluginId, DownloadStatus>>>,
+    db_repository: DataDbRepository,
 }
 
 impl DownloadStatusHolder {
-    pub fn new() -> Self {
-        Self {
-            running_downloads: Arc::new(Mutex::new(HashMap::new()))
-        }
+    // statuses are persisted, so downloads interrupted by crash or restart are shown as failed on next start
+    pub async fn new(db_repository: DataDbRepository) -> anyhow::Result<Self> {
+        let running_downloads = db_repository.list_download_statuses()
+            .await?
+            .into_iter()
+            .map(|status| {
+                let plugin_id = PluginId::from_string(status.plugin_id);
+
+                let status = match db_download_status_from_str(&status.status) {
+                    DbDownloadStatus::InProgress => DownloadStatus::Failed { message: "Download was interrupted, please retry".to_owned() },
+                    DbDownloadStatus::Failed => DownloadStatus::Failed { message: status.message },
+                };
+
+                (plugin_id, status)
+            })
+            .collect();
+
+        // shown only once after restart, until user retries or restarts again
+        db_repository.remove_all_download_statuses()
+            .await?;
+
+        Ok(Self {
+            running_downloads: Arc::new(Mutex::new(running_downloads)),
+            db_repository,
+        })
     }
 
-    pub fn download_started(&self, plugin_id: PluginId) -> DownloadStatusGuard {
-        let mut running_downloads = self.running_downloads.lock().expect("lock is poisoned");
-        running_downloads.insert(plugin_id.clone(), DownloadStatus::InProgress);
-        DownloadStatusGuard {
+    // download is queued until there is a free download slot, see DownloadStatusGuard::download_started
+    pub async fn download_queued(&self, plugin_id: PluginId) -> DownloadStatusGuard {
+        {
+            let mut running_downloads = self.running_downloads.lock().expect("lock is poisoned");
+            running_downloads.insert(plugin_id.clone(), DownloadStatus::Pending);
+        }
+
+        let guard = DownloadStatusGuard {
             running_downloads: self.running_downloads.clone(),
+            db_repository: self.db_repository.clone(),
             id: plugin_id,
-        }
+        };
+
+        guard.persist(DbDownloadStatus::InProgress, "").await;
+
+        guard
     }
 
     pub fn download_status(&self) -> HashMap<PluginId, DownloadStatus> {
@@ -34,26 +67,52 @@ impl DownloadStatusHolder {
 
 pub struct DownloadStatusGuard {
     id: PluginId,
-    running_downloads: Arc<Mutex<HashMap<PluginId, DownloadStatus>>>
+    running_downloads: Arc<Mutex<HashMap<PluginId, DownloadStatus>>>,
+    db_repository: DataDbRepository,
 }
 
 impl DownloadStatusGuard {
-    pub fn download_finished(&self) {
+    pub fn download_started(&self) {
         let mut running_downloads = self.running_downloads.lock().expect("lock is poisoned");
 
-        running_downloads.insert(self.id.clone(), DownloadStatus::Done);
+        running_downloads.insert(self.id.clone(), DownloadStatus::InProgress);
+    }
+
+    pub async fn download_finished(&self) {
+        {
+            let mut running_downloads = self.running_downloads.lock().expect("lock is poisoned");
+
+            running_downloads.insert(self.id.clone(), DownloadStatus::Done);
+        }
+
+        if let Err(err) = self.db_repository.remove_download_status(&self.id.to_string()).await {
+            tracing::warn!("error occurred when removing download status of plugin {:?}: {:?}", self.id, err)
+        }
 
         self.drop_eventually()
     }
 
-    pub fn download_failed(&self, message: String) {
-        let mut running_downloads = self.running_downloads.lock().expect("lock is poisoned");
+    pub async fn download_failed(&self, message: String) {
+        {
+            let mut running_downloads = self.running_downloads.lock().expect("lock is poisoned");
+
+            running_downloads.insert(self.id.clone(), DownloadStatus::Failed { message: message.clone() });
+        }
 
-        running_downloads.insert(self.id.clone(), DownloadStatus::Failed { message });
+        self.persist(DbDownloadStatus::Failed, &message).await;
 
         self.drop_eventually()
     }
 
+    async fn persist(&self, status: DbDownloadStatus, message: &str) {
+        let result = self.db_repository.save_download_status(&self.id.to_string(), db_download_status_to_str(status), message)
+            .await;
+
+        if let Err(err) = result {
+            tracing::warn!("error occurred when saving download status of plugin {:?}: {:?}", self.id, err)
+        }
+    }
+
     fn drop_eventually(&self) {
         let running_downloads = self.running_downloads.clone();
         let plugin_id = self.id.clone();
@@ -65,3 +124,48 @@ impl DownloadStatusGuard {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn interrupted_download_is_reported_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        {
+            let holder = DownloadStatusHolder::new(repository.clone()).await.unwrap();
+
+            // neither finished nor failed, as if application was closed in the middle of download
+            let guard = holder.download_queued(plugin_id.clone()).await;
+            guard.download_started();
+        }
+
+        let holder = DownloadStatusHolder::new(repository.clone()).await.unwrap();
+
+        let status = holder.download_status();
+
+        assert!(matches!(status.get(&plugin_id), Some(DownloadStatus::Failed { message }) if message.contains("interrupted")));
+    }
+
+    #[tokio::test]
+    async fn finished_download_is_not_reported_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        {
+            let holder = DownloadStatusHolder::new(repository.clone()).await.unwrap();
+
+            let guard = holder.download_queued(plugin_id.clone()).await;
+            guard.download_started();
+            guard.download_finished().await;
+        }
+
+        let holder = DownloadStatusHolder::new(repository.clone()).await.unwrap();
+
+        assert!(holder.download_status().is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use common::model::{EntrypointId, PluginId};
+
+use crate::plugins::data_db_repository::DataDbRepository;
+
+#[derive(Default)]
+struct EnabledStates {
+    plugins: HashMap<PluginId, bool>,
+    entrypoints: HashMap<(PluginId, EntrypointId), bool>,
+}
+
+// enabled state of plugins and entrypoints, filled lazily from db
+// lock is held while db is read or written, so read that started before a write cannot put stale value into cache
+// shared with plugin runtimes, so search index rebuilds see the same state as toggles in settings
+#[derive(Clone)]
+pub struct EnabledStateCache {
+    db_repository: DataDbRepository,
+    states: Arc<Mutex<EnabledStates>>,
+}
+
+impl EnabledStateCache {
+    pub fn new(db_repository: DataDbRepository) -> Self {
+        Self {
+            db_repository,
+            states: Arc::new(Mutex::new(EnabledStates::default())),
+        }
+    }
+
+    pub async fn is_plugin_enabled(&self, plugin_id: &PluginId) -> anyhow::Result<bool> {
+        let mut states = self.states.lock().await;
+
+        if let Some(enabled) = states.plugins.get(plugin_id) {
+            return Ok(*enabled)
+        }
+
+        let enabled = self.db_repository.is_plugin_enabled(&plugin_id.to_string())
+            .await?;
+
+        states.plugins.insert(plugin_id.clone(), enabled);
+
+        Ok(enabled)
+    }
+
+    pub async fn is_entrypoint_enabled(&self, plugin_id: &PluginId, entrypoint_id: &EntrypointId) -> anyhow::Result<bool> {
+        let mut states = self.states.lock().await;
+
+        let key = (plugin_id.clone(), entrypoint_id.clone());
+
+        if let Some(enabled) = states.entrypoints.get(&key) {
+            return Ok(*enabled)
+        }
+
+        let enabled = self.db_repository.is_plugin_entrypoint_enabled(&plugin_id.to_string(), &entrypoint_id.to_string())
+            .await?;
+
+        states.entrypoints.insert(key, enabled);
+
+        Ok(enabled)
+    }
+
+    pub async fn set_plugin_enabled(&self, plugin_id: &PluginId, enabled: bool) -> anyhow::Result<()> {
+        let mut states = self.states.lock().await;
+
+        // removed first so failed write doesn't leave possibly outdated value
+        states.plugins.remove(plugin_id);
+
+        self.db_repository.set_plugin_enabled(&plugin_id.to_string(), enabled)
+            .await?;
+
+        states.plugins.insert(plugin_id.clone(), enabled);
+
+        Ok(())
+    }
+
+    pub async fn set_entrypoint_enabled(&self, plugin_id: &PluginId, entrypoint_id: &EntrypointId, enabled: bool) -> anyhow::Result<()> {
+        let mut states = self.states.lock().await;
+
+        let key = (plugin_id.clone(), entrypoint_id.clone());
+
+        states.entrypoints.remove(&key);
+
+        self.db_repository.set_plugin_entrypoint_enabled(&plugin_id.to_string(), &entrypoint_id.to_string(), enabled)
+            .await?;
+
+        states.entrypoints.insert(key, enabled);
+
+        Ok(())
+    }
+
+    // should be called after plugin is written to db by anything other than methods above, e.g. install or import
+    pub async fn invalidate_plugin(&self, plugin_id: &PluginId) {
+        let mut states = self.states.lock().await;
+
+        states.plugins.remove(plugin_id);
+        states.entrypoints.retain(|(entrypoint_plugin_id, _), _| entrypoint_plugin_id != plugin_id);
+    }
+}
@@ -0,0 +1,428 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+
+use common::model::PluginId;
+
+use crate::plugins::data_db_repository::{DataDbRepository, db_plugin_type_to_str, DbCode, DbPluginAction, DbPluginPermissions, DbPluginPreference, DbPluginPreferenceUserData, DbPluginType, DbWritePlugin, DbWritePluginAssetData, DbWritePluginEntrypoint};
+use crate::plugins::loader::PluginLoader;
+
+// increment when bundle format changes in a way older versions are not able to read
+const PLUGIN_EXPORT_FORMAT_VERSION: u32 = 1;
+const SETTINGS_EXPORT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct PluginExportBundle {
+    format_version: u32,
+    id: String,
+    name: String,
+    description: String,
+    js: HashMap<String, String>,
+    entrypoints: Vec<PluginExportEntrypoint>,
+    asset_data: Vec<PluginExportAssetData>,
+    permissions: DbPluginPermissions,
+    preferences: HashMap<String, DbPluginPreference>,
+    preferences_user_data: HashMap<String, DbPluginPreferenceUserData>,
+    #[serde(default)]
+    min_app_version: Option<u32>,
+    #[serde(default)]
+    max_app_version: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PluginExportEntrypoint {
+    id: String,
+    name: String,
+    description: String,
+    icon_path: Option<String>,
+    entrypoint_type: String,
+    preferences: HashMap<String, DbPluginPreference>,
+    preferences_user_data: HashMap<String, DbPluginPreferenceUserData>,
+    actions: Vec<DbPluginAction>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PluginExportAssetData {
+    path: String,
+    // json array of numbers is several times larger than the asset itself
+    #[serde(with="base64")]
+    data: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SettingsExport {
+    format_version: u32,
+    plugins: Vec<PluginSettingsExport>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PluginSettingsExport {
+    id: String,
+    enabled: bool,
+    preferences_user_data: HashMap<String, DbPluginPreferenceUserData>,
+    entrypoints: Vec<EntrypointSettingsExport>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EntrypointSettingsExport {
+    id: String,
+    enabled: bool,
+    preferences_user_data: HashMap<String, DbPluginPreferenceUserData>,
+}
+
+// none of the preference types hold secrets at the moment, so all user values are exported
+pub async fn export_plugin(db_repository: &DataDbRepository, plugin_id: &PluginId) -> anyhow::Result<Vec<u8>> {
+    let plugin_id = plugin_id.to_string();
+
+    let plugin = db_repository.get_plugin_by_id(&plugin_id)
+        .await?;
+
+    let js = PluginLoader::read_plugin_code(plugin.code)?;
+
+    let entrypoints = db_repository.get_entrypoints_by_plugin_id(&plugin_id)
+        .await?
+        .into_iter()
+        .map(|entrypoint| PluginExportEntrypoint {
+            id: entrypoint.id,
+            name: entrypoint.name,
+            description: entrypoint.description,
+            icon_path: entrypoint.icon_path,
+            entrypoint_type: entrypoint.entrypoint_type,
+            preferences: entrypoint.preferences,
+            preferences_user_data: entrypoint.preferences_user_data,
+            actions: entrypoint.actions,
+        })
+        .collect();
+
+    let asset_data = db_repository.list_asset_data(&plugin_id)
+        .await?
+        .into_iter()
+        .map(|asset| PluginExportAssetData {
+            path: asset.path,
+            data: asset.data,
+        })
+        .collect();
+
+    let bundle = PluginExportBundle {
+        format_version: PLUGIN_EXPORT_FORMAT_VERSION,
+        id: plugin.id,
+        name: plugin.name,
+        description: plugin.description,
+        js,
+        entrypoints,
+        asset_data,
+        permissions: plugin.permissions,
+        preferences: plugin.preferences,
+        preferences_user_data: plugin.preferences_user_data,
+        min_app_version: plugin.min_app_version,
+        max_app_version: plugin.max_app_version,
+    };
+
+    let bytes = serde_json::to_vec(&bundle)
+        .context("Unable to serialize plugin bundle")?;
+
+    Ok(bytes)
+}
+
+// imported plugin is saved as a normal plugin, even if it was exported from linked or config plugin
+pub async fn import_plugin(db_repository: &DataDbRepository, bytes: &[u8]) -> anyhow::Result<PluginId> {
+    let bundle: PluginExportBundle = serde_json::from_slice(bytes)
+        .context("Unable to read plugin bundle")?;
+
+    if bundle.format_version != PLUGIN_EXPORT_FORMAT_VERSION {
+        return Err(anyhow!("Plugin bundle format version {} is not supported, expected {}", bundle.format_version, PLUGIN_EXPORT_FORMAT_VERSION))
+    }
+
+    let plugin_id = PluginId::from_string(bundle.id.clone());
+
+    PluginLoader::validate_plugin_id(db_repository, &plugin_id, &DbPluginType::Normal)
+        .await?;
+
+    PluginLoader::check_app_version(bundle.min_app_version, bundle.max_app_version)?;
+
+    let mut entrypoints_user_data = vec![];
+
+    let entrypoints = bundle.entrypoints
+        .into_iter()
+        .map(|entrypoint| {
+            entrypoints_user_data.push((entrypoint.id.clone(), entrypoint.preferences_user_data));
+
+            DbWritePluginEntrypoint {
+                id: entrypoint.id,
+                name: entrypoint.name,
+                description: entrypoint.description,
+                icon_path: entrypoint.icon_path,
+                entrypoint_type: entrypoint.entrypoint_type,
+                preferences: entrypoint.preferences,
+                actions: entrypoint.actions,
+            }
+        })
+        .collect();
+
+    let asset_data = bundle.asset_data
+        .into_iter()
+        .map(|asset| DbWritePluginAssetData {
+            path: asset.path,
+            data: asset.data,
+        })
+        .collect();
+
+    db_repository.save_plugin(DbWritePlugin {
+        id: bundle.id.clone(),
+        name: bundle.name,
+        description: bundle.description,
+        enabled: false,
+        code: DbCode {
+            js: bundle.js,
+            linked_path: None,
+        },
+        entrypoints,
+        asset_data,
+        permissions: bundle.permissions,
+        plugin_type: db_plugin_type_to_str(DbPluginType::Normal).to_owned(),
+        preferences: bundle.preferences,
+        version: None,
+        min_app_version: bundle.min_app_version,
+        max_app_version: bundle.max_app_version,
+    }).await?;
+
+    for (preference_id, value) in bundle.preferences_user_data {
+        db_repository.set_preference_value(bundle.id.clone(), None, preference_id, value)
+            .await?;
+    }
+
+    for (entrypoint_id, preferences_user_data) in entrypoints_user_data {
+        for (preference_id, value) in preferences_user_data {
+            db_repository.set_preference_value(bundle.id.clone(), Some(entrypoint_id.clone()), preference_id, value)
+                .await?;
+        }
+    }
+
+    Ok(plugin_id)
+}
+
+// same as with plugin export, all preference user values are exported
+pub async fn export_settings(db_repository: &DataDbRepository) -> anyhow::Result<Vec<u8>> {
+    let plugins = db_repository.list_plugins_and_entrypoints()
+        .await?
+        .into_iter()
+        .map(|(plugin, entrypoints)| PluginSettingsExport {
+            id: plugin.id,
+            enabled: plugin.enabled,
+            preferences_user_data: plugin.preferences_user_data,
+            entrypoints: entrypoints.into_iter()
+                .map(|entrypoint| EntrypointSettingsExport {
+                    id: entrypoint.id,
+                    enabled: entrypoint.enabled,
+                    preferences_user_data: entrypoint.preferences_user_data,
+                })
+                .collect(),
+        })
+        .collect();
+
+    let settings = SettingsExport {
+        format_version: SETTINGS_EXPORT_FORMAT_VERSION,
+        plugins,
+    };
+
+    let bytes = serde_json::to_vec(&settings)
+        .context("Unable to serialize settings")?;
+
+    Ok(bytes)
+}
+
+// plugins and entrypoints which are not installed are skipped.
+// returns enabled state for each imported plugin, it is up to caller to start or stop them
+pub async fn import_settings(db_repository: &DataDbRepository, bytes: &[u8]) -> anyhow::Result<Vec<(PluginId, bool)>> {
+    let settings: SettingsExport = serde_json::from_slice(bytes)
+        .context("Unable to read settings")?;
+
+    if settings.format_version != SETTINGS_EXPORT_FORMAT_VERSION {
+        return Err(anyhow!("Settings format version {} is not supported, expected {}", settings.format_version, SETTINGS_EXPORT_FORMAT_VERSION))
+    }
+
+    let mut result = vec![];
+
+    for plugin in settings.plugins {
+        if db_repository.get_plugin_by_id_option(&plugin.id).await?.is_none() {
+            tracing::debug!(target = "plugin", "Plugin {:?} is not installed, skipping its settings", plugin.id);
+            continue;
+        }
+
+        db_repository.set_preference_values(plugin.id.clone(), None, plugin.preferences_user_data)
+            .await?;
+
+        let installed_entrypoints = db_repository.get_entrypoints_by_plugin_id(&plugin.id)
+            .await?
+            .into_iter()
+            .map(|entrypoint| entrypoint.id)
+            .collect::<HashSet<_>>();
+
+        for entrypoint in plugin.entrypoints {
+            if !installed_entrypoints.contains(&entrypoint.id) {
+                tracing::debug!(target = "plugin", "Entrypoint {:?} of plugin {:?} doesn't exist, skipping its settings", entrypoint.id, plugin.id);
+                continue;
+            }
+
+            db_repository.set_plugin_entrypoint_enabled(&plugin.id, &entrypoint.id, entrypoint.enabled)
+                .await?;
+
+            db_repository.set_preference_values(plugin.id.clone(), Some(entrypoint.id.clone()), entrypoint.preferences_user_data)
+                .await?;
+        }
+
+        result.push((PluginId::from_string(plugin.id), plugin.enabled));
+    }
+
+    Ok(result)
+}
+
+mod base64 {
+    use serde::{Serialize, Deserialize};
+    use serde::{Deserializer, Serializer};
+    use base64::Engine;
+
+    pub fn serialize<S: Serializer>(v: &Vec<u8>, s: S) -> Result<S::Ok, S::Error> {
+        let base64 = base64::engine::general_purpose::STANDARD.encode(v);
+        String::serialize(&base64, s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let base64 = String::deserialize(d)?;
+        base64::engine::general_purpose::STANDARD.decode(base64.as_bytes())
+            .map_err(|e| serde::de::Error::custom(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::plugins::data_db_repository::{DbPluginPermissionsExec, DbPluginPermissionsFileSystem};
+
+    use super::*;
+
+    const PLUGIN_ID: &str = "https://github.com/project-gauntlet/plugin";
+
+    async fn open_repository(dir: &Path) -> DataDbRepository {
+        DataDbRepository::open(&dir.join("data.db")).await.unwrap()
+    }
+
+    fn string_preference() -> HashMap<String, DbPluginPreference> {
+        HashMap::from([
+            ("greeting".to_string(), DbPluginPreference::String { name: Some("Greeting".to_string()), default: None, description: "".to_string() })
+        ])
+    }
+
+    fn test_plugin(plugin_id: &str) -> DbWritePlugin {
+        DbWritePlugin {
+            id: plugin_id.to_string(),
+            name: "Test Plugin".to_string(),
+            description: "Plugin to export".to_string(),
+            enabled: true,
+            code: DbCode { js: HashMap::from([("command".to_string(), "export default function command() {}".to_string())]), linked_path: None },
+            entrypoints: vec![
+                DbWritePluginEntrypoint {
+                    id: "command".to_string(),
+                    name: "Command".to_string(),
+                    description: "".to_string(),
+                    icon_path: None,
+                    entrypoint_type: "command".to_string(),
+                    preferences: string_preference(),
+                    actions: vec![],
+                }
+            ],
+            asset_data: vec![
+                DbWritePluginAssetData { path: "icon.png".to_string(), data: vec![1, 2, 3] }
+            ],
+            permissions: DbPluginPermissions {
+                environment: vec![],
+                network: vec![],
+                filesystem: DbPluginPermissionsFileSystem::default(),
+                exec: DbPluginPermissionsExec::default(),
+                system: vec![],
+                clipboard: vec![],
+                main_search_bar: vec![],
+                high_resolution_time: false,
+                list_entrypoints: false,
+                ffi: vec![],
+            },
+            plugin_type: db_plugin_type_to_str(DbPluginType::Normal).to_owned(),
+            preferences: string_preference(),
+            version: None,
+            min_app_version: None,
+            max_app_version: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn exported_plugin_is_imported_with_preferences() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source_repository = open_repository(source_dir.path()).await;
+
+        source_repository.save_plugin(test_plugin(PLUGIN_ID)).await.unwrap();
+
+        source_repository.set_preference_value(PLUGIN_ID.to_string(), None, "greeting".to_string(), DbPluginPreferenceUserData::String { value: Some("hello".to_string()) }).await.unwrap();
+        source_repository.set_preference_value(PLUGIN_ID.to_string(), Some("command".to_string()), "greeting".to_string(), DbPluginPreferenceUserData::String { value: Some("hi".to_string()) }).await.unwrap();
+
+        let bytes = export_plugin(&source_repository, &PluginId::from_string(PLUGIN_ID)).await.unwrap();
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target_repository = open_repository(target_dir.path()).await;
+
+        let plugin_id = import_plugin(&target_repository, &bytes).await.unwrap();
+
+        assert_eq!(plugin_id.to_string(), PLUGIN_ID);
+
+        let plugin = target_repository.get_plugin_by_id(PLUGIN_ID).await.unwrap();
+
+        assert_eq!(plugin.name, "Test Plugin");
+        assert_eq!(plugin.description, "Plugin to export");
+        assert_eq!(plugin.code.js.get("command").map(String::as_str), Some("export default function command() {}"));
+        assert!(matches!(plugin.preferences_user_data.get("greeting"), Some(DbPluginPreferenceUserData::String { value: Some(value) }) if value == "hello"));
+
+        let entrypoints = target_repository.get_entrypoints_by_plugin_id(PLUGIN_ID).await.unwrap();
+
+        assert_eq!(entrypoints.len(), 1);
+        assert!(matches!(entrypoints[0].preferences_user_data.get("greeting"), Some(DbPluginPreferenceUserData::String { value: Some(value) }) if value == "hi"));
+
+        let asset_data = target_repository.list_asset_data(PLUGIN_ID).await.unwrap();
+
+        assert_eq!(asset_data.len(), 1);
+        assert_eq!(asset_data[0].data, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn exported_settings_are_imported_for_installed_plugins() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source_repository = open_repository(source_dir.path()).await;
+
+        source_repository.save_plugin(test_plugin(PLUGIN_ID)).await.unwrap();
+        source_repository.save_plugin(test_plugin("https://github.com/project-gauntlet/not-installed")).await.unwrap();
+        source_repository.set_plugin_enabled(PLUGIN_ID, false).await.unwrap();
+        source_repository.set_plugin_entrypoint_enabled(PLUGIN_ID, "command", false).await.unwrap();
+        source_repository.set_preference_value(PLUGIN_ID.to_string(), None, "greeting".to_string(), DbPluginPreferenceUserData::String { value: Some("hello".to_string()) }).await.unwrap();
+        source_repository.set_preference_value(PLUGIN_ID.to_string(), Some("command".to_string()), "greeting".to_string(), DbPluginPreferenceUserData::String { value: Some("hi".to_string()) }).await.unwrap();
+
+        let bytes = export_settings(&source_repository).await.unwrap();
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target_repository = open_repository(target_dir.path()).await;
+
+        target_repository.save_plugin(test_plugin(PLUGIN_ID)).await.unwrap();
+
+        let imported = import_settings(&target_repository, &bytes).await.unwrap();
+
+        assert_eq!(imported, vec![(PluginId::from_string(PLUGIN_ID), false)]);
+
+        let plugin = target_repository.get_plugin_by_id(PLUGIN_ID).await.unwrap();
+        assert!(matches!(plugin.preferences_user_data.get("greeting"), Some(DbPluginPreferenceUserData::String { value: Some(value) }) if value == "hello"));
+
+        let entrypoint = target_repository.get_entrypoint_by_id(PLUGIN_ID, "command").await.unwrap();
+        assert!(!entrypoint.enabled);
+        assert!(matches!(entrypoint.preferences_user_data.get("greeting"), Some(DbPluginPreferenceUserData::String { value: Some(value) }) if value == "hi"));
+
+        assert!(target_repository.get_plugin_by_id_option("https://github.com/project-gauntlet/not-installed").await.unwrap().is_none());
+    }
+}
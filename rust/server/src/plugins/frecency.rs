@@ -1,7 +1,13 @@
 
 // shamelessly stolen from MIT licensed https://github.com/camdencheek/fre
 
-use std::time::SystemTime;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use crate::plugins::data_db_repository::DataDbRepository;
+
+const FRECENCY_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
 
 pub struct FrecencyMetaParams {
     pub reference_time: f64,
@@ -50,6 +56,13 @@ impl FrecencyItemStats {
         self.update_last_access(current_time_secs());
     }
 
+    /// Apply several uses at once, decay between them is negligible compared to half life
+    pub fn mark_used_times(&mut self, times: i32, last_accessed: f64) {
+        self.update_frecency(times as f64);
+        self.update_num_accesses(times);
+        self.update_last_access(last_accessed);
+    }
+
     /// Change the half life of the item, maintaining the same frecency
     pub fn set_half_life(&mut self, half_life: f64) {
         let secs = current_time_secs();
@@ -100,6 +113,97 @@ impl FrecencyItemStats {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct PendingFrecencyMarks {
+    pub times: i32,
+    pub last_accessed: f64,
+}
+
+// entrypoint usages are accumulated in memory and written to db in batches
+#[derive(Clone)]
+pub struct FrecencyWriteBuffer {
+    pending: Arc<Mutex<HashMap<(String, String), PendingFrecencyMarks>>>,
+    // held for the whole flush, so that stats removed by user are not written back by flush that is in flight
+    write_lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl FrecencyWriteBuffer {
+    pub fn new() -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            write_lock: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    pub async fn lock_writes(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.write_lock.lock().await
+    }
+
+    pub fn mark(&self, plugin_id: &str, entrypoint_id: &str) {
+        let mut pending = self.pending.lock().expect("lock is poisoned");
+
+        let marks = pending.entry((plugin_id.to_owned(), entrypoint_id.to_owned()))
+            .or_insert(PendingFrecencyMarks { times: 0, last_accessed: 0.0 });
+
+        marks.times += 1;
+        marks.last_accessed = current_time_secs();
+    }
+
+    pub fn pending_for_plugin(&self, plugin_id: &str) -> HashMap<String, PendingFrecencyMarks> {
+        let pending = self.pending.lock().expect("lock is poisoned");
+
+        pending.iter()
+            .filter(|((pending_plugin_id, _), _)| pending_plugin_id == plugin_id)
+            .map(|((_, entrypoint_id), marks)| (entrypoint_id.clone(), marks.clone()))
+            .collect()
+    }
+
+    pub fn take(&self) -> HashMap<(String, String), PendingFrecencyMarks> {
+        let mut pending = self.pending.lock().expect("lock is poisoned");
+
+        std::mem::take(&mut *pending)
+    }
+
+    pub fn forget(&self, plugin_id: &str, entrypoint_id: &str) {
+        let mut pending = self.pending.lock().expect("lock is poisoned");
+
+        pending.remove(&(plugin_id.to_owned(), entrypoint_id.to_owned()));
+    }
+
+    pub fn clear(&self) {
+        let mut pending = self.pending.lock().expect("lock is poisoned");
+
+        pending.clear();
+    }
+
+    // used if writing failed, so marks are not lost and retried on next flush
+    pub fn restore(&self, marks: HashMap<(String, String), PendingFrecencyMarks>) {
+        let mut pending = self.pending.lock().expect("lock is poisoned");
+
+        for (key, restored) in marks {
+            let marks = pending.entry(key)
+                .or_insert(PendingFrecencyMarks { times: 0, last_accessed: 0.0 });
+
+            marks.times += restored.times;
+            marks.last_accessed = marks.last_accessed.max(restored.last_accessed);
+        }
+    }
+}
+
+pub fn start_frecency_flush(db_repository: DataDbRepository) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(FRECENCY_FLUSH_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = db_repository.flush_frecency().await {
+                tracing::warn!("error occurred when writing frecency to db {:?}", err)
+            }
+        }
+    });
+}
+
 fn current_time_secs() -> f64 {
     SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -0,0 +1,224 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use deno_core::error::{custom_error, get_custom_error_class};
+use deno_core::{op, OpState};
+
+use crate::plugins::audit_log::PluginAuditLog;
+use crate::plugins::js::permissions::{is_path_allowed, PermissionDecisionCache};
+use crate::plugins::js::PluginData;
+
+#[op]
+async fn op_fs_read(state: Rc<RefCell<OpState>>, path: String) -> anyhow::Result<Vec<u8>> {
+    let path = check_fs_path(&state, &path, FsAccess::Read)?;
+
+    Ok(tokio::fs::read(path).await?)
+}
+
+#[op]
+async fn op_fs_write(state: Rc<RefCell<OpState>>, path: String, bytes: Vec<u8>) -> anyhow::Result<()> {
+    let path = check_fs_path(&state, &path, FsAccess::Write)?;
+
+    Ok(tokio::fs::write(path, bytes).await?)
+}
+
+#[derive(Clone, Copy)]
+enum FsAccess {
+    Read,
+    Write,
+}
+
+impl FsAccess {
+    fn permission_kind(&self) -> &'static str {
+        match self {
+            FsAccess::Read => "filesystem-read",
+            FsAccess::Write => "filesystem-write",
+        }
+    }
+}
+
+// permission errors are thrown as Deno.errors.PermissionDenied,
+// io errors keep their own class, e.g. Deno.errors.NotFound
+fn check_fs_path(state: &Rc<RefCell<OpState>>, path: &str, access: FsAccess) -> anyhow::Result<PathBuf> {
+    let state = state.borrow();
+
+    let plugin_data = state.borrow::<PluginData>();
+
+    let mut granted_paths = match access {
+        FsAccess::Read => plugin_data.permissions().fs_read_allowlist.clone(),
+        FsAccess::Write => plugin_data.permissions().fs_write_allowlist.clone(),
+    };
+
+    granted_paths.extend(state.borrow::<PermissionDecisionCache>().granted_paths(access.permission_kind()));
+
+    let result = check_granted_fs_path(&granted_paths, path, access);
+
+    let audit_log = state.borrow::<PluginAuditLog>();
+
+    match &result {
+        Ok(path) => {
+            // reads are not recorded, they are too frequent and don't change anything
+            if let FsAccess::Write = access {
+                audit_log.record(&plugin_data.plugin_id(), access.permission_kind(), &path.to_string_lossy());
+            }
+        }
+        Err(err) => {
+            if get_custom_error_class(err) == Some("PermissionDenied") {
+                audit_log.record(&plugin_data.plugin_id(), &format!("{}-denied", access.permission_kind()), path);
+            }
+        }
+    }
+
+    result
+}
+
+fn check_granted_fs_path(granted_paths: &[PathBuf], path: &str, access: FsAccess) -> anyhow::Result<PathBuf> {
+    let path = Path::new(path);
+
+    if !path.is_absolute() {
+        return Err(anyhow!("Path should be absolute: {}", path.display()));
+    }
+
+    let path = match access {
+        FsAccess::Read => path.to_path_buf(),
+        FsAccess::Write => resolve_write_path(path)?,
+    };
+
+    if !is_path_allowed(granted_paths, &path) {
+        return Err(custom_error("PermissionDenied", format!("Plugin doesn't have {} permission for path: {}", access.permission_kind(), path.display())));
+    }
+
+    Ok(path)
+}
+
+// file being written may not exist yet, so parent directory is resolved instead
+// to make sure symlinked directory cannot be used to write outside of granted directory
+fn resolve_write_path(path: &Path) -> anyhow::Result<PathBuf> {
+    let file_name = path.file_name()
+        .ok_or_else(|| anyhow!("Path doesn't point to a file: {}", path.display()))?;
+
+    let parent = path.parent()
+        .ok_or_else(|| anyhow!("Path doesn't point to a file: {}", path.display()))?;
+
+    let path = std::fs::canonicalize(parent)?.join(file_name);
+
+    // final component is not resolved above and write would follow it if it is a symlink
+    if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+        if metadata.file_type().is_symlink() {
+            return Err(custom_error("PermissionDenied", format!("Writing through symlink is not allowed: {}", path.display())));
+        }
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path_in(dir: &Path, file_name: &str) -> String {
+        dir.join(file_name).to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn file_inside_granted_dir_is_written_and_read() {
+        let granted_dir = tempfile::tempdir().unwrap();
+        let granted_paths = vec![granted_dir.path().to_path_buf()];
+
+        let file_path = path_in(granted_dir.path(), "file.txt");
+
+        let write_path = check_granted_fs_path(&granted_paths, &file_path, FsAccess::Write).unwrap();
+        std::fs::write(write_path, b"content").unwrap();
+
+        let read_path = check_granted_fs_path(&granted_paths, &file_path, FsAccess::Read).unwrap();
+        assert_eq!(std::fs::read(read_path).unwrap(), b"content");
+    }
+
+    #[test]
+    fn file_outside_granted_dir_is_rejected() {
+        let granted_dir = tempfile::tempdir().unwrap();
+        let other_dir = tempfile::tempdir().unwrap();
+        let granted_paths = vec![granted_dir.path().to_path_buf()];
+
+        std::fs::write(other_dir.path().join("file.txt"), b"content").unwrap();
+
+        let file_path = path_in(other_dir.path(), "file.txt");
+
+        for access in [FsAccess::Read, FsAccess::Write] {
+            let err = check_granted_fs_path(&granted_paths, &file_path, access).unwrap_err();
+
+            assert_eq!(get_custom_error_class(&err), Some("PermissionDenied"));
+        }
+
+        let escaping_path = path_in(granted_dir.path(), "../escaped.txt");
+
+        let err = check_granted_fs_path(&granted_paths, &escaping_path, FsAccess::Write).unwrap_err();
+        assert_eq!(get_custom_error_class(&err), Some("PermissionDenied"));
+    }
+
+    #[test]
+    fn relative_path_is_rejected() {
+        let granted_dir = tempfile::tempdir().unwrap();
+        let granted_paths = vec![granted_dir.path().to_path_buf()];
+
+        assert!(check_granted_fs_path(&granted_paths, "file.txt", FsAccess::Read).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_path_resolves_symlinked_parent_directory() {
+        let allowed = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+
+        let linked_dir = allowed.path().join("linked");
+        std::os::unix::fs::symlink(outside.path(), &linked_dir).unwrap();
+
+        let resolved = resolve_write_path(&linked_dir.join("file.txt")).unwrap();
+
+        let granted_paths = vec![allowed.path().to_path_buf()];
+
+        assert_eq!(resolved, std::fs::canonicalize(outside.path()).unwrap().join("file.txt"));
+        assert!(!is_path_allowed(&granted_paths, &resolved));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_path_rejects_symlink_pointing_outside_of_allowed_directory() {
+        let allowed = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+
+        let target = outside.path().join("target.txt");
+        std::fs::write(&target, "original").unwrap();
+
+        let link = allowed.path().join("link.txt");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        assert!(resolve_write_path(&link).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_path_rejects_dangling_symlink() {
+        let allowed = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+
+        let link = allowed.path().join("link.txt");
+        std::os::unix::fs::symlink(outside.path().join("not-yet-created.txt"), &link).unwrap();
+
+        assert!(resolve_write_path(&link).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_path_allows_new_file_in_allowed_directory() {
+        let allowed = tempfile::tempdir().unwrap();
+
+        let resolved = resolve_write_path(&allowed.path().join("new.txt")).unwrap();
+
+        let granted_paths = vec![allowed.path().to_path_buf()];
+
+        assert!(is_path_allowed(&granted_paths, &resolved));
+    }
+}
@@ -1,64 +1,134 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 use deno_core::{op, OpState};
+use common::model::{PluginId, PluginLogLevel};
 use crate::plugins::js::PluginData;
+use crate::plugins::log_buffer::{log_level_from_str, PluginLogBuffer};
 
 #[op]
 fn op_log_trace(state: Rc<RefCell<OpState>>, target: String, message: String) -> anyhow::Result<()> {
-    let plugin_id = state.borrow()
-        .borrow::<PluginData>()
-        .plugin_id()
-        .to_string();
+    let (plugin_id, log_buffer) = plugin_log_context(&state);
+
+    log_buffer.push(&plugin_id, PluginLogLevel::Trace, &message);
 
-    tracing::trace!(target = target, plugin_id = plugin_id, message);
+    tracing::trace!(target = target, plugin_id = plugin_id.to_string(), message);
 
     Ok(())
 }
 
 #[op]
 fn op_log_debug(state: Rc<RefCell<OpState>>, target: String, message: String) -> anyhow::Result<()> {
-    let plugin_id = state.borrow()
-        .borrow::<PluginData>()
-        .plugin_id()
-        .to_string();
+    let (plugin_id, log_buffer) = plugin_log_context(&state);
 
-    tracing::debug!(target = target, plugin_id = plugin_id, message);
+    log_buffer.push(&plugin_id, PluginLogLevel::Debug, &message);
+
+    tracing::debug!(target = target, plugin_id = plugin_id.to_string(), message);
 
     Ok(())
 }
 
 #[op]
 fn op_log_info(state: Rc<RefCell<OpState>>, target: String, message: String) -> anyhow::Result<()> {
-    let plugin_id = state.borrow()
-        .borrow::<PluginData>()
-        .plugin_id()
-        .to_string();
+    let (plugin_id, log_buffer) = plugin_log_context(&state);
 
-    tracing::info!(target = target, plugin_id = plugin_id, message);
+    log_buffer.push(&plugin_id, PluginLogLevel::Info, &message);
+
+    tracing::info!(target = target, plugin_id = plugin_id.to_string(), message);
 
     Ok(())
 }
 
 #[op]
 fn op_log_warn(state: Rc<RefCell<OpState>>, target: String, message: String) -> anyhow::Result<()> {
-    let plugin_id = state.borrow()
-        .borrow::<PluginData>()
-        .plugin_id()
-        .to_string();
+    let (plugin_id, log_buffer) = plugin_log_context(&state);
+
+    log_buffer.push(&plugin_id, PluginLogLevel::Warn, &message);
 
-    tracing::warn!(target = target, plugin_id = plugin_id, message);
+    tracing::warn!(target = target, plugin_id = plugin_id.to_string(), message);
 
     Ok(())
 }
 
 #[op]
 fn op_log_error(state: Rc<RefCell<OpState>>, target: String, message: String) -> anyhow::Result<()> {
-    let plugin_id = state.borrow()
-        .borrow::<PluginData>()
-        .plugin_id()
-        .to_string();
+    let (plugin_id, log_buffer) = plugin_log_context(&state);
+
+    log_buffer.push(&plugin_id, PluginLogLevel::Error, &message);
 
-    tracing::error!(target = target, plugin_id = plugin_id, message);
+    tracing::error!(target = target, plugin_id = plugin_id.to_string(), message);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+// console output of plugin code
+#[op]
+fn op_console_log(state: Rc<RefCell<OpState>>, level: String, message: String) -> anyhow::Result<()> {
+    let (plugin_id, log_buffer) = plugin_log_context(&state);
+
+    console_log(&plugin_id, &log_buffer, &level, message);
+
+    Ok(())
+}
+
+fn console_log(plugin_id: &PluginId, log_buffer: &PluginLogBuffer, level: &str, message: String) {
+    let level = log_level_from_str(level)
+        .unwrap_or(PluginLogLevel::Info);
+
+    let plugin_id_str = plugin_id.to_string();
+
+    match level {
+        PluginLogLevel::Trace => tracing::trace!(target = "plugin_console", plugin_id = plugin_id_str, message),
+        PluginLogLevel::Debug => tracing::debug!(target = "plugin_console", plugin_id = plugin_id_str, message),
+        PluginLogLevel::Info => tracing::info!(target = "plugin_console", plugin_id = plugin_id_str, message),
+        PluginLogLevel::Warn => tracing::warn!(target = "plugin_console", plugin_id = plugin_id_str, message),
+        PluginLogLevel::Error => tracing::error!(target = "plugin_console", plugin_id = plugin_id_str, message),
+    }
+
+    log_buffer.push(plugin_id, level, message);
+}
+
+fn plugin_log_context(state: &Rc<RefCell<OpState>>) -> (PluginId, PluginLogBuffer) {
+    let state = state.borrow();
+
+    let plugin_id = state
+        .borrow::<PluginData>()
+        .plugin_id();
+
+    let log_buffer = state
+        .borrow::<PluginLogBuffer>()
+        .clone();
+
+    (plugin_id, log_buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn console_output_is_buffered_with_its_level() {
+        let log_buffer = PluginLogBuffer::new();
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        console_log(&plugin_id, &log_buffer, "warn", "warning message".to_string());
+        console_log(&plugin_id, &log_buffer, "log", "log message".to_string());
+
+        let lines = log_buffer.lines(&plugin_id, 10);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].message, "warning message");
+        assert_eq!(lines[0].level, PluginLogLevel::Warn);
+        assert_eq!(lines[1].message, "log message");
+        assert_eq!(lines[1].level, PluginLogLevel::Info);
+    }
+
+    #[test]
+    fn unknown_console_level_is_buffered_as_info() {
+        let log_buffer = PluginLogBuffer::new();
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        console_log(&plugin_id, &log_buffer, "table", "message".to_string());
+
+        assert_eq!(log_buffer.lines(&plugin_id, 10)[0].level, PluginLogLevel::Info);
+    }
+}
@@ -1,5 +1,5 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fs::File;
 use std::hash::Hash;
 use std::net::SocketAddr;
@@ -25,10 +25,11 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use tokio::net::TcpStream;
+use tokio::sync::broadcast::error::RecvError;
 use tokio_util::sync::CancellationToken;
 
 use common::dirs::Dirs;
-use common::model::{EntrypointId, KeyboardEventOrigin, PhysicalKey, PluginId, SearchResultEntrypointType, UiPropertyValue, UiRenderLocation, UiWidget, UiWidgetId};
+use common::model::{EntrypointId, KeyboardEventOrigin, PhysicalKey, PluginId, PluginLogLevel, PluginResourceUsage, SearchResultEntrypointType, UiPropertyValue, UiRenderLocation, UiWidget, UiWidgetId};
 use common::rpc::frontend_api::FrontendApi;
 use component_model::{create_component_model, Children, Component, Property, PropertyType, SharedType};
 
@@ -36,18 +37,25 @@ use crate::model::{IntermediateUiEvent, JsUiEvent, JsUiPropertyValue, JsUiRender
 use crate::plugins::applications::{get_apps, DesktopEntry};
 use crate::plugins::data_db_repository::{db_entrypoint_from_str, DataDbRepository, DbPluginClipboardPermissions, DbPluginEntrypointType, DbPluginPreference, DbPluginPreferenceUserData, DbReadPlugin, DbReadPluginEntrypoint};
 use crate::plugins::icon_cache::IconCache;
+use crate::plugins::log_buffer::PluginLogBuffer;
+use crate::plugins::audit_log::PluginAuditLog;
+use crate::plugins::enabled_cache::EnabledStateCache;
 use crate::plugins::js::assets::{asset_data, asset_data_blocking};
 use crate::plugins::js::clipboard::{clipboard_clear, clipboard_read, clipboard_read_text, clipboard_write, clipboard_write_text};
 use crate::plugins::js::command_generators::get_command_generator_entrypoint_ids;
-use crate::plugins::js::logs::{op_log_debug, op_log_error, op_log_info, op_log_trace, op_log_warn};
-use crate::plugins::js::permissions::{permissions_to_deno, PluginPermissions, PluginPermissionsClipboard};
+use crate::plugins::js::logs::{op_console_log, op_log_debug, op_log_error, op_log_info, op_log_trace, op_log_warn};
+use crate::plugins::js::permissions::{op_request_permission, permissions_to_deno, resolve_exec_allowlist, resolve_ffi_allowlist, resolve_filesystem_allowlist, PermissionDecisionCache, PluginPermissions, PluginPermissionsClipboard};
 use crate::plugins::js::plugins::applications::{list_applications, open_application};
 use crate::plugins::js::plugins::numbat::{run_numbat, NumbatContext};
-use crate::plugins::js::plugins::settings::open_settings;
+use crate::plugins::js::plugins::settings::{open_settings, open_settings_preferences, search_all_preferences};
 use crate::plugins::js::preferences::{entrypoint_preferences_required, get_entrypoint_preferences, get_plugin_preferences, plugin_preferences_required};
-use crate::plugins::js::search::reload_search_index;
-use crate::plugins::js::ui::{clear_inline_view, fetch_action_id_for_shortcut, op_component_model, op_inline_view_endpoint_id, op_react_replace_view, show_hud, show_plugin_error_view, show_preferences_required_view};
-use crate::plugins::run_status::RunStatusGuard;
+use crate::plugins::js::search::{op_list_entrypoints, reload_search_index};
+use crate::plugins::js::view_state::{op_load_view_state, op_save_view_state};
+use crate::plugins::js::fs::{op_fs_read, op_fs_write};
+use crate::plugins::js::network::{op_fetch, op_fetch_stream, op_fetch_stream_close, op_fetch_stream_read, FetchStreams};
+use crate::plugins::js::system::{op_ffi_resolve_library, op_get_env, op_launch_application, op_performance_now, op_run_subprocess, op_system_info, TimeOrigin};
+use crate::plugins::js::ui::{clear_inline_view, fetch_action_id_for_shortcut, op_component_model, op_inline_view_endpoint_id, op_react_replace_view, op_widget_id_epoch_start, show_hud, show_inline_answer, show_plugin_error_view, show_preferences_required_view, ViewOpenState, WidgetIdEpoch};
+use crate::plugins::run_status::{PluginHealthReporter, PluginResourceUsageReporter, RunStatusGuard};
 use crate::search::{SearchIndex, SearchIndexItem};
 
 mod ui;
@@ -58,6 +66,10 @@ mod preferences;
 mod search;
 mod command_generators;
 mod clipboard;
+mod system;
+mod view_state;
+mod network;
+mod fs;
 pub mod permissions;
 
 pub struct PluginRuntimeData {
@@ -74,6 +86,10 @@ pub struct PluginRuntimeData {
     pub icon_cache: IconCache,
     pub frontend_api: FrontendApi,
     pub dirs: Dirs,
+    pub log_buffer: PluginLogBuffer,
+    pub audit_log: PluginAuditLog,
+    pub enabled_state_cache: EnabledStateCache,
+    pub locale: Option<String>,
 }
 
 pub struct PluginCode {
@@ -83,6 +99,15 @@ pub struct PluginCode {
 #[derive(Clone, Debug)]
 pub struct PluginRuntimePermissions {
     pub clipboard: Vec<PluginPermissionsClipboard>,
+    pub exec_allowlist: Vec<PathBuf>,
+    pub system: Vec<String>,
+    pub environment: Vec<String>,
+    pub high_resolution_time: bool,
+    pub list_entrypoints: bool,
+    pub ffi_allowlist: Vec<PathBuf>,
+    pub network: Vec<String>,
+    pub fs_read_allowlist: Vec<PathBuf>,
+    pub fs_write_allowlist: Vec<PathBuf>,
 }
 
 #[derive(Clone, Debug)]
@@ -104,6 +129,11 @@ pub enum OnePluginCommandData {
     CloseView,
     RunCommand {
         entrypoint_id: String,
+        action_id: Option<String>,
+    },
+    RunFallbackCommand {
+        entrypoint_id: String,
+        text: String,
     },
     RunGeneratedCommand {
         entrypoint_id: String,
@@ -131,20 +161,35 @@ pub enum OnePluginCommandData {
 pub enum AllPluginCommandData {
     OpenInlineView {
         text: String
-    }
+    },
+    Ping,
 }
 
+const RESOURCE_USAGE_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
 pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: RunStatusGuard) -> anyhow::Result<()> {
     let component_model = create_component_model();
 
     let mut command_receiver = data.command_receiver;
+    let command_plugin_id = data.id.clone();
     let command_stream = async_stream::stream! {
         loop {
-            yield command_receiver.recv().await.unwrap();
+            match command_receiver.recv().await {
+                Ok(command) => yield command,
+                // channel is bounded, if plugin is too slow to keep up the oldest commands are dropped
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(target = "plugin", "Plugin {} is not keeping up with events, {} events were dropped", command_plugin_id, skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
         }
     };
 
+    let widget_id_epoch = WidgetIdEpoch::next();
+    let view_open_state = ViewOpenState::new();
+
     let plugin_id = data.id.clone();
+    let stream_view_open_state = view_open_state.clone();
     let event_stream = command_stream
         .filter_map(move |command: PluginCommand| {
             let plugin_id = plugin_id.clone();
@@ -156,16 +201,27 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
                     } else {
                         match data {
                             OnePluginCommandData::RenderView { entrypoint_id } => {
+                                stream_view_open_state.open();
+
                                 Some(IntermediateUiEvent::OpenView {
                                     entrypoint_id,
                                 })
                             }
                             OnePluginCommandData::CloseView => {
+                                stream_view_open_state.close();
+
                                 Some(IntermediateUiEvent::CloseView)
                             }
-                            OnePluginCommandData::RunCommand { entrypoint_id } => {
+                            OnePluginCommandData::RunCommand { entrypoint_id, action_id } => {
                                 Some(IntermediateUiEvent::RunCommand {
                                     entrypoint_id,
+                                    action_id,
+                                })
+                            }
+                            OnePluginCommandData::RunFallbackCommand { entrypoint_id, text } => {
+                                Some(IntermediateUiEvent::RunFallbackCommand {
+                                    entrypoint_id,
+                                    text,
                                 })
                             }
                             OnePluginCommandData::RunGeneratedCommand { entrypoint_id, action_index } => {
@@ -175,11 +231,16 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
                                 })
                             }
                             OnePluginCommandData::HandleViewEvent { widget_id, event_name, event_arguments } => {
-                                Some(IntermediateUiEvent::HandleViewEvent {
-                                    widget_id,
-                                    event_name,
-                                    event_arguments,
-                                })
+                                if widget_id_epoch.contains(widget_id) {
+                                    Some(IntermediateUiEvent::HandleViewEvent {
+                                        widget_id,
+                                        event_name,
+                                        event_arguments,
+                                    })
+                                } else {
+                                    tracing::debug!(target = "plugin", "dropping event {:?} for widget {:?} rendered before plugin reload", event_name, widget_id);
+                                    None
+                                }
                             }
                             OnePluginCommandData::HandleKeyboardEvent { entrypoint_id, origin, key, modifier_shift, modifier_control, modifier_alt, modifier_meta } => {
                                 Some(IntermediateUiEvent::HandleKeyboardEvent {
@@ -206,6 +267,9 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
                         AllPluginCommandData::OpenInlineView { text } => {
                             Some(IntermediateUiEvent::OpenInlineView { text })
                         }
+                        AllPluginCommandData::Ping => {
+                            Some(IntermediateUiEvent::Ping)
+                        }
                     }
                 }
             };
@@ -221,7 +285,10 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
     let plugin_uuid = data.uuid.clone();
     let plugin_id = data.id.clone();
 
-    let thread_fn = move || {
+    let health_reporter = run_status_guard.health_reporter();
+    let resource_usage_reporter = run_status_guard.resource_usage_reporter();
+
+    let thread_fn = in_current_span(move || {
         let plugin_id = data.id.clone();
 
         tokio::runtime::Builder::new_current_thread()
@@ -230,6 +297,7 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
             .expect("unable to start tokio runtime for plugin")
             .block_on({
                 let plugin_id = data.id.clone();
+                let log_buffer = data.log_buffer.clone();
 
                 async move {
                     tokio::select! {
@@ -247,18 +315,30 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
                                      data.permissions,
                                      data.inline_view_entrypoint_id,
                                      event_stream,
+                                     health_reporter,
+                                     resource_usage_reporter,
                                      data.frontend_api,
                                      component_model,
                                      data.db_repository,
                                      data.search_index,
                                      data.icon_cache,
-                                     data.dirs
+                                     data.dirs,
+                                     data.log_buffer,
+                                     data.audit_log,
+                                     data.enabled_state_cache,
+                                     widget_id_epoch,
+                                     view_open_state,
+                                     data.locale
                                  ).await
                             })
                         } => {
                             if let Err(err) = result {
+                                log_buffer.push(&plugin_id, PluginLogLevel::Error, format!("Plugin runtime has failed: {:#}", err));
+
                                 tracing::error!(target = "plugin", "Plugin runtime has failed {:?} - {:?}", plugin_id, err)
                             } else {
+                                log_buffer.push(&plugin_id, PluginLogLevel::Error, "Plugin runtime has stopped unexpectedly");
+
                                 tracing::error!(target = "plugin", "Plugin runtime has stopped unexpectedly {:?}", plugin_id)
                             }
                         }
@@ -269,7 +349,7 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
         if let Err(err) = cache.clear_plugin_icon_cache_dir(&plugin_uuid) {
             tracing::error!(target = "plugin", "plugin {:?} unable to cleanup icon cache {:?}", plugin_id, err)
         }
-    };
+    });
 
     std::thread::Builder::new()
         .name("plugin-js-thread".into())
@@ -279,6 +359,18 @@ pub async fn start_plugin_runtime(data: PluginRuntimeData, run_status_guard: Run
     Ok(())
 }
 
+// span of the plugin lifecycle operation which started the runtime carries plugin_id field,
+// runtime runs in separate thread so the span has to be entered there explicitly
+fn in_current_span<R>(f: impl FnOnce() -> R) -> impl FnOnce() -> R {
+    let span = tracing::Span::current();
+
+    move || {
+        let _span_guard = span.enter();
+
+        f()
+    }
+}
+
 async fn start_js_runtime(
     plugin_id: PluginId,
     plugin_uuid: String,
@@ -288,12 +380,20 @@ async fn start_js_runtime(
     permissions: PluginPermissions,
     inline_view_entrypoint_id: Option<String>,
     event_stream: Pin<Box<dyn Stream<Item=IntermediateUiEvent>>>,
+    health_reporter: PluginHealthReporter,
+    resource_usage_reporter: PluginResourceUsageReporter,
     frontend_api: FrontendApi,
     component_model: Vec<Component>,
     repository: DataDbRepository,
     search_index: SearchIndex,
     icon_cache: IconCache,
     dirs: Dirs,
+    log_buffer: PluginLogBuffer,
+    audit_log: PluginAuditLog,
+    enabled_state_cache: EnabledStateCache,
+    widget_id_epoch: WidgetIdEpoch,
+    view_open_state: ViewOpenState,
+    locale: Option<String>,
 ) -> anyhow::Result<()> {
 
     let dev_plugin = plugin_id.to_string().starts_with("file://");
@@ -332,8 +432,25 @@ async fn start_js_runtime(
 
     let permissions_container = permissions_to_deno(&permissions, &dirs, &plugin_uuid)?;
 
+    let exec_allowlist = resolve_exec_allowlist(&permissions.exec, &dirs, &plugin_uuid)?;
+    let ffi_allowlist = resolve_ffi_allowlist(&permissions.ffi, &dirs, &plugin_uuid)?;
+    let fs_read_allowlist = resolve_filesystem_allowlist(&permissions.filesystem.read, &dirs, &plugin_uuid)?;
+    let fs_write_allowlist = resolve_filesystem_allowlist(&permissions.filesystem.write, &dirs, &plugin_uuid)?;
+
+    // deno ffi is unstable api, so it is enabled only for plugins which declared ffi permission
+    let unstable = !ffi_allowlist.is_empty();
+
     let runtime_permissions = PluginRuntimePermissions {
         clipboard: permissions.clipboard,
+        exec_allowlist,
+        system: permissions.system,
+        environment: permissions.environment,
+        high_resolution_time: permissions.high_resolution_time,
+        list_entrypoints: permissions.list_entrypoints,
+        ffi_allowlist,
+        network: permissions.network,
+        fs_read_allowlist,
+        fs_write_allowlist,
     };
 
     let mut worker = MainWorker::bootstrap_from_options(
@@ -342,18 +459,24 @@ async fn start_js_runtime(
         WorkerOptions {
             bootstrap: BootstrapOptions {
                 is_tty: false,
+                unstable,
+                // default is the language of the system
+                locale: locale.unwrap_or_else(|| BootstrapOptions::default().locale),
                 ..Default::default()
             },
             module_loader: Rc::new(CustomModuleLoader::new(code, dev_plugin)),
             extensions: vec![plugin_ext::init_ops_and_esm(
-                EventReceiver::new(event_stream),
-                PluginData::new(plugin_id, plugin_uuid, plugin_name, entrypoint_names, inline_view_entrypoint_id, runtime_permissions),
+                EventReceiver::new(event_stream, health_reporter),
+                PluginData::new(plugin_id, plugin_uuid, plugin_name, entrypoint_names, inline_view_entrypoint_id, runtime_permissions, widget_id_epoch, view_open_state),
                 frontend_api,
                 ComponentModel::new(component_model),
                 repository,
                 search_index,
                 icon_cache,
-                numbat_context
+                numbat_context,
+                log_buffer,
+                audit_log,
+                enabled_state_cache
             )],
             // maybe_inspector_server: Some(inspector_server.clone()),
             // should_wait_for_inspector_session: true,
@@ -371,12 +494,40 @@ async fn start_js_runtime(
         },
     );
 
+    resource_usage_reporter.register_isolate(worker.js_runtime.v8_isolate().thread_safe_handle());
+
     worker.execute_side_module(&core_url).await?;
-    worker.run_event_loop(false).await?;
+
+    // event loop future only polls the runtime, so it is fine to drop it and start it again after sampling
+    let mut resource_usage_interval = tokio::time::interval(RESOURCE_USAGE_SAMPLE_INTERVAL);
+
+    loop {
+        tokio::select! {
+            result = worker.run_event_loop(false) => {
+                result?;
+
+                break
+            }
+            _ = resource_usage_interval.tick() => {
+                resource_usage_reporter.report(sample_resource_usage(worker.js_runtime.v8_isolate()));
+            }
+        }
+    }
 
     Ok(())
 }
 
+fn sample_resource_usage(isolate: &mut v8::Isolate) -> PluginResourceUsage {
+    let mut heap_statistics = v8::HeapStatistics::default();
+
+    isolate.get_heap_statistics(&mut heap_statistics);
+
+    PluginResourceUsage {
+        heap_used_bytes: heap_statistics.used_heap_size(),
+        heap_total_bytes: heap_statistics.total_heap_size(),
+    }
+}
+
 pub struct CustomModuleLoader {
     code: PluginCode,
     static_loader: StaticModuleLoader,
@@ -490,6 +641,7 @@ deno_core::extension!(
         op_log_info,
         op_log_warn,
         op_log_error,
+        op_console_log,
 
         // command generators
         get_command_generator_entrypoint_ids,
@@ -505,8 +657,12 @@ deno_core::extension!(
         clear_inline_view,
         show_preferences_required_view,
         op_component_model,
+        op_widget_id_epoch_start,
         fetch_action_id_for_shortcut,
         show_hud,
+        show_inline_answer,
+        op_save_view_state,
+        op_load_view_state,
 
         // preferences
         get_plugin_preferences,
@@ -516,6 +672,7 @@ deno_core::extension!(
 
         // search
         reload_search_index,
+        op_list_entrypoints,
 
         // clipboard
         clipboard_read_text,
@@ -524,6 +681,23 @@ deno_core::extension!(
         clipboard_write_text,
         clipboard_clear,
 
+        // permissions
+        op_request_permission,
+
+        // system
+        op_system_info,
+        op_get_env,
+        op_performance_now,
+        op_ffi_resolve_library,
+        op_launch_application,
+        op_run_subprocess,
+        op_fetch,
+        op_fetch_stream,
+        op_fetch_stream_read,
+        op_fetch_stream_close,
+        op_fs_read,
+        op_fs_write,
+
         // plugins numbat
         run_numbat,
 
@@ -533,6 +707,8 @@ deno_core::extension!(
 
         // plugins settings
         open_settings,
+        open_settings_preferences,
+        search_all_preferences,
     ],
     options = {
         event_receiver: EventReceiver,
@@ -543,6 +719,9 @@ deno_core::extension!(
         search_index: SearchIndex,
         icon_cache: IconCache,
         numbat_context: Option<NumbatContext>,
+        log_buffer: PluginLogBuffer,
+        audit_log: PluginAuditLog,
+        enabled_state_cache: EnabledStateCache,
     },
     state = |state, options| {
         state.put(options.event_receiver);
@@ -553,22 +732,50 @@ deno_core::extension!(
         state.put(options.search_index);
         state.put(options.icon_cache);
         state.put(options.numbat_context);
+        state.put(options.log_buffer);
+        state.put(options.audit_log);
+        state.put(options.enabled_state_cache);
+        state.put(PermissionDecisionCache::new());
+        state.put(TimeOrigin::new());
+        state.put(FetchStreams::new());
     },
 );
 
 #[op]
 async fn op_plugin_get_pending_event(state: Rc<RefCell<OpState>>) -> anyhow::Result<JsUiEvent> {
-    let event_stream = {
-        state.borrow()
-            .borrow::<EventReceiver>()
-            .event_stream
-            .clone()
+    let (event_stream, pending_events, health_reporter) = {
+        let state = state.borrow();
+        let event_receiver = state.borrow::<EventReceiver>();
+
+        (event_receiver.event_stream.clone(), event_receiver.pending_events.clone(), event_receiver.health_reporter.clone())
     };
 
     let mut event_stream = event_stream.borrow_mut();
-    let event = event_stream.next()
-        .await
-        .ok_or_else(|| anyhow!("event stream was suddenly closed"))?;
+    let mut pending_events = pending_events.borrow_mut();
+
+    // pings are answered here and not passed to js
+    while pending_events.is_empty() {
+        let event = event_stream.next()
+            .await
+            .ok_or_else(|| anyhow!("event stream was suddenly closed"))?;
+
+        match event {
+            IntermediateUiEvent::Ping => health_reporter.responded(),
+            event => pending_events.push(event)
+        }
+    }
+
+    // events that are already available are pulled all at once, so that the ones still waiting can be coalesced
+    while !pending_events.is_full() {
+        match event_stream.next().now_or_never() {
+            Some(Some(IntermediateUiEvent::Ping)) => health_reporter.responded(),
+            Some(Some(event)) => pending_events.push(event),
+            Some(None) | None => break,
+        }
+    }
+
+    let event = pending_events.pop()
+        .expect("at least one event should be pending");
 
     tracing::trace!(target = "renderer_rs", "Received plugin event {:?}", event);
 
@@ -625,13 +832,13 @@ async fn make_request_async(plugin_id: PluginId, plugin_name: String, frontend_a
 
             Ok(JsUiResponseData::Nothing)
         }
-        JsUiRequestData::ShowPluginErrorView { entrypoint_id, render_location } => {
+        JsUiRequestData::ShowPluginErrorView { entrypoint_id, render_location, message } => {
             let render_location = match render_location { // TODO into?
                 JsUiRenderLocation::InlineView => UiRenderLocation::InlineView,
                 JsUiRenderLocation::View => UiRenderLocation::View,
             };
 
-            frontend_api.show_plugin_error_view(plugin_id, entrypoint_id, render_location).await?;
+            frontend_api.show_plugin_error_view(plugin_id, entrypoint_id, render_location, message).await?;
 
             Ok(JsUiResponseData::Nothing)
         }
@@ -639,6 +846,12 @@ async fn make_request_async(plugin_id: PluginId, plugin_name: String, frontend_a
 
             frontend_api.show_hud(display).await?;
 
+            Ok(JsUiResponseData::Nothing)
+        }
+        JsUiRequestData::ShowInlineAnswer { value, copy_text } => {
+
+            frontend_api.show_inline_answer(plugin_id, value, copy_text).await?;
+
             Ok(JsUiResponseData::Nothing)
         }
     }
@@ -650,8 +863,13 @@ fn from_intermediate_to_js_event(event: IntermediateUiEvent) -> JsUiEvent {
             entrypoint_id: entrypoint_id.to_string(),
         },
         IntermediateUiEvent::CloseView => JsUiEvent::CloseView,
-        IntermediateUiEvent::RunCommand { entrypoint_id } => JsUiEvent::RunCommand {
-            entrypoint_id
+        IntermediateUiEvent::RunCommand { entrypoint_id, action_id } => JsUiEvent::RunCommand {
+            entrypoint_id,
+            action_id,
+        },
+        IntermediateUiEvent::RunFallbackCommand { entrypoint_id, text } => JsUiEvent::RunFallbackCommand {
+            entrypoint_id,
+            text,
         },
         IntermediateUiEvent::RunGeneratedCommand { entrypoint_id, action_index } => JsUiEvent::RunGeneratedCommand {
             entrypoint_id,
@@ -693,6 +911,7 @@ fn from_intermediate_to_js_event(event: IntermediateUiEvent) -> JsUiEvent {
         IntermediateUiEvent::OpenInlineView { text } => JsUiEvent::OpenInlineView { text },
         IntermediateUiEvent::ReloadSearchIndex => JsUiEvent::ReloadSearchIndex,
         IntermediateUiEvent::RefreshSearchIndex => JsUiEvent::RefreshSearchIndex,
+        IntermediateUiEvent::Ping => unreachable!("ping is answered by runtime and never passed to js"),
     }
 }
 
@@ -702,7 +921,9 @@ pub struct PluginData {
     plugin_name: String,
     entrypoint_names: HashMap<EntrypointId, String>,
     inline_view_entrypoint_id: Option<String>,
-    permissions: PluginRuntimePermissions
+    permissions: PluginRuntimePermissions,
+    widget_id_epoch: WidgetIdEpoch,
+    view_open_state: ViewOpenState,
 }
 
 impl PluginData {
@@ -712,7 +933,9 @@ impl PluginData {
         plugin_name: String,
         entrypoint_names: HashMap<EntrypointId, String>,
         inline_view_entrypoint_id: Option<String>,
-        permissions: PluginRuntimePermissions
+        permissions: PluginRuntimePermissions,
+        widget_id_epoch: WidgetIdEpoch,
+        view_open_state: ViewOpenState,
     ) -> Self {
         Self {
             plugin_id,
@@ -720,7 +943,9 @@ impl PluginData {
             plugin_name,
             entrypoint_names,
             inline_view_entrypoint_id,
-            permissions
+            permissions,
+            widget_id_epoch,
+            view_open_state,
         }
     }
 
@@ -743,6 +968,14 @@ impl PluginData {
     fn permissions(&self) -> &PluginRuntimePermissions {
         &self.permissions
     }
+
+    fn widget_id_epoch(&self) -> WidgetIdEpoch {
+        self.widget_id_epoch
+    }
+
+    fn view_open_state(&self) -> &ViewOpenState {
+        &self.view_open_state
+    }
 }
 
 pub struct ComponentModel {
@@ -767,12 +1000,268 @@ impl ComponentModel {
 
 pub struct EventReceiver {
     event_stream: Rc<RefCell<Pin<Box<dyn Stream<Item=IntermediateUiEvent>>>>>,
+    pending_events: Rc<RefCell<PendingEvents>>,
+    health_reporter: PluginHealthReporter,
 }
 
 impl EventReceiver {
-    fn new(event_stream: Pin<Box<dyn Stream<Item=IntermediateUiEvent>>>) -> EventReceiver {
+    fn new(event_stream: Pin<Box<dyn Stream<Item=IntermediateUiEvent>>>, health_reporter: PluginHealthReporter) -> EventReceiver {
         Self {
             event_stream: Rc::new(RefCell::new(event_stream)),
+            pending_events: Rc::new(RefCell::new(PendingEvents::new())),
+            health_reporter,
+        }
+    }
+}
+
+const MAX_PENDING_EVENTS: usize = 100;
+
+// view events which can be fired at high rate, e.g. on every key press in text field.
+// plugin only cares about the latest value, so queued event for the same widget is replaced
+const COALESCED_VIEW_EVENTS: [&str; 1] = ["onChange"];
+
+struct PendingEvents {
+    events: VecDeque<IntermediateUiEvent>,
+}
+
+impl PendingEvents {
+    fn new() -> Self {
+        Self {
+            events: VecDeque::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.events.len() >= MAX_PENDING_EVENTS
+    }
+
+    fn pop(&mut self) -> Option<IntermediateUiEvent> {
+        self.events.pop_front()
+    }
+
+    fn push(&mut self, event: IntermediateUiEvent) {
+        if let IntermediateUiEvent::HandleViewEvent { widget_id, event_name, .. } = &event {
+            if COALESCED_VIEW_EVENTS.contains(&event_name.as_str()) {
+                let is_same_event = |queued: &IntermediateUiEvent| {
+                    matches!(queued, IntermediateUiEvent::HandleViewEvent { widget_id: queued_widget_id, event_name: queued_event_name, .. } if queued_widget_id == widget_id && queued_event_name == event_name)
+                };
+
+                // replaced only if nothing was queued after it,
+                // otherwise newer value would be seen by handlers of events queued in between, e.g. submit
+                if let Some(queued) = self.events.back_mut().filter(|queued| is_same_event(queued)) {
+                    *queued = event;
+                    return;
+                }
+            }
+        }
+
+        self.events.push_back(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use common::model::{UiRequestData, UiResponseData};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct CapturedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedOutput {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl std::io::Write for CapturedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CapturedOutput {
+        type Writer = CapturedOutput;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn runtime_thread_logs_inside_of_plugin_span() {
+        let output = CapturedOutput::default();
+
+        let dispatch = tracing::Dispatch::new(
+            tracing_subscriber::fmt()
+                .with_writer(output.clone())
+                .with_ansi(false)
+                .finish()
+        );
+
+        let thread_fn = tracing::dispatcher::with_default(&dispatch, || {
+            let span = tracing::info_span!("start_plugin", plugin_id = "test-plugin");
+            let _span_guard = span.enter();
+
+            in_current_span(|| tracing::info!("message from runtime thread"))
+        });
+
+        // subscriber is set as global default in application, in test it has to be set for spawned thread
+        std::thread::spawn(move || tracing::dispatcher::with_default(&dispatch, thread_fn))
+            .join()
+            .unwrap();
+
+        let output = output.contents();
+
+        assert!(output.contains("message from runtime thread"));
+        assert!(output.contains("start_plugin{plugin_id=\"test-plugin\"}"));
+    }
+
+    #[tokio::test]
+    async fn render_error_is_shown_in_error_view_with_message() {
+        let (sender, mut receiver) = utils::channel::channel::<UiRequestData, UiResponseData>();
+        let mut frontend_api = FrontendApi::new(sender);
+
+        let frontend = tokio::spawn(async move {
+            let (request, responder) = receiver.recv().await;
+            responder.respond(UiResponseData::Nothing);
+            request
+        });
+
+        let data = JsUiRequestData::ShowPluginErrorView {
+            entrypoint_id: EntrypointId::from_string("view"),
+            render_location: JsUiRenderLocation::View,
+            message: Some("TypeError: undefined is not a function".to_string()),
+        };
+
+        let response = make_request_async(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), &mut frontend_api, data)
+            .await
+            .unwrap();
+
+        assert!(matches!(response, JsUiResponseData::Nothing));
+
+        match frontend.await.unwrap() {
+            UiRequestData::ShowPluginErrorView { plugin_id, entrypoint_id, render_location: UiRenderLocation::View, message } => {
+                assert_eq!(plugin_id, PluginId::from_string("test-plugin"));
+                assert_eq!(entrypoint_id, EntrypointId::from_string("view"));
+                assert_eq!(message.as_deref(), Some("TypeError: undefined is not a function"));
+            }
+            request @ _ => panic!("unexpected request {:?}", request),
         }
     }
+
+    fn view_event(widget_id: UiWidgetId, event_name: &str, value: &str) -> IntermediateUiEvent {
+        IntermediateUiEvent::HandleViewEvent {
+            widget_id,
+            event_name: event_name.to_string(),
+            event_arguments: vec![UiPropertyValue::String(value.to_string())],
+        }
+    }
+
+    fn drain(pending_events: &mut PendingEvents) -> Vec<(UiWidgetId, String, String)> {
+        std::iter::from_fn(|| pending_events.pop())
+            .map(|event| {
+                match event {
+                    IntermediateUiEvent::HandleViewEvent { widget_id, event_name, event_arguments } => {
+                        let value = event_arguments[0].as_string().unwrap().to_string();
+                        (widget_id, event_name, value)
+                    }
+                    _ => panic!("unexpected event")
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn flood_of_change_events_keeps_only_latest_one() {
+        let mut pending_events = PendingEvents::new();
+
+        for index in 0..10_000 {
+            pending_events.push(view_event(1, "onChange", &index.to_string()));
+
+            assert!(!pending_events.is_full());
+        }
+
+        assert_eq!(drain(&mut pending_events), vec![(1, "onChange".to_string(), "9999".to_string())]);
+    }
+
+    #[test]
+    fn consecutive_change_events_are_coalesced() {
+        let mut pending_events = PendingEvents::new();
+
+        pending_events.push(view_event(1, "onChange", "a"));
+        pending_events.push(view_event(1, "onChange", "ab"));
+        pending_events.push(view_event(1, "onChange", "abc"));
+
+        assert_eq!(drain(&mut pending_events), vec![(1, "onChange".to_string(), "abc".to_string())]);
+    }
+
+    #[test]
+    fn change_event_is_not_moved_before_event_queued_after_it() {
+        let mut pending_events = PendingEvents::new();
+
+        pending_events.push(view_event(1, "onChange", "a"));
+        pending_events.push(view_event(2, "onSubmit", "submit"));
+        pending_events.push(view_event(1, "onChange", "ab"));
+
+        assert_eq!(
+            drain(&mut pending_events),
+            vec![
+                (1, "onChange".to_string(), "a".to_string()),
+                (2, "onSubmit".to_string(), "submit".to_string()),
+                (1, "onChange".to_string(), "ab".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn change_events_of_different_widgets_are_not_coalesced() {
+        let mut pending_events = PendingEvents::new();
+
+        pending_events.push(view_event(1, "onChange", "a"));
+        pending_events.push(view_event(2, "onChange", "b"));
+
+        assert_eq!(
+            drain(&mut pending_events),
+            vec![
+                (1, "onChange".to_string(), "a".to_string()),
+                (2, "onChange".to_string(), "b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_events_are_not_coalesced() {
+        let mut pending_events = PendingEvents::new();
+
+        pending_events.push(view_event(1, "onClick", "first"));
+        pending_events.push(view_event(1, "onClick", "second"));
+
+        assert_eq!(drain(&mut pending_events).len(), 2);
+    }
+
+    #[test]
+    fn heap_usage_of_running_runtime_is_positive() {
+        let mut runtime = deno_core::JsRuntime::new(deno_core::RuntimeOptions::default());
+
+        runtime.execute_script_static("test.js", "globalThis.values = Array.from({ length: 1000 }, (_, index) => ({ index }))").unwrap();
+
+        let usage = sample_resource_usage(runtime.v8_isolate());
+
+        assert!(usage.heap_used_bytes > 0);
+        assert!(usage.heap_total_bytes >= usage.heap_used_bytes);
+    }
 }
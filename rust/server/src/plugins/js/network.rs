@@ -0,0 +1,409 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Read;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use deno_core::url::Url;
+use deno_core::{op, OpState};
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::audit_log::PluginAuditLog;
+use crate::plugins::js::permissions::{is_host_allowed, PermissionDecisionCache};
+use crate::plugins::js::PluginData;
+
+const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_FETCH_TIMEOUT: Duration = Duration::from_secs(300);
+const MAX_FETCH_BODY_SIZE: u64 = 50 * 1024 * 1024;
+const FETCH_STREAM_CHUNK_SIZE: usize = 64 * 1024;
+// sum over all responses the plugin has streamed since it was started, closing streams doesn't reset it
+const MAX_STREAMED_BYTES: u64 = 512 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchRequest {
+    url: String,
+    method: Option<String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    body: Option<String>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FetchResponse {
+    status: u16,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchStreamResponse {
+    stream_id: u32,
+    status: u16,
+    headers: HashMap<String, String>,
+}
+
+struct FetchStream {
+    reader: Arc<Mutex<Box<dyn Read + Send + Sync>>>,
+}
+
+pub struct FetchStreams {
+    next_id: u32,
+    streams: HashMap<u32, FetchStream>,
+    read_bytes: u64,
+}
+
+impl FetchStreams {
+    pub fn new() -> Self {
+        Self {
+            next_id: 0,
+            streams: HashMap::new(),
+            read_bytes: 0,
+        }
+    }
+
+    fn insert(&mut self, reader: Box<dyn Read + Send + Sync>) -> u32 {
+        let stream_id = self.next_id;
+        self.next_id += 1;
+
+        let stream = FetchStream {
+            reader: Arc::new(Mutex::new(reader)),
+        };
+
+        self.streams.insert(stream_id, stream);
+
+        stream_id
+    }
+
+    fn reader(&self, stream_id: u32) -> anyhow::Result<Arc<Mutex<Box<dyn Read + Send + Sync>>>> {
+        let reader = self.streams
+            .get(&stream_id)
+            .ok_or_else(|| anyhow!("Fetch stream with id {} doesn't exist", stream_id))?
+            .reader
+            .clone();
+
+        Ok(reader)
+    }
+
+    // empty chunk means that whole body has been read
+    fn accept_chunk(&mut self, stream_id: u32, chunk: Vec<u8>, max_streamed_bytes: u64) -> anyhow::Result<Option<Vec<u8>>> {
+        if chunk.is_empty() {
+            self.streams.remove(&stream_id);
+
+            return Ok(None)
+        }
+
+        if self.read_bytes + chunk.len() as u64 > max_streamed_bytes {
+            self.streams.remove(&stream_id);
+
+            return Err(anyhow!("Plugin exceeded limit of {} bytes for streamed responses", max_streamed_bytes));
+        }
+
+        self.read_bytes += chunk.len() as u64;
+
+        Ok(Some(chunk))
+    }
+}
+
+#[op]
+async fn op_fetch(state: Rc<RefCell<OpState>>, request: FetchRequest) -> anyhow::Result<FetchResponse> {
+    let url = check_plugin_fetch_url(&state, &request.url)?;
+
+    let timeout = fetch_timeout(request.timeout_ms);
+
+    tokio::task::spawn_blocking(move || fetch(url, request.method, request.headers, request.body, timeout))
+        .await?
+}
+
+#[op]
+async fn op_fetch_stream(state: Rc<RefCell<OpState>>, request: FetchRequest) -> anyhow::Result<FetchStreamResponse> {
+    let url = check_plugin_fetch_url(&state, &request.url)?;
+
+    let timeout = fetch_timeout(request.timeout_ms);
+
+    let response = tokio::task::spawn_blocking(move || {
+        let agent = fetch_stream_agent(timeout);
+
+        send_fetch_request(&agent, &url, request.method, request.headers, request.body)
+    }).await??;
+
+    let status = response.status();
+    let headers = response_headers(&response);
+
+    let stream_id = state.borrow_mut()
+        .borrow_mut::<FetchStreams>()
+        .insert(response.into_reader());
+
+    Ok(FetchStreamResponse {
+        stream_id,
+        status,
+        headers,
+    })
+}
+
+// returns None when whole body has been read, stream is closed after that
+#[op]
+async fn op_fetch_stream_read(state: Rc<RefCell<OpState>>, stream_id: u32) -> anyhow::Result<Option<Vec<u8>>> {
+    let reader = state.borrow()
+        .borrow::<FetchStreams>()
+        .reader(stream_id)?;
+
+    let chunk = tokio::task::spawn_blocking(move || read_chunk(&reader)).await??;
+
+    let mut state = state.borrow_mut();
+
+    state.borrow_mut::<FetchStreams>()
+        .accept_chunk(stream_id, chunk, MAX_STREAMED_BYTES)
+}
+
+fn read_chunk(reader: &Mutex<Box<dyn Read + Send + Sync>>) -> std::io::Result<Vec<u8>> {
+    let mut reader = reader.lock().expect("lock is poisoned");
+
+    let mut chunk = vec![0; FETCH_STREAM_CHUNK_SIZE];
+    let read = reader.read(&mut chunk)?;
+    chunk.truncate(read);
+
+    Ok(chunk)
+}
+
+#[op]
+fn op_fetch_stream_close(state: &mut OpState, stream_id: u32) {
+    state.borrow_mut::<FetchStreams>()
+        .streams
+        .remove(&stream_id);
+}
+
+fn fetch_timeout(timeout_ms: Option<u64>) -> Duration {
+    timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_FETCH_TIMEOUT)
+        .min(MAX_FETCH_TIMEOUT)
+}
+
+fn check_plugin_fetch_url(state: &Rc<RefCell<OpState>>, url: &str) -> anyhow::Result<Url> {
+    let state = state.borrow();
+
+    let plugin_data = state.borrow::<PluginData>();
+
+    let audit_log = state.borrow::<PluginAuditLog>();
+
+    let mut granted_hosts = plugin_data.permissions().network.clone();
+
+    granted_hosts.extend(state.borrow::<PermissionDecisionCache>().granted_hosts());
+
+    match check_fetch_url(&granted_hosts, url) {
+        Ok(url) => {
+            audit_log.record(&plugin_data.plugin_id(), "fetch", url.as_str());
+
+            Ok(url)
+        }
+        Err(err) => {
+            audit_log.record(&plugin_data.plugin_id(), "fetch-denied", url);
+
+            Err(err)
+        }
+    }
+}
+
+// host is checked before any connection is made
+fn check_fetch_url(network: &[String], url: &str) -> anyhow::Result<Url> {
+    let url = Url::parse(url)
+        .context(format!("Invalid url: {}", url))?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(anyhow!("Unsupported url scheme: {}", url.scheme()));
+    }
+
+    let host = url.host_str()
+        .ok_or_else(|| anyhow!("Url doesn't have a host: {}", url))?;
+
+    if !is_host_allowed(network, host, url.port_or_known_default()) {
+        return Err(anyhow!("Plugin doesn't have permission to access host '{}'", host));
+    }
+
+    Ok(url)
+}
+
+fn fetch_agent(timeout: Duration) -> ureq::Agent {
+    // redirects are not followed, target of the redirect is not checked against permissions
+    ureq::AgentBuilder::new()
+        .timeout(timeout)
+        .redirects(0)
+        .build()
+}
+
+// streamed responses can legitimately take long, so timeout applies to each read instead of the whole request
+fn fetch_stream_agent(timeout: Duration) -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(timeout)
+        .timeout_read(timeout)
+        .timeout_write(timeout)
+        .redirects(0)
+        .build()
+}
+
+fn send_fetch_request(agent: &ureq::Agent, url: &Url, method: Option<String>, headers: HashMap<String, String>, body: Option<String>) -> anyhow::Result<ureq::Response> {
+    let method = method
+        .unwrap_or_else(|| "GET".to_string())
+        .to_uppercase();
+
+    let mut request = agent.request(&method, url.as_str());
+
+    for (name, value) in &headers {
+        request = request.set(name, value);
+    }
+
+    let result = match body {
+        Some(body) => request.send_string(&body),
+        None => request.call(),
+    };
+
+    // same as in web fetch, response with error status is not an error
+    match result {
+        Ok(response) => Ok(response),
+        Err(ureq::Error::Status(_, response)) => Ok(response),
+        Err(err) => Err(anyhow!("Request to {} failed: {}", url, err)),
+    }
+}
+
+fn response_headers(response: &ureq::Response) -> HashMap<String, String> {
+    response.headers_names()
+        .into_iter()
+        .filter_map(|name| {
+            let value = response.header(&name)?.to_string();
+
+            Some((name, value))
+        })
+        .collect()
+}
+
+fn fetch(url: Url, method: Option<String>, headers: HashMap<String, String>, body: Option<String>, timeout: Duration) -> anyhow::Result<FetchResponse> {
+    let agent = fetch_agent(timeout);
+
+    let response = send_fetch_request(&agent, &url, method, headers, body)?;
+
+    let status = response.status();
+    let headers = response_headers(&response);
+
+    let mut body = vec![];
+
+    response.into_reader()
+        .take(MAX_FETCH_BODY_SIZE + 1)
+        .read_to_end(&mut body)?;
+
+    if body.len() as u64 > MAX_FETCH_BODY_SIZE {
+        return Err(anyhow!("Response body of {} is larger than {} bytes", url, MAX_FETCH_BODY_SIZE));
+    }
+
+    Ok(FetchResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn allowed_host_is_fetched() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+
+            stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nX-Test: value\r\n\r\nok").unwrap();
+        });
+
+        let network = vec![format!("127.0.0.1:{}", port)];
+
+        let url = check_fetch_url(&network, &format!("http://127.0.0.1:{}/path", port)).unwrap();
+
+        let response = fetch(url, None, HashMap::new(), None, Duration::from_secs(5)).unwrap();
+
+        server.join().unwrap();
+
+        assert_eq!(response.status, 200);
+        assert!(response.headers.iter().any(|(name, value)| name.eq_ignore_ascii_case("x-test") && value == "value"));
+        assert_eq!(response.body, b"ok");
+    }
+
+    #[test]
+    fn disallowed_host_is_rejected_before_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let network = vec!["example.com".to_string()];
+
+        let result = check_fetch_url(&network, &format!("http://127.0.0.1:{}/path", port));
+
+        assert!(result.is_err());
+        assert!(listener.accept().is_err());
+    }
+
+    fn read_stream(fetch_streams: &mut FetchStreams, stream_id: u32, max_streamed_bytes: u64) -> anyhow::Result<Vec<u8>> {
+        let mut body = vec![];
+
+        loop {
+            let reader = fetch_streams.reader(stream_id)?;
+            let chunk = read_chunk(&reader)?;
+
+            match fetch_streams.accept_chunk(stream_id, chunk, max_streamed_bytes)? {
+                Some(chunk) => body.extend(chunk),
+                None => return Ok(body),
+            }
+        }
+    }
+
+    fn multi_chunk_body() -> Vec<u8> {
+        (0..FETCH_STREAM_CHUNK_SIZE * 3 + 100)
+            .map(|index| (index % 251) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn streamed_chunks_reassemble_into_body() {
+        let body = multi_chunk_body();
+
+        let mut fetch_streams = FetchStreams::new();
+        let stream_id = fetch_streams.insert(Box::new(std::io::Cursor::new(body.clone())));
+
+        let streamed = read_stream(&mut fetch_streams, stream_id, MAX_STREAMED_BYTES).unwrap();
+
+        assert_eq!(streamed, body);
+        assert!(fetch_streams.streams.is_empty());
+    }
+
+    #[test]
+    fn streaming_over_the_cap_is_stopped() {
+        let body = multi_chunk_body();
+
+        let mut fetch_streams = FetchStreams::new();
+        let stream_id = fetch_streams.insert(Box::new(std::io::Cursor::new(body.clone())));
+
+        let max_streamed_bytes = FETCH_STREAM_CHUNK_SIZE as u64 * 2;
+
+        assert!(read_stream(&mut fetch_streams, stream_id, max_streamed_bytes).is_err());
+        assert!(fetch_streams.streams.is_empty());
+    }
+
+    #[test]
+    fn non_http_url_is_rejected() {
+        let network = vec!["example.com".to_string()];
+
+        assert!(check_fetch_url(&network, "file://example.com/etc/passwd").is_err());
+    }
+}
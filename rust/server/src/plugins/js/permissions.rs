@@ -1,12 +1,19 @@
-use deno_runtime::permissions::{Descriptor, EnvDescriptor, NetDescriptor, Permissions, PermissionsContainer, ReadDescriptor, RunDescriptor, SysDescriptor, UnaryPermission, WriteDescriptor};
-use std::collections::HashSet;
+use deno_core::{op, OpState};
+use deno_runtime::permissions::{Descriptor, EnvDescriptor, FfiDescriptor, NetDescriptor, Permissions, PermissionsContainer, ReadDescriptor, RunDescriptor, SysDescriptor, UnaryPermission, WriteDescriptor};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
 use std::str::FromStr;
 use anyhow::anyhow;
 use typed_path::Utf8TypedPath;
 use common::dirs::Dirs;
 use common::model::PluginId;
+use common::rpc::frontend_api::{FrontendApi, FrontendApiError};
+use crate::plugins::audit_log::PluginAuditLog;
+use crate::plugins::data_db_repository::DataDbRepository;
+use crate::plugins::js::PluginData;
 use crate::plugins::loader::VARIABLE_PATTERN;
 
 pub struct PluginPermissions {
@@ -17,6 +24,9 @@ pub struct PluginPermissions {
     pub system: Vec<String>,
     pub clipboard: Vec<PluginPermissionsClipboard>,
     pub main_search_bar: Vec<PluginPermissionsMainSearchBar>,
+    pub high_resolution_time: bool,
+    pub list_entrypoints: bool,
+    pub ffi: Vec<String>,
 }
 
 pub struct PluginPermissionsFileSystem {
@@ -41,6 +51,140 @@ pub enum PluginPermissionsMainSearchBar {
     Read,
 }
 
+// decisions made during current plugin run, keyed by (kind, detail)
+pub struct PermissionDecisionCache {
+    decisions: HashMap<(String, String), bool>,
+}
+
+impl PermissionDecisionCache {
+    pub fn new() -> Self {
+        Self {
+            decisions: HashMap::new(),
+        }
+    }
+
+    // paths granted by user during current plugin run for given filesystem permission kind
+    pub fn granted_paths(&self, kind: &str) -> Vec<PathBuf> {
+        self.granted_details(kind)
+            .into_iter()
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    // hosts in "domain[:port]" format granted by user during current plugin run
+    pub fn granted_hosts(&self) -> Vec<String> {
+        self.granted_details("network")
+    }
+
+    fn granted_details(&self, kind: &str) -> Vec<String> {
+        self.decisions
+            .iter()
+            .filter(|((decision_kind, _), granted)| decision_kind == kind && **granted)
+            .map(|((_, detail), _)| detail.clone())
+            .collect()
+    }
+}
+
+#[op]
+async fn op_request_permission(state: Rc<RefCell<OpState>>, kind: String, detail: String) -> anyhow::Result<bool> {
+    if !matches!(kind.as_str(), "network" | "environment" | "filesystem-read" | "filesystem-write") {
+        return Err(anyhow!("Unknown permission kind: {}", kind))
+    }
+
+    let (plugin_id, plugin_name, repository, audit_log, mut frontend_api) = {
+        let state = state.borrow();
+
+        if let Some(granted) = state.borrow::<PermissionDecisionCache>().decisions.get(&(kind.clone(), detail.clone())) {
+            return Ok(*granted)
+        }
+
+        let plugin_data = state.borrow::<PluginData>();
+
+        let repository = state.borrow::<DataDbRepository>().clone();
+        let audit_log = state.borrow::<PluginAuditLog>().clone();
+        let frontend_api = state.borrow::<FrontendApi>().clone();
+
+        (plugin_data.plugin_id(), plugin_data.plugin_name().to_string(), repository, audit_log, frontend_api)
+    };
+
+    let granted = permission_decision(&repository, &audit_log, &mut frontend_api, plugin_id, plugin_name, &kind, &detail).await?;
+
+    let mut state = state.borrow_mut();
+
+    if granted {
+        grant_runtime_permission(&state.borrow::<PermissionsContainer>(), &kind, &detail)?;
+    }
+
+    state.borrow_mut::<PermissionDecisionCache>()
+        .decisions
+        .insert((kind, detail), granted);
+
+    Ok(granted)
+}
+
+async fn permission_decision(
+    repository: &DataDbRepository,
+    audit_log: &PluginAuditLog,
+    frontend_api: &mut FrontendApi,
+    plugin_id: PluginId,
+    plugin_name: String,
+    kind: &str,
+    detail: &str
+) -> anyhow::Result<bool> {
+    if let Some(granted) = repository.get_permission_decision(&plugin_id.to_string(), kind, detail).await? {
+        return Ok(granted)
+    }
+
+    let granted = match frontend_api.request_permission(plugin_id.clone(), plugin_name.clone(), kind.to_string(), detail.to_string()).await {
+        Ok(granted) => granted,
+        Err(FrontendApiError::TimeoutError) => {
+            tracing::warn!(target = "plugin", "Permission prompt for plugin {:?} expired without decision", plugin_id);
+
+            let _ = frontend_api.show_hud(format!("Permission request of \"{}\" expired and was denied", plugin_name)).await;
+
+            return Ok(false)
+        }
+        Err(err) => {
+            // not persisted, so user will be asked again next time
+            tracing::warn!(target = "plugin", "Unable to get permission decision from user: {:?}", err);
+            return Ok(false)
+        }
+    };
+
+    repository.save_permission_decision(&plugin_id.to_string(), kind, detail, granted).await?;
+
+    let action = if granted { "permission-granted" } else { "permission-denied" };
+
+    audit_log.record(&plugin_id, action, &format!("{}:{}", kind, detail));
+
+    Ok(granted)
+}
+
+fn grant_runtime_permission(permissions: &PermissionsContainer, kind: &str, detail: &str) -> anyhow::Result<()> {
+    let mut permissions = permissions.0.lock();
+
+    match kind {
+        "network" => {
+            let descriptor = NetDescriptor::from_str(detail)
+                .map_err(|_| anyhow!("Invalid network permission: {}", detail))?;
+
+            permissions.net.granted_list.insert(descriptor);
+        }
+        "environment" => {
+            permissions.env.granted_list.insert(EnvDescriptor::new(detail));
+        }
+        "filesystem-read" => {
+            permissions.read.granted_list.insert(ReadDescriptor(normalize_path(Path::new(detail))));
+        }
+        "filesystem-write" => {
+            permissions.write.granted_list.insert(WriteDescriptor(normalize_path(Path::new(detail))));
+        }
+        _ => unreachable!()
+    }
+
+    Ok(())
+}
+
 pub fn permissions_to_deno(permissions: &PluginPermissions, dirs: &Dirs, plugin_uuid: &str) -> anyhow::Result<PermissionsContainer> {
     Ok(PermissionsContainer::new(Permissions {
         read: path_permission(&permissions.filesystem.read, ReadDescriptor, dirs, plugin_uuid)?,
@@ -49,8 +193,8 @@ pub fn permissions_to_deno(permissions: &PluginPermissions, dirs: &Dirs, plugin_
         env: env_permission(&permissions.environment),
         sys: sys_permission(&permissions.system),
         run: run_permission(&permissions.exec, dirs, plugin_uuid)?,
-        ffi: Permissions::new_ffi(&None, &None, false).expect("new_ffi should always succeed"),
-        hrtime: Permissions::new_hrtime(true, false),
+        ffi: ffi_permission(&permissions.ffi, dirs, plugin_uuid)?,
+        hrtime: Permissions::new_hrtime(permissions.high_resolution_time, false),
     }))
 }
 
@@ -64,7 +208,7 @@ fn path_permission<T: Descriptor + Hash>(
         .into_iter()
         .map(|path| {
             augment_path(path, dirs, plugin_uuid)
-                .map(|path| path.map(|path| to_permission(path)))
+                .map(|path| path.map(|path| to_permission(normalize_path(&path))))
         })
         .collect::<anyhow::Result<Vec<_>>>()?
         .into_iter()
@@ -80,9 +224,63 @@ fn path_permission<T: Descriptor + Hash>(
     })
 }
 
+fn ffi_permission(paths: &[String], dirs: &Dirs, plugin_uuid: &str) -> anyhow::Result<UnaryPermission<FfiDescriptor>> {
+    let granted = resolve_ffi_allowlist(paths, dirs, plugin_uuid)?
+        .into_iter()
+        .map(|path| FfiDescriptor(path))
+        .collect();
+
+    Ok(UnaryPermission {
+        prompt: false,
+        granted_global: false,
+        flag_denied_global: false,
+        granted_list: granted,
+        ..Default::default()
+    })
+}
+
+// libraries are loaded by canonical path so symlinks cannot point to a library outside of allowlist
+pub fn resolve_ffi_allowlist(paths: &[String], dirs: &Dirs, plugin_uuid: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let mut allowlist = vec![];
+
+    for path in paths {
+        if let Some(path) = augment_path(path, dirs, plugin_uuid)? {
+            allowlist.push(canonicalize_or_normalize(&path));
+        }
+    }
+
+    Ok(allowlist)
+}
+
+// directories from "filesystem" permissions resolved to canonical paths
+pub fn resolve_filesystem_allowlist(paths: &[String], dirs: &Dirs, plugin_uuid: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let mut allowlist = vec![];
+
+    for path in paths {
+        if let Some(path) = augment_path(path, dirs, plugin_uuid)? {
+            allowlist.push(canonicalize_or_normalize(&path));
+        }
+    }
+
+    Ok(allowlist)
+}
+
+pub fn resolve_allowed_library(ffi_allowlist: &[PathBuf], path: &str) -> anyhow::Result<PathBuf> {
+    let resolved = canonicalize_or_normalize(Path::new(path));
+
+    if !ffi_allowlist.contains(&resolved) {
+        return Err(anyhow!("Plugin doesn't have ffi permission for library: {}", path))
+    }
+
+    Ok(resolved)
+}
+
 fn net_permission(domain_and_ports: &[String]) -> UnaryPermission<NetDescriptor> {
+    // deno permissions only support exact hosts, so global fetch denies wildcard ones,
+    // they are allowed only in op_fetch and op_fetch_stream, which check them with network_permission_matches
     let granted = domain_and_ports
         .into_iter()
+        .filter(|domain_and_port| !domain_and_port.starts_with("*."))
         .map(|domain_and_port| {
             NetDescriptor::from_str(&domain_and_port)
                 .expect("should be validated when loading")
@@ -98,6 +296,43 @@ fn net_permission(domain_and_ports: &[String]) -> UnaryPermission<NetDescriptor>
     }
 }
 
+pub fn is_host_allowed(domain_and_ports: &[String], host: &str, port: Option<u16>) -> bool {
+    domain_and_ports.iter()
+        .any(|domain_and_port| network_permission_matches(domain_and_port, host, port))
+}
+
+// permission is in format "domain[:port]", where domain can start with "*." to allow any subdomain
+fn network_permission_matches(domain_and_port: &str, host: &str, port: Option<u16>) -> bool {
+    let (permission_domain, permission_port) = match domain_and_port.rsplit_once(':') {
+        Some((domain, permission_port)) => {
+            match permission_port.parse::<u16>() {
+                Ok(permission_port) => (domain, Some(permission_port)),
+                Err(_) => return false
+            }
+        }
+        None => (domain_and_port, None),
+    };
+
+    if let Some(permission_port) = permission_port {
+        if Some(permission_port) != port {
+            return false
+        }
+    }
+
+    let permission_domain = permission_domain.to_lowercase();
+    let host = host.to_lowercase();
+
+    match permission_domain.strip_prefix("*.") {
+        Some(parent_domain) => {
+            host.strip_suffix(parent_domain)
+                .and_then(|subdomain| subdomain.strip_suffix('.'))
+                .map(|subdomain| !subdomain.is_empty())
+                .unwrap_or(false)
+        }
+        None => permission_domain == host
+    }
+}
+
 fn env_permission(envs: &[String]) -> UnaryPermission<EnvDescriptor> {
     let granted = envs
         .into_iter()
@@ -128,27 +363,14 @@ fn sys_permission(system: &[String]) -> UnaryPermission<SysDescriptor> {
     }
 }
 
+// granted by resolved absolute paths only, so that Deno.Command cannot start
+// a different program with the same name found earlier in PATH or behind a replaced symlink
 fn run_permission(permissions: &PluginPermissionsExec, dirs: &Dirs, plugin_uuid: &str) -> anyhow::Result<UnaryPermission<RunDescriptor>> {
-    let granted_executable = permissions.executable
-        .iter()
-        .map(|path| {
-            augment_path(path, dirs, plugin_uuid)
-                .map(|path| path.map(|path| RunDescriptor::Path(path)))
-        })
-        .collect::<anyhow::Result<Vec<_>>>()?
+    let granted = resolve_exec_allowlist(permissions, dirs, plugin_uuid)?
         .into_iter()
-        .filter_map(std::convert::identity)
-        .collect::<HashSet<_>>();
-
-    let granted_command = permissions.command
-        .iter()
-        .map(|cmd| RunDescriptor::Name(cmd.to_owned()))
+        .map(|path| RunDescriptor::Path(path))
         .collect::<HashSet<_>>();
 
-    let mut granted = HashSet::new();
-    granted.extend(granted_executable);
-    granted.extend(granted_command);
-
     Ok(UnaryPermission {
         prompt: false,
         granted_global: false,
@@ -158,6 +380,64 @@ fn run_permission(permissions: &PluginPermissionsExec, dirs: &Dirs, plugin_uuid:
     })
 }
 
+// commands and executables from manifest resolved to absolute paths
+pub fn resolve_exec_allowlist(permissions: &PluginPermissionsExec, dirs: &Dirs, plugin_uuid: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let mut allowlist = vec![];
+
+    for path in &permissions.executable {
+        if let Some(path) = augment_path(path, dirs, plugin_uuid)? {
+            allowlist.push(canonicalize_or_normalize(&path));
+        }
+    }
+
+    for command in &permissions.command {
+        match find_in_path(command) {
+            Some(path) => allowlist.push(path),
+            None => tracing::debug!(target = "plugin", "Command '{}' from exec permissions was not found in PATH", command),
+        }
+    }
+
+    Ok(allowlist)
+}
+
+// returns resolved absolute path, which should be used to start the program,
+// so that PATH is not looked up again between the check and the spawn
+pub fn resolve_allowed_executable(exec_allowlist: &[PathBuf], program: &str) -> Option<PathBuf> {
+    let program_path = Path::new(program);
+
+    let resolved = if program_path.components().count() > 1 || program_path.is_absolute() {
+        Some(canonicalize_or_normalize(program_path))
+    } else {
+        find_in_path(program)
+    };
+
+    resolved.filter(|resolved| exec_allowlist.contains(resolved))
+}
+
+fn find_in_path(command: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+
+    std::env::split_paths(&paths)
+        .flat_map(|dir| {
+            let path = dir.join(command);
+
+            if cfg!(windows) {
+                vec![path.with_extension("exe"), path]
+            } else {
+                vec![path]
+            }
+        })
+        .find(|path| path.is_file())
+        .map(|path| canonicalize_or_normalize(&path))
+}
+
+fn canonicalize_or_normalize(path: &Path) -> PathBuf {
+    match std::fs::canonicalize(path) {
+        Ok(path) => path,
+        Err(_) => normalize_path(path),
+    }
+}
+
 fn augment_path(path: &String, dirs: &Dirs, plugin_uuid: &str) -> anyhow::Result<Option<PathBuf>> {
     if let Some(matches) = VARIABLE_PATTERN.captures(path) {
         let namespace = &matches["namespace"];
@@ -219,4 +499,329 @@ fn augment_path(path: &String, dirs: &Dirs, plugin_uuid: &str) -> anyhow::Result
             }
         }
     }
-}
\ No newline at end of file
+}
+
+pub fn is_path_allowed(granted_paths: &[PathBuf], path: &Path) -> bool {
+    // symlinks are resolved if path exists so they cannot be used to escape granted directory
+    let path = canonicalize_or_normalize(path);
+
+    granted_paths.iter()
+        .map(|granted_path| canonicalize_or_normalize(granted_path))
+        .any(|granted_path| path.starts_with(granted_path))
+}
+
+// resolves "." and ".." without touching file system, ".." never goes above root
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir | Component::Normal(_) => result.push(component),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                match result.components().next_back() {
+                    Some(Component::Normal(_)) => {
+                        result.pop();
+                    }
+                    Some(Component::Prefix(_) | Component::RootDir) => {}
+                    _ => result.push(component),
+                }
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use common::model::{UiRequestData, UiResponseData};
+    use crate::plugins::data_db_repository::{DbCode, DbPluginPermissions, DbPluginPermissionsExec, DbPluginPermissionsFileSystem, DbWritePlugin};
+    use super::*;
+
+    #[test]
+    fn exact_host_matches() {
+        let permissions = vec!["example.com".to_string()];
+
+        assert!(is_host_allowed(&permissions, "example.com", None));
+        assert!(is_host_allowed(&permissions, "Example.COM", Some(443)));
+        assert!(!is_host_allowed(&permissions, "api.example.com", None));
+        assert!(!is_host_allowed(&permissions, "example.org", None));
+    }
+
+    #[test]
+    fn host_with_port_matches_only_that_port() {
+        let permissions = vec!["example.com:8080".to_string()];
+
+        assert!(is_host_allowed(&permissions, "example.com", Some(8080)));
+        assert!(!is_host_allowed(&permissions, "example.com", Some(443)));
+        assert!(!is_host_allowed(&permissions, "example.com", None));
+    }
+
+    #[test]
+    fn wildcard_host_matches_only_subdomains() {
+        let permissions = vec!["*.example.com".to_string()];
+
+        assert!(is_host_allowed(&permissions, "api.example.com", None));
+        assert!(is_host_allowed(&permissions, "a.b.example.com", None));
+        assert!(!is_host_allowed(&permissions, "example.com", None));
+        assert!(!is_host_allowed(&permissions, "badexample.com", None));
+    }
+
+    #[test]
+    fn host_is_denied_without_permissions() {
+        assert!(!is_host_allowed(&[], "example.com", None));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn traversal_out_of_granted_directory_is_not_allowed() {
+        let granted_paths = vec![PathBuf::from("/allowed")];
+
+        assert!(!is_path_allowed(&granted_paths, Path::new("/allowed/../etc/passwd")));
+        assert!(is_path_allowed(&granted_paths, Path::new("/allowed/nested/file.txt")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parent_components_are_normalized() {
+        assert_eq!(normalize_path(Path::new("/a/b/../c/./d")), PathBuf::from("/a/c/d"));
+        assert_eq!(normalize_path(Path::new("/../a")), PathBuf::from("/a"));
+        assert_eq!(normalize_path(Path::new("a/../../b")), PathBuf::from("../b"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn path_outside_granted_directory_is_not_allowed() {
+        let dir = tempfile::tempdir().unwrap();
+        // temp dir itself can be behind a symlink, e.g. on macos
+        let dir_path = std::fs::canonicalize(dir.path()).unwrap();
+        let granted = dir_path.join("granted");
+
+        std::fs::create_dir(&granted).unwrap();
+
+        let granted_paths = vec![granted.clone()];
+
+        assert!(is_path_allowed(&granted_paths, &granted.join("file.txt")));
+        assert!(!is_path_allowed(&granted_paths, &granted.join("../file.txt")));
+        assert!(!is_path_allowed(&granted_paths, &dir_path.join("granted-other")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn path_escaping_through_symlink_is_not_allowed() {
+        let dir = tempfile::tempdir().unwrap();
+        let granted = dir.path().join("granted");
+        let outside = dir.path().join("outside");
+
+        std::fs::create_dir(&granted).unwrap();
+        std::fs::create_dir(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, granted.join("link")).unwrap();
+
+        assert!(!is_path_allowed(&[granted.clone()], &granted.join("link")));
+    }
+
+    async fn open_repository(dir: &Path) -> DataDbRepository {
+        let repository = DataDbRepository::open(&dir.join("data.db")).await.unwrap();
+
+        repository.save_plugin(DbWritePlugin {
+            id: "test-plugin".to_string(),
+            name: "Test Plugin".to_string(),
+            description: "".to_string(),
+            enabled: true,
+            code: DbCode { js: HashMap::new(), linked_path: None },
+            entrypoints: vec![],
+            asset_data: vec![],
+            permissions: DbPluginPermissions {
+                environment: vec![],
+                network: vec![],
+                filesystem: DbPluginPermissionsFileSystem::default(),
+                exec: DbPluginPermissionsExec::default(),
+                system: vec![],
+                clipboard: vec![],
+                main_search_bar: vec![],
+                high_resolution_time: false,
+                list_entrypoints: false,
+                ffi: vec![],
+            },
+            plugin_type: "normal".to_string(),
+            preferences: HashMap::new(),
+            version: None,
+            min_app_version: None,
+            max_app_version: None,
+        }).await.unwrap();
+
+        repository
+    }
+
+    // frontend which answers every permission prompt with the same decision
+    fn mock_frontend(granted: bool) -> (FrontendApi, Arc<AtomicUsize>) {
+        let (sender, mut receiver) = utils::channel::channel::<UiRequestData, UiResponseData>();
+        let prompts = Arc::new(AtomicUsize::new(0));
+
+        let prompts_clone = prompts.clone();
+        tokio::spawn(async move {
+            loop {
+                let (request, responder) = receiver.recv().await;
+
+                match request {
+                    UiRequestData::RequestPermission { .. } => {
+                        prompts_clone.fetch_add(1, Ordering::SeqCst);
+                        responder.respond(UiResponseData::PermissionDecision { granted })
+                    }
+                    _ => responder.respond(UiResponseData::Nothing)
+                }
+            }
+        });
+
+        (FrontendApi::new(sender), prompts)
+    }
+
+    #[tokio::test]
+    async fn granted_permission_is_remembered() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(dir.path()).await;
+        let audit_log = PluginAuditLog::new(repository.clone());
+        let (mut frontend_api, prompts) = mock_frontend(true);
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        let granted = permission_decision(&repository, &audit_log, &mut frontend_api, plugin_id.clone(), "Test Plugin".to_string(), "network", "example.com").await.unwrap();
+        assert!(granted);
+
+        let granted = permission_decision(&repository, &audit_log, &mut frontend_api, plugin_id, "Test Plugin".to_string(), "network", "example.com").await.unwrap();
+        assert!(granted);
+
+        assert_eq!(prompts.load(Ordering::SeqCst), 1);
+        assert_eq!(repository.get_permission_decision("test-plugin", "network", "example.com").await.unwrap(), Some(true));
+    }
+
+    #[tokio::test]
+    async fn denied_permission_is_remembered() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(dir.path()).await;
+        let audit_log = PluginAuditLog::new(repository.clone());
+        let (mut frontend_api, prompts) = mock_frontend(false);
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        let granted = permission_decision(&repository, &audit_log, &mut frontend_api, plugin_id.clone(), "Test Plugin".to_string(), "environment", "HOME").await.unwrap();
+        assert!(!granted);
+
+        let granted = permission_decision(&repository, &audit_log, &mut frontend_api, plugin_id, "Test Plugin".to_string(), "environment", "HOME").await.unwrap();
+        assert!(!granted);
+
+        assert_eq!(prompts.load(Ordering::SeqCst), 1);
+        assert_eq!(repository.get_permission_decision("test-plugin", "environment", "HOME").await.unwrap(), Some(false));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn executable_is_allowed_only_if_in_allowlist() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = std::fs::canonicalize(dir.path()).unwrap();
+        let executable = dir_path.join("tool");
+        let other_executable = dir_path.join("other-tool");
+
+        std::fs::write(&executable, "").unwrap();
+        std::fs::write(&other_executable, "").unwrap();
+
+        let exec_allowlist = vec![executable.clone()];
+
+        assert_eq!(resolve_allowed_executable(&exec_allowlist, executable.to_str().unwrap()), Some(executable.clone()));
+        assert_eq!(resolve_allowed_executable(&exec_allowlist, dir_path.join("missing/../tool").to_str().unwrap()), Some(executable.clone()));
+        assert_eq!(resolve_allowed_executable(&exec_allowlist, other_executable.to_str().unwrap()), None);
+        assert_eq!(resolve_allowed_executable(&[], executable.to_str().unwrap()), None);
+    }
+
+    #[tokio::test]
+    async fn network_permission_decision_is_audited() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(dir.path()).await;
+        let audit_log = PluginAuditLog::new(repository.clone());
+        let (mut frontend_api, _) = mock_frontend(true);
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        permission_decision(&repository, &audit_log, &mut frontend_api, plugin_id, "Test Plugin".to_string(), "network", "example.com:443").await.unwrap();
+
+        // entry is written in background
+        let mut entries = vec![];
+        for _ in 0..50 {
+            entries = repository.list_audit_entries("test-plugin").await.unwrap();
+
+            if !entries.is_empty() {
+                break
+            }
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, "permission-granted");
+        assert_eq!(entries[0].target, "network:example.com:443");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn library_outside_of_ffi_allowlist_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = std::fs::canonicalize(dir.path()).unwrap();
+        let library = dir_path.join("libexample.so");
+        let other_library = dir_path.join("libother.so");
+
+        std::fs::write(&library, "").unwrap();
+        std::fs::write(&other_library, "").unwrap();
+        std::os::unix::fs::symlink(&other_library, dir_path.join("libexample-link.so")).unwrap();
+
+        let ffi_allowlist = vec![library.clone()];
+
+        assert_eq!(resolve_allowed_library(&ffi_allowlist, library.to_str().unwrap()).unwrap(), library);
+        assert!(resolve_allowed_library(&ffi_allowlist, other_library.to_str().unwrap()).is_err());
+        assert!(resolve_allowed_library(&ffi_allowlist, dir_path.join("libexample-link.so").to_str().unwrap()).is_err());
+        assert!(resolve_allowed_library(&[], library.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn only_granted_decisions_are_returned() {
+        let mut cache = PermissionDecisionCache::new();
+
+        cache.decisions.insert(("network".to_string(), "example.com".to_string()), true);
+        cache.decisions.insert(("network".to_string(), "denied.com".to_string()), false);
+        cache.decisions.insert(("filesystem-read".to_string(), "/tmp/granted".to_string()), true);
+        cache.decisions.insert(("filesystem-read".to_string(), "/tmp/denied".to_string()), false);
+
+        assert_eq!(cache.granted_hosts(), vec!["example.com".to_string()]);
+        assert_eq!(cache.granted_paths("filesystem-read"), vec![PathBuf::from("/tmp/granted")]);
+        assert!(cache.granted_paths("filesystem-write").is_empty());
+    }
+
+    #[test]
+    fn wildcard_network_permission_is_not_granted_to_global_fetch() {
+        let permission = net_permission(&["*.example.com".to_string(), "example.com".to_string()]);
+
+        assert_eq!(permission.granted_list.len(), 1);
+        assert!(is_host_allowed(&["*.example.com".to_string()], "api.example.com", Some(443)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_permission_is_granted_for_resolved_paths_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = std::fs::canonicalize(dir.path()).unwrap();
+        let executable = dir_path.join("tool");
+        let link = dir_path.join("tool-link");
+
+        std::fs::write(&executable, "").unwrap();
+        std::os::unix::fs::symlink(&executable, &link).unwrap();
+
+        let permissions = PluginPermissionsExec {
+            command: vec![],
+            executable: vec![link.to_str().unwrap().to_string()],
+        };
+
+        let permission = run_permission(&permissions, &Dirs::new(), "uuid").unwrap();
+
+        assert_eq!(permission.granted_list, HashSet::from([RunDescriptor::Path(executable)]));
+    }
+}
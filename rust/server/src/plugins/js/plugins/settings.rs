@@ -1,4 +1,15 @@
-use deno_core::op;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use deno_core::{op, OpState};
+use serde::Serialize;
+
+use common::{settings_env_data_to_string, SettingsEnvData};
+
+use crate::plugins::data_db_repository::DataDbRepository;
+use crate::plugins::js::PluginData;
+use crate::SETTINGS_ENV;
 
 #[op]
 fn open_settings() -> anyhow::Result<()> {
@@ -7,4 +18,68 @@ fn open_settings() -> anyhow::Result<()> {
         .spawn()?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[op]
+fn open_settings_preferences(plugin_id: String, entrypoint_id: Option<String>) -> anyhow::Result<()> {
+    let data = match entrypoint_id {
+        Some(entrypoint_id) => SettingsEnvData::OpenEntrypointPreferences { plugin_id, entrypoint_id },
+        None => SettingsEnvData::OpenPluginPreferences { plugin_id },
+    };
+
+    std::process::Command::new(std::env::current_exe()?)
+        .args(["settings"])
+        .env(SETTINGS_ENV, settings_env_data_to_string(data))
+        .spawn()?;
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct JsPreferenceSearchResult {
+    #[serde(rename = "pluginId")]
+    plugin_id: String,
+    #[serde(rename = "pluginName")]
+    plugin_name: String,
+    #[serde(rename = "entrypointId")]
+    entrypoint_id: Option<String>,
+    #[serde(rename = "entrypointName")]
+    entrypoint_name: Option<String>,
+    #[serde(rename = "preferenceId")]
+    preference_id: String,
+    #[serde(rename = "preferenceName")]
+    preference_name: String,
+    description: String,
+}
+
+#[op]
+async fn search_all_preferences(state: Rc<RefCell<OpState>>, text: String) -> anyhow::Result<Vec<JsPreferenceSearchResult>> {
+    let repository = {
+        let state = state.borrow();
+
+        // exposes preferences of every installed plugin, so only available to bundled plugin
+        if state.borrow::<PluginData>().plugin_id().to_string() != "bundled://gauntlet" {
+            return Err(anyhow!("plugin id is not equal to 'bundled://gauntlet'"))
+        }
+
+        state.borrow::<DataDbRepository>().clone()
+    };
+
+    let result = repository.search_preferences(&text)
+        .await?
+        .into_iter()
+        .map(|preference| {
+            JsPreferenceSearchResult {
+                plugin_id: preference.plugin_id,
+                plugin_name: preference.plugin_name,
+                entrypoint_id: preference.entrypoint_id,
+                entrypoint_name: preference.entrypoint_name,
+                preference_id: preference.preference_id,
+                preference_name: preference.preference_name,
+                description: preference.description,
+            }
+        })
+        .collect();
+
+    Ok(result)
+}
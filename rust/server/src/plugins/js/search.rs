@@ -1,18 +1,19 @@
-use crate::plugins::data_db_repository::{db_entrypoint_from_str, DataDbRepository, DbPluginEntrypointType, DbReadPlugin, DbReadPluginEntrypoint};
+use crate::plugins::data_db_repository::{db_entrypoint_from_str, DataDbRepository, DbPluginActionShortcutKind, DbPluginEntrypointType, DbReadPlugin, DbReadPluginEntrypoint};
+use crate::plugins::enabled_cache::EnabledStateCache;
 use crate::plugins::icon_cache::IconCache;
 use crate::plugins::js::PluginData;
 use crate::search::{SearchIndex, SearchIndexItem, SearchIndexItemAction};
-use anyhow::Context;
-use common::model::{EntrypointId, SearchResultEntrypointType};
+use anyhow::{anyhow, Context};
+use common::model::{EntrypointId, PhysicalShortcut, PluginId, SearchResultEntrypointType};
 use deno_core::{op, OpState};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 #[op]
 async fn reload_search_index(state: Rc<RefCell<OpState>>, generated_commands: Vec<AdditionalSearchItem>, refresh_search_list: bool) -> anyhow::Result<()> {
-    let (plugin_id, plugin_uuid, repository, mut search_index, icon_cache) = {
+    let (plugin_id, plugin_uuid, repository, enabled_state_cache, mut search_index, icon_cache) = {
         let state = state.borrow();
 
         let plugin_data = state.borrow::<PluginData>();
@@ -29,6 +30,10 @@ async fn reload_search_index(state: Rc<RefCell<OpState>>, generated_commands: Ve
             .borrow::<DataDbRepository>()
             .clone();
 
+        let enabled_state_cache = state
+            .borrow::<EnabledStateCache>()
+            .clone();
+
         let search_index = state
             .borrow::<SearchIndex>()
             .clone();
@@ -37,10 +42,23 @@ async fn reload_search_index(state: Rc<RefCell<OpState>>, generated_commands: Ve
             .borrow::<IconCache>()
             .clone();
 
-        (plugin_id, plugin_uuid, repository, search_index, icon_cache)
+        (plugin_id, plugin_uuid, repository, enabled_state_cache, search_index, icon_cache)
     };
 
-    icon_cache.clear_plugin_icon_cache_dir(&plugin_uuid)
+    reload_plugin_search_index(&repository, &enabled_state_cache, &mut search_index, &icon_cache, plugin_id, &plugin_uuid, generated_commands, refresh_search_list).await
+}
+
+async fn reload_plugin_search_index(
+    repository: &DataDbRepository,
+    enabled_state_cache: &EnabledStateCache,
+    search_index: &mut SearchIndex,
+    icon_cache: &IconCache,
+    plugin_id: PluginId,
+    plugin_uuid: &str,
+    generated_commands: Vec<AdditionalSearchItem>,
+    refresh_search_list: bool
+) -> anyhow::Result<()> {
+    icon_cache.clear_plugin_icon_cache_dir(plugin_uuid)
         .context("error when clearing up icon cache before recreating it")?;
 
     let DbReadPlugin { name, .. } = repository.get_plugin_by_id(&plugin_id.to_string())
@@ -62,11 +80,17 @@ async fn reload_search_index(state: Rc<RefCell<OpState>>, generated_commands: Ve
         shortcuts.insert(id.clone(), entrypoint_shortcuts);
     }
 
+    let non_searchable_generators = entrypoints.iter()
+        .filter(|entrypoint| !entrypoint.searchable)
+        .map(|entrypoint| entrypoint.id.clone())
+        .collect::<Vec<_>>();
+
     let mut plugins_search_items = generated_commands.into_iter()
+        .filter(|item| !non_searchable_generators.contains(&item.generator_entrypoint_id))
         .map(|item| {
             let entrypoint_icon_path = match item.entrypoint_icon {
                 None => None,
-                Some(data) => Some(icon_cache.save_entrypoint_icon_to_cache(&plugin_uuid, &item.entrypoint_uuid, &data)?),
+                Some(data) => Some(icon_cache.save_entrypoint_icon_to_cache(plugin_uuid, &item.entrypoint_uuid, &data)?),
             };
 
             let entrypoint_frecency = frecency_map.get(&item.entrypoint_id).cloned().unwrap_or(0.0);
@@ -94,6 +118,7 @@ async fn reload_search_index(state: Rc<RefCell<OpState>>, generated_commands: Ve
                 entrypoint_type: SearchResultEntrypointType::GeneratedCommand,
                 entrypoint_id: EntrypointId::from_string(item.entrypoint_id),
                 entrypoint_name: item.entrypoint_name,
+                entrypoint_description: String::new(),
                 entrypoint_icon_path,
                 entrypoint_frecency,
                 entrypoint_actions,
@@ -114,10 +139,20 @@ async fn reload_search_index(state: Rc<RefCell<OpState>>, generated_commands: Ve
         }
     }
 
+    // enabled state is taken from cache instead of db row, same as when entrypoint is run from search
+    let mut enabled_entrypoints = HashSet::new();
+
+    for entrypoint in &entrypoints {
+        if enabled_state_cache.is_entrypoint_enabled(&plugin_id, &EntrypointId::from_string(&entrypoint.id)).await? {
+            enabled_entrypoints.insert(entrypoint.id.clone());
+        }
+    }
+
     let mut builtin_search_items = entrypoints.into_iter()
-        .filter(|entrypoint| entrypoint.enabled)
+        .filter(|entrypoint| enabled_entrypoints.contains(&entrypoint.id) && entrypoint.searchable)
         .map(|entrypoint| {
             let entrypoint_type = db_entrypoint_from_str(&entrypoint.entrypoint_type);
+            let entrypoint_actions = entrypoint_search_actions(&entrypoint, shortcuts.get(&entrypoint.id));
             let entrypoint_id = entrypoint.id.to_string();
 
             let entrypoint_frecency = frecency_map.get(&entrypoint_id).cloned().unwrap_or(0.0);
@@ -127,7 +162,7 @@ async fn reload_search_index(state: Rc<RefCell<OpState>>, generated_commands: Ve
                 Some(path_to_asset) => {
                     match icon_asset_data.get(&(entrypoint.id, path_to_asset)) {
                         None => None,
-                        Some(data) => Some(icon_cache.save_entrypoint_icon_to_cache(&plugin_uuid, &entrypoint.uuid, data)?)
+                        Some(data) => Some(icon_cache.save_entrypoint_icon_to_cache(plugin_uuid, &entrypoint.uuid, data)?)
                     }
                 },
             };
@@ -139,6 +174,7 @@ async fn reload_search_index(state: Rc<RefCell<OpState>>, generated_commands: Ve
                     Ok(Some(SearchIndexItem {
                         entrypoint_type: SearchResultEntrypointType::Command,
                         entrypoint_name: entrypoint.name,
+                        entrypoint_description: entrypoint.description,
                         entrypoint_id,
                         entrypoint_icon_path,
                         entrypoint_frecency,
@@ -149,10 +185,11 @@ async fn reload_search_index(state: Rc<RefCell<OpState>>, generated_commands: Ve
                     Ok(Some(SearchIndexItem {
                         entrypoint_type: SearchResultEntrypointType::View,
                         entrypoint_name: entrypoint.name,
+                        entrypoint_description: entrypoint.description,
                         entrypoint_id,
                         entrypoint_icon_path,
                         entrypoint_frecency,
-                        entrypoint_actions: vec![],
+                        entrypoint_actions,
                     }))
                 },
                 DbPluginEntrypointType::CommandGenerator | DbPluginEntrypointType::InlineView => {
@@ -173,6 +210,24 @@ async fn reload_search_index(state: Rc<RefCell<OpState>>, generated_commands: Ve
     Ok(())
 }
 
+// actions declared in manifest are available in view, so they are shown in search for view entrypoints.
+// main action goes first, so it gets primary position in action panel
+fn entrypoint_search_actions(entrypoint: &DbReadPluginEntrypoint, shortcuts: Option<&HashMap<String, PhysicalShortcut>>) -> Vec<SearchIndexItemAction> {
+    let mut actions = entrypoint.actions.iter().collect::<Vec<_>>();
+
+    actions.sort_by_key(|action| match action.kind {
+        DbPluginActionShortcutKind::Main => 0,
+        DbPluginActionShortcutKind::Alternative => 1,
+    });
+
+    actions.into_iter()
+        .map(|action| SearchIndexItemAction {
+            label: action.description.clone(),
+            shortcut: shortcuts.and_then(|shortcuts| shortcuts.get(&action.id).cloned()),
+        })
+        .collect()
+}
+
 #[derive(Debug, Deserialize)]
 struct AdditionalSearchItem {
     entrypoint_name: String,
@@ -187,4 +242,255 @@ struct AdditionalSearchItem {
 pub struct AdditionalSearchItemAction {
     id: Option<String>,
     label: String,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Serialize)]
+struct JsInstalledEntrypoint {
+    #[serde(rename = "id")]
+    entrypoint_id: String,
+    #[serde(rename = "name")]
+    entrypoint_name: String,
+    #[serde(rename = "pluginId")]
+    plugin_id: String,
+    #[serde(rename = "pluginName")]
+    plugin_name: String,
+}
+
+// lists the same entrypoints main search shows, generated commands are not included
+#[op]
+async fn op_list_entrypoints(state: Rc<RefCell<OpState>>) -> anyhow::Result<Vec<JsInstalledEntrypoint>> {
+    let (repository, list_entrypoints) = {
+        let state = state.borrow();
+
+        let list_entrypoints = state
+            .borrow::<PluginData>()
+            .permissions()
+            .list_entrypoints;
+
+        let repository = state
+            .borrow::<DataDbRepository>()
+            .clone();
+
+        (repository, list_entrypoints)
+    };
+
+    list_installed_entrypoints(&repository, list_entrypoints).await
+}
+
+async fn list_installed_entrypoints(repository: &DataDbRepository, list_entrypoints: bool) -> anyhow::Result<Vec<JsInstalledEntrypoint>> {
+    if !list_entrypoints {
+        return Err(anyhow!("Plugin doesn't have 'list_entrypoints' permission"))
+    }
+
+    let result = repository.list_plugins_and_entrypoints()
+        .await?
+        .into_iter()
+        .filter(|(plugin, _)| plugin.enabled)
+        .flat_map(|(plugin, entrypoints)| {
+            entrypoints.into_iter()
+                .filter(|entrypoint| entrypoint.enabled && entrypoint.searchable)
+                .map(move |entrypoint| JsInstalledEntrypoint {
+                    entrypoint_id: entrypoint.id,
+                    entrypoint_name: entrypoint.name,
+                    plugin_id: plugin.id.clone(),
+                    plugin_name: plugin.name.clone(),
+                })
+        })
+        .collect();
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::dirs::Dirs;
+    use common::model::PhysicalKey;
+    use common::rpc::frontend_api::FrontendApi;
+    use crate::plugins::data_db_repository::{DbCode, DbPluginAction, DbPluginPermissions, DbPluginPermissionsExec, DbPluginPermissionsFileSystem, DbWritePlugin, DbWritePluginEntrypoint};
+    use super::*;
+
+    fn entrypoint(id: &str, name: &str) -> DbWritePluginEntrypoint {
+        DbWritePluginEntrypoint {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: "".to_string(),
+            icon_path: None,
+            entrypoint_type: "view".to_string(),
+            preferences: HashMap::new(),
+            actions: vec![],
+        }
+    }
+
+    async fn open_repository(dir: &std::path::Path) -> DataDbRepository {
+        let repository = DataDbRepository::open(&dir.join("data.db")).await.unwrap();
+
+        repository.save_plugin(DbWritePlugin {
+            id: "test-plugin".to_string(),
+            name: "Test Plugin".to_string(),
+            description: "".to_string(),
+            enabled: true,
+            code: DbCode { js: HashMap::new(), linked_path: None },
+            entrypoints: vec![
+                entrypoint("visible", "Visible View"),
+                entrypoint("hidden", "Hidden View"),
+            ],
+            asset_data: vec![],
+            permissions: DbPluginPermissions {
+                environment: vec![],
+                network: vec![],
+                filesystem: DbPluginPermissionsFileSystem::default(),
+                exec: DbPluginPermissionsExec::default(),
+                system: vec![],
+                clipboard: vec![],
+                main_search_bar: vec![],
+                high_resolution_time: false,
+                list_entrypoints: false,
+                ffi: vec![],
+            },
+            plugin_type: "normal".to_string(),
+            preferences: HashMap::new(),
+            version: None,
+            min_app_version: None,
+            max_app_version: None,
+        }).await.unwrap();
+
+        repository
+    }
+
+    #[tokio::test]
+    async fn non_searchable_entrypoint_is_not_indexed_but_remains_runnable() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(dir.path()).await;
+
+        repository.set_plugin_entrypoint_searchable("test-plugin", "hidden", false).await.unwrap();
+
+        let (frontend_sender, _) = utils::channel::channel();
+        let mut search_index = SearchIndex::open_or_create(FrontendApi::new(frontend_sender), &dir.path().join("search_index")).unwrap();
+        let icon_cache = IconCache::new(Dirs::new());
+
+        let enabled_state_cache = EnabledStateCache::new(repository.clone());
+
+        reload_plugin_search_index(&repository, &enabled_state_cache, &mut search_index, &icon_cache, PluginId::from_string("test-plugin"), "test-plugin-uuid", vec![], false)
+            .await
+            .unwrap();
+
+        // index reader picks up the commit in background
+        let mut results = vec![];
+        for _ in 0..100 {
+            results = search_index.search("view").unwrap();
+
+            if !results.is_empty() {
+                break
+            }
+
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entrypoint_id, EntrypointId::from_string("visible"));
+
+        // entrypoint can still be found and run directly
+        let hidden = repository.get_entrypoint_by_id("test-plugin", "hidden").await.unwrap();
+
+        assert!(hidden.enabled);
+        assert!(!hidden.searchable);
+    }
+
+    #[tokio::test]
+    async fn entrypoint_disabled_through_cache_is_not_indexed() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(dir.path()).await;
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        let (frontend_sender, _) = utils::channel::channel();
+        let mut search_index = SearchIndex::open_or_create(FrontendApi::new(frontend_sender), &dir.path().join("search_index")).unwrap();
+        let icon_cache = IconCache::new(Dirs::new());
+        let enabled_state_cache = EnabledStateCache::new(repository.clone());
+
+        // value is cached before toggle, so stale read would keep entrypoint in index
+        assert!(enabled_state_cache.is_entrypoint_enabled(&plugin_id, &EntrypointId::from_string("hidden")).await.unwrap());
+
+        enabled_state_cache.set_entrypoint_enabled(&plugin_id, &EntrypointId::from_string("hidden"), false).await.unwrap();
+
+        reload_plugin_search_index(&repository, &enabled_state_cache, &mut search_index, &icon_cache, plugin_id, "test-plugin-uuid", vec![], false)
+            .await
+            .unwrap();
+
+        // index reader picks up the commit in background
+        let mut results = vec![];
+        for _ in 0..100 {
+            results = search_index.search("view").unwrap();
+
+            if !results.is_empty() {
+                break
+            }
+
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entrypoint_id, EntrypointId::from_string("visible"));
+    }
+
+    #[tokio::test]
+    async fn enabled_searchable_entrypoints_are_listed_for_permitted_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(dir.path()).await;
+
+        repository.set_plugin_entrypoint_searchable("test-plugin", "hidden", false).await.unwrap();
+
+        let entrypoints = list_installed_entrypoints(&repository, true).await.unwrap();
+
+        assert_eq!(entrypoints.len(), 1);
+        assert_eq!(entrypoints[0].entrypoint_id, "visible");
+        assert_eq!(entrypoints[0].entrypoint_name, "Visible View");
+        assert_eq!(entrypoints[0].plugin_id, "test-plugin");
+
+        repository.set_plugin_entrypoint_enabled("test-plugin", "visible", false).await.unwrap();
+
+        assert!(list_installed_entrypoints(&repository, true).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn entrypoints_are_not_listed_without_permission() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(dir.path()).await;
+
+        assert!(list_installed_entrypoints(&repository, false).await.is_err());
+    }
+
+    fn shortcut(physical_key: PhysicalKey) -> PhysicalShortcut {
+        PhysicalShortcut {
+            physical_key,
+            modifier_shift: false,
+            modifier_control: true,
+            modifier_alt: false,
+            modifier_meta: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn main_action_is_shown_first_with_its_shortcut() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = open_repository(dir.path()).await;
+
+        let mut entrypoint = repository.get_entrypoint_by_id("test-plugin", "visible").await.unwrap();
+        entrypoint.actions = vec![
+            DbPluginAction { id: "copy".to_string(), description: "Copy Path".to_string(), key: "c".to_string(), kind: DbPluginActionShortcutKind::Alternative },
+            DbPluginAction { id: "open".to_string(), description: "Open".to_string(), key: "o".to_string(), kind: DbPluginActionShortcutKind::Main },
+        ];
+
+        let shortcuts = HashMap::from([
+            ("copy".to_string(), shortcut(PhysicalKey::KeyC)),
+            ("open".to_string(), shortcut(PhysicalKey::KeyO)),
+        ]);
+
+        let actions = entrypoint_search_actions(&entrypoint, Some(&shortcuts));
+
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0].label, "Open");
+        assert!(matches!(actions[0].shortcut, Some(PhysicalShortcut { physical_key: PhysicalKey::KeyO, .. })));
+        assert_eq!(actions[1].label, "Copy Path");
+        assert!(matches!(actions[1].shortcut, Some(PhysicalShortcut { physical_key: PhysicalKey::KeyC, .. })));
+    }
+}
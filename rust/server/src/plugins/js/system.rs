@@ -0,0 +1,410 @@
+use std::cell::RefCell;
+use std::process::Stdio;
+use std::rc::Rc;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use deno_core::{op, OpState};
+use serde::Serialize;
+
+use crate::plugins::audit_log::PluginAuditLog;
+use crate::plugins::js::permissions::{resolve_allowed_executable, resolve_allowed_library};
+use crate::plugins::js::PluginData;
+
+#[op]
+fn op_system_info(state: Rc<RefCell<OpState>>, kind: String) -> anyhow::Result<String> {
+    let state = state.borrow();
+
+    let granted = &state
+        .borrow::<PluginData>()
+        .permissions()
+        .system;
+
+    system_info(granted, &kind)
+}
+
+fn system_info(granted: &[String], kind: &str) -> anyhow::Result<String> {
+    if !granted.iter().any(|granted| granted == kind) {
+        return Err(anyhow!("Plugin doesn't have '{}' system permission", kind));
+    }
+
+    match kind {
+        "os" => Ok(std::env::consts::OS.to_string()),
+        "arch" => Ok(std::env::consts::ARCH.to_string()),
+        "hostname" => hostname(),
+        "osRelease" => os_release(),
+        _ => Err(anyhow!("Unknown system info kind: {}", kind))
+    }
+}
+
+#[op]
+fn op_get_env(state: Rc<RefCell<OpState>>, name: String) -> anyhow::Result<Option<String>> {
+    let state = state.borrow();
+
+    let granted = &state
+        .borrow::<PluginData>()
+        .permissions()
+        .environment;
+
+    get_env(granted, &name)
+}
+
+fn get_env(granted: &[String], name: &str) -> anyhow::Result<Option<String>> {
+    if !granted.iter().any(|granted| granted == name) {
+        return Err(anyhow!("Plugin doesn't have permission to read '{}' environment variable", name));
+    }
+
+    Ok(std::env::var(name).ok())
+}
+
+#[op]
+fn op_ffi_resolve_library(state: Rc<RefCell<OpState>>, path: String) -> anyhow::Result<String> {
+    let state = state.borrow();
+
+    let ffi_allowlist = &state
+        .borrow::<PluginData>()
+        .permissions()
+        .ffi_allowlist;
+
+    let resolved = resolve_allowed_library(ffi_allowlist, &path)?;
+
+    Ok(resolved.to_str().expect("non-utf8 file paths are not supported").to_string())
+}
+
+// application is started detached, so it keeps running after plugin is stopped
+#[op]
+fn op_launch_application(state: Rc<RefCell<OpState>>, path: String, args: Vec<String>) -> anyhow::Result<()> {
+    let (plugin_id, audit_log, exec_allowlist) = {
+        let state = state.borrow();
+
+        let plugin_data = state.borrow::<PluginData>();
+
+        (plugin_data.plugin_id(), state.borrow::<PluginAuditLog>().clone(), plugin_data.permissions().exec_allowlist.clone())
+    };
+
+    launch_application(&exec_allowlist, &path, &args, |path, args| {
+        audit_log.record(&plugin_id, "launch-application", path);
+
+        launch_detached(path, args)
+    })
+}
+
+fn launch_application<F>(exec_allowlist: &[PathBuf], path: &str, args: &[String], launch: F) -> anyhow::Result<()>
+where
+    F: FnOnce(&str, &[String]) -> anyhow::Result<()>,
+{
+    let Some(resolved) = resolve_allowed_executable(exec_allowlist, path) else {
+        return Err(anyhow!("Plugin doesn't have permission to launch '{}'", path));
+    };
+
+    // exact path that passed the allowlist check is started, instead of looking up PATH again
+    launch(resolved.to_str().expect("non-utf8 file paths are not supported"), args)
+}
+
+const SUBPROCESS_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Serialize)]
+pub struct SubprocessOutput {
+    stdout: String,
+    stderr: String,
+    // not set if process was terminated by a signal
+    code: Option<i32>,
+}
+
+// program is started directly without a shell, so arguments are never interpreted by one
+#[op]
+async fn op_run_subprocess(state: Rc<RefCell<OpState>>, program: String, args: Vec<String>) -> anyhow::Result<SubprocessOutput> {
+    let (plugin_id, audit_log, resolved) = {
+        let state = state.borrow();
+
+        let plugin_data = state.borrow::<PluginData>();
+
+        let resolved = check_subprocess_allowed(&plugin_data.permissions().exec_allowlist, &program)?;
+
+        (plugin_data.plugin_id(), state.borrow::<PluginAuditLog>().clone(), resolved)
+    };
+
+    audit_log.record(&plugin_id, "run-subprocess", &resolved.to_string_lossy());
+
+    run_subprocess(&resolved, &args, SUBPROCESS_TIMEOUT).await
+}
+
+fn check_subprocess_allowed(exec_allowlist: &[PathBuf], program: &str) -> anyhow::Result<PathBuf> {
+    let Some(resolved) = resolve_allowed_executable(exec_allowlist, program) else {
+        return Err(anyhow!("Plugin doesn't have permission to run '{}'", program));
+    };
+
+    Ok(resolved)
+}
+
+async fn run_subprocess(program: &Path, args: &[String], timeout: Duration) -> anyhow::Result<SubprocessOutput> {
+    // exact path that passed the allowlist check is started, instead of looking up PATH again
+    let child = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // child is dropped and therefore killed on timeout
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| anyhow!("Process '{}' didn't finish in {} seconds", program.display(), timeout.as_secs()))??;
+
+    Ok(SubprocessOutput {
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        code: output.status.code(),
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn launch_detached(path: &str, args: &[String]) -> anyhow::Result<()> {
+    use crate::plugins::js::plugins::applications::spawn_detached;
+
+    // app bundles are directories and can only be started through launch services
+    if path.ends_with(".app") {
+        let open_args = ["-a".to_string(), path.to_string(), "--args".to_string()]
+            .into_iter()
+            .chain(args.iter().cloned())
+            .collect::<Vec<_>>();
+
+        spawn_detached("/usr/bin/open", &open_args[..])?;
+    } else {
+        spawn_detached(path, args)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn launch_detached(path: &str, args: &[String]) -> anyhow::Result<()> {
+    use crate::plugins::js::plugins::applications::spawn_detached;
+
+    spawn_detached(path, args)?;
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn launch_detached(path: &str, args: &[String]) -> anyhow::Result<()> {
+    use std::os::windows::process::CommandExt;
+    use std::process::{Command, Stdio};
+
+    const DETACHED_PROCESS: u32 = 0x00000008;
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+
+    Command::new(path)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP)
+        .spawn()?;
+
+    Ok(())
+}
+
+pub struct TimeOrigin(Instant);
+
+impl TimeOrigin {
+    pub fn new() -> Self {
+        Self(Instant::now())
+    }
+}
+
+// milliseconds since plugin start, coarsened to whole milliseconds without permission, same as browsers do
+#[op]
+fn op_performance_now(state: Rc<RefCell<OpState>>) -> f64 {
+    let state = state.borrow();
+
+    let elapsed = state.borrow::<TimeOrigin>().0.elapsed();
+
+    let high_resolution_time = state
+        .borrow::<PluginData>()
+        .permissions()
+        .high_resolution_time;
+
+    performance_now(elapsed, high_resolution_time)
+}
+
+fn performance_now(elapsed: Duration, high_resolution_time: bool) -> f64 {
+    if high_resolution_time {
+        elapsed.as_secs_f64() * 1000.0
+    } else {
+        elapsed.as_millis() as f64
+    }
+}
+
+#[cfg(unix)]
+fn uname() -> anyhow::Result<libc::utsname> {
+    let mut info = unsafe { std::mem::zeroed::<libc::utsname>() };
+
+    let result = unsafe { libc::uname(&mut info) };
+
+    if result != 0 {
+        return Err(anyhow!("Unable to get system info: {}", std::io::Error::last_os_error()))
+    }
+
+    Ok(info)
+}
+
+#[cfg(unix)]
+fn utsname_field_to_string(field: &[libc::c_char]) -> String {
+    let bytes = field.iter()
+        .take_while(|char| **char != 0)
+        .map(|char| *char as u8)
+        .collect::<Vec<_>>();
+
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+#[cfg(unix)]
+fn hostname() -> anyhow::Result<String> {
+    Ok(utsname_field_to_string(&uname()?.nodename))
+}
+
+#[cfg(unix)]
+fn os_release() -> anyhow::Result<String> {
+    Ok(utsname_field_to_string(&uname()?.release))
+}
+
+#[cfg(windows)]
+fn hostname() -> anyhow::Result<String> {
+    std::env::var("COMPUTERNAME")
+        .map_err(|_| anyhow!("Unable to get hostname"))
+}
+
+#[cfg(windows)]
+fn os_release() -> anyhow::Result<String> {
+    Err(anyhow!("'osRelease' system info is not supported on windows"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ungranted_system_info_is_rejected() {
+        let granted = vec!["os".to_string()];
+
+        assert!(system_info(&granted, "arch").is_err());
+        assert!(system_info(&[], "os").is_err());
+    }
+
+    #[test]
+    fn granted_system_info_is_returned() {
+        let granted = vec!["os".to_string(), "arch".to_string()];
+
+        assert_eq!(system_info(&granted, "os").unwrap(), std::env::consts::OS);
+        assert_eq!(system_info(&granted, "arch").unwrap(), std::env::consts::ARCH);
+    }
+
+    #[test]
+    fn unknown_system_info_is_rejected_even_if_granted() {
+        let granted = vec!["unknown".to_string()];
+
+        assert!(system_info(&granted, "unknown").is_err());
+    }
+
+    #[test]
+    fn granted_environment_variable_is_readable() {
+        std::env::set_var("GAUNTLET_TEST_GET_ENV_GRANTED", "value");
+
+        let granted = vec!["GAUNTLET_TEST_GET_ENV_GRANTED".to_string()];
+
+        assert_eq!(get_env(&granted, "GAUNTLET_TEST_GET_ENV_GRANTED").unwrap(), Some("value".to_string()));
+    }
+
+    #[test]
+    fn ungranted_environment_variable_is_rejected_even_if_set() {
+        std::env::set_var("GAUNTLET_TEST_GET_ENV_UNGRANTED", "secret");
+
+        let granted = vec!["GAUNTLET_TEST_GET_ENV_GRANTED".to_string()];
+
+        assert!(get_env(&granted, "GAUNTLET_TEST_GET_ENV_UNGRANTED").is_err());
+    }
+
+    #[test]
+    fn granted_but_unset_environment_variable_is_none() {
+        let granted = vec!["GAUNTLET_TEST_GET_ENV_UNSET".to_string()];
+
+        assert_eq!(get_env(&granted, "GAUNTLET_TEST_GET_ENV_UNSET").unwrap(), None);
+    }
+
+    #[test]
+    fn granted_performance_now_has_sub_millisecond_precision() {
+        let now = performance_now(Duration::from_micros(1500), true);
+
+        assert!((now - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ungranted_performance_now_is_coarsened_to_milliseconds() {
+        assert_eq!(performance_now(Duration::from_micros(1500), false), 1.0);
+        assert_eq!(performance_now(Duration::from_micros(999), false), 0.0);
+    }
+
+    #[test]
+    fn application_is_launched_only_if_allowed() {
+        let dir = tempfile::tempdir().unwrap();
+        let application = std::fs::canonicalize(dir.path()).unwrap().join("editor");
+
+        std::fs::write(&application, "").unwrap();
+
+        let application_path = application.to_str().unwrap();
+        let args = vec!["--new-window".to_string()];
+
+        let mut launched = vec![];
+
+        let result = launch_application(&[], application_path, &args, |path, args| {
+            launched.push((path.to_string(), args.to_vec()));
+            Ok(())
+        });
+
+        assert!(result.is_err());
+        assert!(launched.is_empty());
+
+        launch_application(&[application.clone()], application_path, &args, |path, args| {
+            launched.push((path.to_string(), args.to_vec()));
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(launched, vec![(application_path.to_string(), args)]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn output_of_allowed_subprocess_is_captured() {
+        let exec_allowlist = vec![std::fs::canonicalize("/bin/sh").unwrap()];
+
+        let resolved = check_subprocess_allowed(&exec_allowlist, "/bin/sh").unwrap();
+
+        let args = vec!["-c".to_string(), "echo out; echo err >&2; exit 3".to_string()];
+
+        let output = run_subprocess(&resolved, &args, Duration::from_secs(5)).await.unwrap();
+
+        assert_eq!(output.stdout, "out\n");
+        assert_eq!(output.stderr, "err\n");
+        assert_eq!(output.code, Some(3));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn disallowed_subprocess_is_rejected() {
+        let exec_allowlist = vec![std::fs::canonicalize("/bin/sh").unwrap()];
+
+        assert!(check_subprocess_allowed(&exec_allowlist, "/bin/echo").is_err());
+        assert!(check_subprocess_allowed(&[], "/bin/sh").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn subprocess_is_stopped_after_timeout() {
+        let args = vec!["-c".to_string(), "sleep 5".to_string()];
+
+        assert!(run_subprocess(Path::new("/bin/sh"), &args, Duration::from_millis(100)).await.is_err());
+    }
+}
@@ -2,13 +2,15 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::Read;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use anyhow::{anyhow, Context};
 use deno_core::{op, OpState, serde_v8, v8};
 use deno_core::futures::executor::block_on;
 use deno_core::v8::{GetPropertyNamesArgs, KeyConversionMode, PropertyFilter};
 use indexmap::IndexMap;
 use serde::Deserialize;
-use common::model::{EntrypointId, PhysicalKey, UiPropertyValue, UiWidget};
+use common::model::{EntrypointId, PhysicalKey, UiPropertyValue, UiWidget, UiWidgetId};
 use component_model::{Component, Property, PropertyType, SharedType};
 use crate::model::{JsUiRenderLocation, JsUiRequestData, JsUiResponseData, JsUiWidget};
 use crate::plugins::data_db_repository::DataDbRepository;
@@ -16,10 +18,11 @@ use crate::plugins::js::{ComponentModel, make_request, PluginData};
 
 
 #[op]
-fn show_plugin_error_view(state: Rc<RefCell<OpState>>, entrypoint_id: String, render_location: JsUiRenderLocation) -> anyhow::Result<()> {
+fn show_plugin_error_view(state: Rc<RefCell<OpState>>, entrypoint_id: String, render_location: JsUiRenderLocation, message: Option<String>) -> anyhow::Result<()> {
     let data = JsUiRequestData::ShowPluginErrorView {
         entrypoint_id: EntrypointId::from_string(entrypoint_id),
         render_location,
+        message,
     };
 
     match make_request(&state, data).context("ClearInlineView frontend response")? {
@@ -27,7 +30,7 @@ fn show_plugin_error_view(state: Rc<RefCell<OpState>>, entrypoint_id: String, re
             tracing::trace!(target = "renderer_rs", "Calling show_plugin_error_view returned");
             Ok(())
         }
-        value @ _ => panic!("unsupported response type {:?}", value),
+        value @ _ => Err(anyhow!("unsupported response type {:?}", value)),
     }
 }
 
@@ -44,7 +47,7 @@ fn show_preferences_required_view(state: Rc<RefCell<OpState>>, entrypoint_id: St
             tracing::trace!(target = "renderer_rs", "Calling show_preferences_required_view returned");
             Ok(())
         }
-        value @ _ => panic!("unsupported response type {:?}", value),
+        value @ _ => Err(anyhow!("unsupported response type {:?}", value)),
     }
 }
 
@@ -57,7 +60,7 @@ fn clear_inline_view(state: Rc<RefCell<OpState>>) -> anyhow::Result<()> {
             tracing::trace!(target = "renderer_rs", "Calling clear_inline_view returned");
             Ok(())
         }
-        value @ _ => panic!("unsupported response type {:?}", value),
+        value @ _ => Err(anyhow!("unsupported response type {:?}", value)),
     }
 }
 
@@ -84,6 +87,11 @@ fn op_react_replace_view(
     let component_model = comp_state.borrow::<ComponentModel>();
     let entrypoint_names = comp_state.borrow::<PluginData>();
 
+    if !entrypoint_names.view_open_state().accepts_update(&render_location) {
+        tracing::debug!(target = "renderer_rs", "view is already closed, dropping its update");
+        return Ok(())
+    }
+
     let entrypoint_id = EntrypointId::from_string(entrypoint_id);
 
     let entrypoint_name = entrypoint_names.entrypoint_names
@@ -108,10 +116,78 @@ fn op_react_replace_view(
             tracing::trace!(target = "renderer_rs", "Calling op_react_replace_view returned");
             Ok(())
         }
-        value @ _ => panic!("unsupported response type {:?}", value),
+        value @ _ => Err(anyhow!("unsupported response type {:?}", value)),
+    }
+}
+
+// widget ids carry epoch of plugin runtime which rendered them,
+// so events for widgets rendered before plugin reload don't reach widgets of the new view
+const WIDGET_IDS_PER_EPOCH: UiWidgetId = 1 << 32;
+
+// ids have to stay below javascript max safe integer
+const MAX_WIDGET_ID_EPOCH: usize = 1 << 20;
+
+static NEXT_WIDGET_ID_EPOCH: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WidgetIdEpoch(usize);
+
+impl WidgetIdEpoch {
+    pub fn next() -> Self {
+        // epoch 0 is skipped, root widget always has id 0
+        let epoch = NEXT_WIDGET_ID_EPOCH.fetch_add(1, Ordering::Relaxed) % (MAX_WIDGET_ID_EPOCH - 1) + 1;
+
+        Self(epoch)
+    }
+
+    pub fn start(&self) -> UiWidgetId {
+        self.0 * WIDGET_IDS_PER_EPOCH
+    }
+
+    pub fn contains(&self, widget_id: UiWidgetId) -> bool {
+        widget_id / WIDGET_IDS_PER_EPOCH == self.0
+    }
+}
+
+// tracks whether plugin view is open, updates of view which arrive after it is closed are dropped,
+// frontend doesn't have the view anymore so it would fail to apply them
+#[derive(Debug, Clone)]
+pub struct ViewOpenState {
+    open: Arc<AtomicBool>,
+}
+
+impl ViewOpenState {
+    pub fn new() -> Self {
+        Self {
+            open: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn open(&self) {
+        self.open.store(true, Ordering::SeqCst)
+    }
+
+    pub fn close(&self) {
+        self.open.store(false, Ordering::SeqCst)
+    }
+
+    // inline views are not opened and closed explicitly
+    pub fn accepts_update(&self, render_location: &JsUiRenderLocation) -> bool {
+        match render_location {
+            JsUiRenderLocation::InlineView => true,
+            JsUiRenderLocation::View => self.open.load(Ordering::SeqCst),
+        }
     }
 }
 
+#[op]
+fn op_widget_id_epoch_start(state: Rc<RefCell<OpState>>) -> UiWidgetId {
+    state.borrow()
+        .borrow::<PluginData>()
+        .widget_id_epoch()
+        .start()
+}
+
 #[op]
 fn op_component_model(state: Rc<RefCell<OpState>>) -> HashMap<String, Component> {
     state.borrow()
@@ -169,7 +245,24 @@ async fn show_hud(state: Rc<RefCell<OpState>>, display: String) -> anyhow::Resul
             tracing::trace!("Calling show_hud returned");
             Ok(())
         }
-        value @ _ => panic!("unsupported response type {:?}", value),
+        value @ _ => Err(anyhow!("unsupported response type {:?}", value)),
+    }
+}
+
+// typed alternative to rendering inline view, frontend shows the value as a single answer line
+#[op]
+async fn show_inline_answer(state: Rc<RefCell<OpState>>, value: String, copy_text: Option<String>) -> anyhow::Result<()> {
+    let data = JsUiRequestData::ShowInlineAnswer {
+        value,
+        copy_text,
+    };
+
+    match make_request(&state, data).context("ShowInlineAnswer frontend response")? {
+        JsUiResponseData::Nothing => {
+            tracing::trace!("Calling show_inline_answer returned");
+            Ok(())
+        }
+        value @ _ => Err(anyhow!("unsupported response type {:?}", value)),
     }
 }
 
@@ -178,9 +271,7 @@ fn from_js_to_intermediate_widget(state: Rc<RefCell<OpState>>, scope: &mut v8::H
         .map(|child| from_js_to_intermediate_widget(state.clone(), scope, child, component_model, shared_types))
         .collect::<anyhow::Result<Vec<UiWidget>>>()?;
 
-    let component = component_model.components
-        .get(&ui_widget.widget_type)
-        .expect(&format!("component with type {} doesn't exist", &ui_widget.widget_type));
+    let component = find_component(component_model, &ui_widget.widget_type)?;
 
     let empty = vec![];
     let text_part = vec![Property { name: "value".to_owned(), optional: false, property_type: PropertyType::String, description: "".to_string() }];
@@ -204,6 +295,23 @@ fn from_js_to_intermediate_widget(state: Rc<RefCell<OpState>>, scope: &mut v8::H
     })
 }
 
+fn find_component<'a>(component_model: &'a ComponentModel, widget_type: &str) -> anyhow::Result<&'a Component> {
+    let Some(component) = component_model.components.get(widget_type) else {
+        let mut valid_types = component_model.components
+            .keys()
+            .map(|widget_type| widget_type.as_str())
+            .collect::<Vec<_>>();
+
+        valid_types.sort();
+
+        let valid_types = valid_types.join(", ");
+
+        return Err(anyhow!("unknown widget type {:?}, valid types are: {}", widget_type, valid_types))
+    };
+
+    Ok(component)
+}
+
 fn from_js_to_intermediate_properties(
     state: Rc<RefCell<OpState>>,
     scope: &mut v8::HandleScope,
@@ -480,4 +588,60 @@ enum ImageSource {
     Url {
         url: String
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use component_model::create_component_model;
+
+    use super::*;
+
+    #[test]
+    fn known_widget_type_is_found() {
+        let component_model = ComponentModel::new(create_component_model());
+
+        assert!(find_component(&component_model, "gauntlet:detail").is_ok());
+    }
+
+    #[test]
+    fn unknown_widget_type_is_rejected_with_valid_types() {
+        let component_model = ComponentModel::new(create_component_model());
+
+        let err = find_component(&component_model, "gauntlet:unknown").err().unwrap().to_string();
+
+        assert!(err.contains("\"gauntlet:unknown\""));
+        assert!(err.contains("gauntlet:detail"));
+        assert!(err.contains("gauntlet:list"));
+    }
+
+    #[test]
+    fn widget_ids_of_previous_epoch_are_not_contained_in_next_one() {
+        let previous_epoch = WidgetIdEpoch::next();
+        let epoch = WidgetIdEpoch::next();
+
+        let previous_widget_id = previous_epoch.start() + 5;
+        let widget_id = epoch.start() + 5;
+
+        assert!(previous_epoch.contains(previous_widget_id));
+        assert!(!epoch.contains(previous_widget_id));
+        assert!(epoch.contains(widget_id));
+
+        // root widget doesn't belong to any epoch
+        assert!(!epoch.contains(0));
+    }
+
+    #[test]
+    fn updates_of_closed_view_are_dropped() {
+        let view_open_state = ViewOpenState::new();
+
+        view_open_state.open();
+
+        assert!(view_open_state.accepts_update(&JsUiRenderLocation::View));
+
+        // response for slow render arrives after view was closed
+        view_open_state.close();
+
+        assert!(!view_open_state.accepts_update(&JsUiRenderLocation::View));
+        assert!(view_open_state.accepts_update(&JsUiRenderLocation::InlineView));
+    }
+}
@@ -0,0 +1,52 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use anyhow::anyhow;
+use deno_core::{op, OpState};
+
+use crate::plugins::data_db_repository::DataDbRepository;
+use crate::plugins::js::PluginData;
+
+// state is meant for things like scroll position or selected item, not for storing plugin data
+const MAX_VIEW_STATE_SIZE: usize = 64 * 1024;
+
+// state is opaque to server, view decides what it needs to restore itself when reopened
+#[op]
+async fn op_save_view_state(state: Rc<RefCell<OpState>>, entrypoint_id: String, view_state: serde_json::Value) -> anyhow::Result<()> {
+    let (plugin_id, repository) = {
+        let state = state.borrow();
+
+        let plugin_id = state.borrow::<PluginData>().plugin_id();
+        let repository = state.borrow::<DataDbRepository>().clone();
+
+        (plugin_id, repository)
+    };
+
+    let plugin_id = plugin_id.to_string();
+
+    if repository.get_entrypoint_by_id_option(&plugin_id, &entrypoint_id).await?.is_none() {
+        return Err(anyhow!("Entrypoint {:?} doesn't exist in plugin {:?}", entrypoint_id, plugin_id))
+    }
+
+    let size = serde_json::to_vec(&view_state)?.len();
+
+    if size > MAX_VIEW_STATE_SIZE {
+        return Err(anyhow!("View state of {} bytes is larger than {} bytes", size, MAX_VIEW_STATE_SIZE))
+    }
+
+    repository.save_view_state(&plugin_id, &entrypoint_id, view_state).await
+}
+
+#[op]
+async fn op_load_view_state(state: Rc<RefCell<OpState>>, entrypoint_id: String) -> anyhow::Result<Option<serde_json::Value>> {
+    let (plugin_id, repository) = {
+        let state = state.borrow();
+
+        let plugin_id = state.borrow::<PluginData>().plugin_id();
+        let repository = state.borrow::<DataDbRepository>().clone();
+
+        (plugin_id, repository)
+    };
+
+    repository.get_view_state(&plugin_id.to_string(), &entrypoint_id).await
+}
@@ -1,14 +1,21 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs::DirEntry;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
 use std::io::{ErrorKind};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
 use anyhow::{anyhow, Context};
 use deno_core::url;
 use include_dir::Dir;
 use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+use tokio::sync::Semaphore;
 use uuid::Uuid;
 use walkdir::WalkDir;
 use itertools::Itertools;
@@ -16,25 +23,132 @@ use once_cell::sync::Lazy;
 use regex::{Match, Regex};
 use tracing_subscriber::fmt::format;
 use typed_path::{TypedPathBuf, Utf8TypedPath, Utf8UnixComponent, Utf8WindowsComponent, Utf8WindowsPrefix, Utf8WindowsPrefixComponent};
-use common::model::{DownloadStatus, PluginId};
+use common::model::{DownloadStatus, PluginId, PluginUpdate};
 use crate::model::ActionShortcutKey;
-use crate::plugins::data_db_repository::{DataDbRepository, db_entrypoint_to_str, db_plugin_type_to_str, DbCode, DbPluginAction, DbPluginActionShortcutKind, DbPluginEntrypointType, DbPluginPermissions, DbPluginPreference, DbPluginPreferenceUserData, DbPluginType, DbPreferenceEnumValue, DbWritePlugin, DbWritePluginAssetData, DbWritePluginEntrypoint, DbPluginClipboardPermissions, DbPluginMainSearchBarPermissions, DbPluginPermissionsFileSystem, DbPluginPermissionsExec};
-use crate::plugins::download_status::DownloadStatusHolder;
+use crate::plugins::data_db_repository::{DataDbRepository, db_entrypoint_to_str, db_plugin_type_from_str, db_plugin_type_to_str, DbCode, DbPluginAction, DbPluginActionShortcutKind, DbPluginEntrypointType, DbPluginPermissions, DbPluginPreference, DbPluginPreferenceUserData, DbPluginType, DbPreferenceEnumValue, DbWritePlugin, DbWritePluginAssetData, DbWritePluginEntrypoint, DbPluginClipboardPermissions, DbPluginMainSearchBarPermissions, DbPluginPermissionsFileSystem, DbPluginPermissionsExec};
+use crate::plugins::download_status::{DownloadStatusGuard, DownloadStatusHolder};
 use crate::plugins::js::permissions::{PluginPermissionsExec, PluginPermissionsFileSystem};
 
 pub struct PluginLoader {
     db_repository: DataDbRepository,
-    download_status_holder: DownloadStatusHolder
+    download_status_holder: DownloadStatusHolder,
+    download_retry_policy: DownloadRetryPolicy,
+    download_semaphore: Arc<Semaphore>,
 }
 
+pub const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct DownloadRetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for DownloadRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl DownloadRetryPolicy {
+    fn delay(&self, attempt: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay)
+    }
+}
+
+const RELEASE_BRANCH: &str = "gauntlet/release";
+
+// reserved for plugins bundled with application, e.g. bundled://gauntlet
+const BUNDLED_PLUGIN_ID_PREFIX: &str = "bundled://";
+
+static BUNDLED_PLUGIN_ID_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-z0-9]+(-[a-z0-9]+)*$").expect("invalid regex"));
+
+// plugins can restrict range of app versions they work with, app version is a single number
+pub static APP_VERSION: Lazy<u32> = Lazy::new(|| {
+    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../../VERSION"))
+        .trim()
+        .parse()
+        .expect("VERSION file should contain a number")
+});
+
 pub static VARIABLE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{(?<namespace>.+?):(?<name>.+?)}").expect("invalid regex"));
 
+// network errors and server-side http errors may go away on retry,
+// everything else, like missing repository or branch, will fail the same way again
+fn is_transient_download_error(err: &anyhow::Error) -> bool {
+    let Some(err) = err.downcast_ref::<git2::Error>() else {
+        return false
+    };
+
+    match err.class() {
+        git2::ErrorClass::Net | git2::ErrorClass::Os | git2::ErrorClass::Ssl => true,
+        git2::ErrorClass::Http => {
+            let status_code = err.message()
+                .split_once("status code: ")
+                .and_then(|(_, status_code)| status_code.trim().parse::<u16>().ok());
+
+            match status_code {
+                Some(status_code) => (500..600).contains(&status_code) || status_code == 429,
+                None => true
+            }
+        }
+        _ => false
+    }
+}
+
+async fn retry_transient_download<T, F, Fut>(download_retry_policy: &DownloadRetryPolicy, plugin_id: &PluginId, mut download: F) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output=anyhow::Result<T>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        match download().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt < download_retry_policy.max_attempts && is_transient_download_error(&err) => {
+                let delay = download_retry_policy.delay(attempt);
+
+                tracing::warn!("Download of plugin {:?} failed on attempt {}, retrying in {:?}: {:?}", plugin_id, attempt, delay, err);
+
+                tokio::time::sleep(delay).await;
+
+                attempt += 1;
+            }
+            Err(err) => return Err(err)
+        }
+    }
+}
+
+// downloads over the limit stay pending until one of the running downloads finishes
+async fn run_in_download_slot<T>(download_semaphore: &Semaphore, download_status_guard: &DownloadStatusGuard, download: impl Future<Output=T>) -> T {
+    let _permit = download_semaphore.acquire()
+        .await
+        .expect("download semaphore is never closed");
+
+    download_status_guard.download_started();
+
+    download.await
+}
+
 impl PluginLoader {
-    pub fn new(db_repository: DataDbRepository) -> Self {
-        Self {
+    pub async fn new(db_repository: DataDbRepository, download_retry_policy: DownloadRetryPolicy, max_concurrent_downloads: usize) -> anyhow::Result<Self> {
+        let download_status_holder = DownloadStatusHolder::new(db_repository.clone())
+            .await?;
+
+        Ok(Self {
             db_repository,
-            download_status_holder: DownloadStatusHolder::new()
-        }
+            download_status_holder,
+            download_retry_policy,
+            download_semaphore: Arc::new(Semaphore::new(max_concurrent_downloads)),
+        })
     }
 
     pub fn download_status(&self) -> HashMap<PluginId, DownloadStatus> {
@@ -42,46 +156,30 @@ impl PluginLoader {
     }
 
     pub async fn download_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
-        let download_status_guard = self.download_status_holder.download_started(plugin_id.clone());
+        let download_status_guard = self.download_status_holder.download_queued(plugin_id.clone()).await;
 
         let data_db_repository = self.db_repository.clone();
+        let download_retry_policy = self.download_retry_policy.clone();
+        let download_semaphore = self.download_semaphore.clone();
         let handle = tokio::runtime::Handle::current();
 
         let plugin_id_clone = plugin_id.clone();
         thread::spawn(move || {
-            let result = handle.block_on(async move {
-                let temp_dir = tempfile::tempdir()?;
+            let result = handle.block_on(async {
+                let download = PluginLoader::download_and_save(&data_db_repository, &download_retry_policy, plugin_id_clone, None, false, DbPluginType::Normal);
 
-                PluginLoader::download(temp_dir.path(), plugin_id_clone.clone())?;
-
-                let plugin_data = PluginLoader::read_plugin_dir(temp_dir.path(), plugin_id_clone.clone())
-                    .await?;
-
-                data_db_repository.save_plugin(DbWritePlugin {
-                    id: plugin_data.id,
-                    name: plugin_data.name,
-                    description: plugin_data.description,
-                    enabled: false,
-                    code: plugin_data.code,
-                    entrypoints: plugin_data.entrypoints,
-                    asset_data: plugin_data.asset_data,
-                    permissions: plugin_data.permissions,
-                    plugin_type: db_plugin_type_to_str(DbPluginType::Normal).to_owned(),
-                    preferences: plugin_data.preferences,
-                }).await?;
-
-                anyhow::Ok(())
+                run_in_download_slot(&download_semaphore, &download_status_guard, download).await
             });
 
             handle.block_on(async move {
                 match result {
                     Ok(()) => {
                         tracing::info!("Finished download of plugin: {:?}", plugin_id);
-                        download_status_guard.download_finished()
+                        download_status_guard.download_finished().await
                     },
                     Err(err) => {
                         tracing::warn!("Download of plugin {:?} returned an error {:?}", plugin_id, err);
-                        download_status_guard.download_failed(format!("{}", err))
+                        download_status_guard.download_failed(format!("{}", err)).await
                     }
                 }
             })
@@ -90,33 +188,149 @@ impl PluginLoader {
         Ok(())
     }
 
-    pub async fn save_local_plugin(&self, path: &str) -> anyhow::Result<PluginId> {
+    pub async fn download_config_plugin(&self, plugin_id: PluginId, git_ref: Option<String>) -> anyhow::Result<()> {
+        let download_status_guard = self.download_status_holder.download_queued(plugin_id.clone()).await;
+
+        let download = PluginLoader::download_and_save(&self.db_repository, &self.download_retry_policy, plugin_id.clone(), git_ref.clone(), true, DbPluginType::Config);
+
+        let result = run_in_download_slot(&self.download_semaphore, &download_status_guard, download).await;
+
+        // stored to be able to tell when ref in config changes
+        let result = match result {
+            Ok(()) => self.db_repository.set_plugin_git_ref(&plugin_id.to_string(), git_ref.as_deref()).await,
+            Err(err) => Err(err),
+        };
+
+        match &result {
+            Ok(()) => {
+                tracing::info!("Finished download of config plugin: {:?}", plugin_id);
+                download_status_guard.download_finished().await
+            },
+            Err(err) => {
+                tracing::warn!("Download of config plugin {:?} returned an error {:?}", plugin_id, err);
+                download_status_guard.download_failed(format!("{}", err)).await
+            }
+        }
+
+        result
+    }
+
+    pub async fn update_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        let download_status_guard = self.download_status_holder.download_queued(plugin_id.clone()).await;
+
+        // enabled state of already installed plugin is preserved
+        let download = PluginLoader::download_and_save(&self.db_repository, &self.download_retry_policy, plugin_id.clone(), None, false, DbPluginType::Normal);
+
+        let result = run_in_download_slot(&self.download_semaphore, &download_status_guard, download).await;
+
+        match &result {
+            Ok(()) => {
+                tracing::info!("Finished update of plugin: {:?}", plugin_id);
+                download_status_guard.download_finished().await
+            },
+            Err(err) => {
+                tracing::warn!("Update of plugin {:?} returned an error {:?}", plugin_id, err);
+                download_status_guard.download_failed(format!("{}", err)).await
+            }
+        }
+
+        result
+    }
+
+    async fn download_and_save(
+        data_db_repository: &DataDbRepository,
+        download_retry_policy: &DownloadRetryPolicy,
+        plugin_id: PluginId,
+        git_ref: Option<String>,
+        enabled: bool,
+        plugin_type: DbPluginType
+    ) -> anyhow::Result<()> {
+        PluginLoader::validate_plugin_id(data_db_repository, &plugin_id, &plugin_type)
+            .await?;
+
+        let (temp_dir, version) = PluginLoader::download_with_retry(download_retry_policy, plugin_id.clone(), git_ref)
+            .await?;
+
+        let plugin_data = PluginLoader::read_plugin_dir(temp_dir.path(), plugin_id.clone())
+            .await?;
+
+        data_db_repository.save_plugin(DbWritePlugin {
+            id: plugin_data.id,
+            name: plugin_data.name,
+            description: plugin_data.description,
+            enabled,
+            code: plugin_data.code,
+            entrypoints: plugin_data.entrypoints,
+            asset_data: plugin_data.asset_data,
+            permissions: plugin_data.permissions,
+            plugin_type: db_plugin_type_to_str(plugin_type).to_owned(),
+            preferences: plugin_data.preferences,
+            version: Some(version),
+            min_app_version: plugin_data.min_app_version,
+            max_app_version: plugin_data.max_app_version,
+        }).await?;
+
+        Ok(())
+    }
+
+    // linked plugins read their code from disk on each start, so changes to code
+    // are picked up on reload, changes to manifest still require saving plugin again
+    pub async fn save_local_plugin(&self, path: &str, linked: bool) -> anyhow::Result<PluginId> {
         let plugin_id = PluginId::from_string(format!("file://{}", &path));
 
+        let plugin_type = if linked { DbPluginType::Linked } else { DbPluginType::Normal };
+
+        PluginLoader::validate_plugin_id(&self.db_repository, &plugin_id, &plugin_type)
+            .await?;
+
         let plugin_dir = plugin_id.try_to_path()?.join("dist");
 
         let plugin_data = PluginLoader::read_plugin_dir(&plugin_dir, plugin_id.clone())
             .await
             .context(format!("Unable to read plugin: {}", &plugin_id.to_string()))?;
 
+        let code = if linked {
+            let linked_path = plugin_dir.to_str()
+                .ok_or(anyhow!("non uft8 paths are not supported"))?
+                .to_owned();
+
+            DbCode {
+                js: HashMap::new(),
+                linked_path: Some(linked_path),
+            }
+        } else {
+            plugin_data.code
+        };
+
         self.db_repository.save_plugin(DbWritePlugin {
             id: plugin_data.id,
             name: plugin_data.name,
             description: plugin_data.description,
             enabled: true,
-            code: plugin_data.code,
+            code,
             entrypoints: plugin_data.entrypoints,
             asset_data: plugin_data.asset_data,
             permissions: plugin_data.permissions,
-            plugin_type: db_plugin_type_to_str(DbPluginType::Normal).to_owned(),
+            plugin_type: db_plugin_type_to_str(plugin_type).to_owned(),
             preferences: plugin_data.preferences,
+            version: None,
+            min_app_version: plugin_data.min_app_version,
+            max_app_version: plugin_data.max_app_version,
         }).await?;
 
         Ok(plugin_id)
     }
 
+    pub fn bundled_plugin_id(id: &str) -> PluginId {
+        PluginId::from_string(format!("{BUNDLED_PLUGIN_ID_PREFIX}{id}"))
+    }
+
     pub async fn save_bundled_plugin(&self, id: &str, dir: &Dir<'_>) -> anyhow::Result<PluginId> {
-        let plugin_id = PluginId::from_string(format!("bundled://{id}"));
+        let plugin_id = PluginLoader::bundled_plugin_id(id);
+
+        PluginLoader::validate_plugin_id(&self.db_repository, &plugin_id, &DbPluginType::Bundled)
+            .await?;
+
         let temp_dir = tempfile::tempdir()?;
 
         dir.extract(&temp_dir)?;
@@ -136,29 +350,221 @@ impl PluginLoader {
             permissions: plugin_data.permissions,
             plugin_type: db_plugin_type_to_str(DbPluginType::Bundled).to_owned(),
             preferences: plugin_data.preferences,
+            version: None,
+            min_app_version: plugin_data.min_app_version,
+            max_app_version: plugin_data.max_app_version,
         }).await?;
 
+        self.db_repository.set_plugin_bundle_hash(&plugin_id.to_string(), &PluginLoader::bundle_hash(dir))
+            .await?;
+
         Ok(plugin_id)
     }
 
-    fn download(target_dir: &Path, plugin_id: PluginId) -> anyhow::Result<()> {
-        let url = plugin_id.try_to_git_url()?;
+    // bundled code is the same on every start unless app was updated, so saving it every time is unnecessary
+    // returns whether plugin was saved
+    pub async fn save_bundled_plugin_if_changed(&self, id: &str, dir: &Dir<'_>) -> anyhow::Result<(PluginId, bool)> {
+        let plugin_id = PluginLoader::bundled_plugin_id(id);
+
+        let stored_hash = self.db_repository.get_plugin_bundle_hash(&plugin_id.to_string())
+            .await?;
+
+        if stored_hash.as_deref() == Some(PluginLoader::bundle_hash(dir).as_str()) {
+            return Ok((plugin_id, false))
+        }
+
+        let plugin_id = self.save_bundled_plugin(id, dir).await?;
+
+        Ok((plugin_id, true))
+    }
+
+    // hash algorithm of DefaultHasher is not guaranteed to be the same between rust versions,
+    // which at worst results in one unnecessary save after app update
+    fn bundle_hash(dir: &Dir<'_>) -> String {
+        fn hash_dir(dir: &Dir<'_>, hasher: &mut DefaultHasher) {
+            for entry in dir.entries() {
+                match entry {
+                    include_dir::DirEntry::Dir(dir) => hash_dir(dir, hasher),
+                    include_dir::DirEntry::File(file) => {
+                        file.path().hash(hasher);
+                        file.contents().hash(hasher);
+                    }
+                }
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+
+        hash_dir(dir, &mut hasher);
+
+        format!("{:016x}", hasher.finish())
+    }
 
-        let _ = git2::build::RepoBuilder::new()
-            .branch("gauntlet/release")
-            .clone(&url, target_dir)?;
+    pub async fn validate_plugin_id(data_db_repository: &DataDbRepository, plugin_id: &PluginId, plugin_type: &DbPluginType) -> anyhow::Result<()> {
+        let id = plugin_id.to_string();
+
+        match plugin_type {
+            DbPluginType::Bundled => {
+                let name = id.strip_prefix(BUNDLED_PLUGIN_ID_PREFIX)
+                    .ok_or_else(|| anyhow!("Bundled plugin id '{}' should start with '{}'", id, BUNDLED_PLUGIN_ID_PREFIX))?;
+
+                if !BUNDLED_PLUGIN_ID_PATTERN.is_match(name) {
+                    return Err(anyhow!("Bundled plugin id '{}' should contain only lowercase ascii letters, digits and dashes after '{}'", id, BUNDLED_PLUGIN_ID_PREFIX))
+                }
+            }
+            DbPluginType::Normal | DbPluginType::Config | DbPluginType::Linked => {
+                if id.starts_with(BUNDLED_PLUGIN_ID_PREFIX) {
+                    return Err(anyhow!("Plugin id '{}' is invalid, '{}' is reserved for bundled plugins", id, BUNDLED_PLUGIN_ID_PREFIX))
+                }
+
+                plugin_id.try_to_git_url()
+                    .context(format!("Plugin id '{}' is expected to be a valid url", id))?;
+            }
+        }
+
+        // plugin can be saved again with the same type, e.g. on update, but not replace plugin of other type
+        if let Some(existing_plugin) = data_db_repository.get_plugin_by_id_option(&id).await? {
+            let compatible = match (db_plugin_type_from_str(&existing_plugin.plugin_type), plugin_type) {
+                (DbPluginType::Normal | DbPluginType::Linked, DbPluginType::Normal | DbPluginType::Linked) => true,
+                (DbPluginType::Config, DbPluginType::Config) => true,
+                // plugin added to config takes over already downloaded plugin
+                (DbPluginType::Normal, DbPluginType::Config) => true,
+                (DbPluginType::Bundled, DbPluginType::Bundled) => true,
+                _ => false,
+            };
+
+            if !compatible {
+                return Err(anyhow!("Plugin with id '{}' is already installed as {} plugin", id, existing_plugin.plugin_type))
+            }
+        }
 
         Ok(())
     }
 
-    async fn read_plugin_dir(plugin_dir: &Path, plugin_id: PluginId) -> anyhow::Result<PluginDownloadData> {
+    async fn download_with_retry(
+        download_retry_policy: &DownloadRetryPolicy,
+        plugin_id: PluginId,
+        git_ref: Option<String>
+    ) -> anyhow::Result<(TempDir, String)> {
+        retry_transient_download(download_retry_policy, &plugin_id, || {
+            let plugin_id = plugin_id.clone();
+            let git_ref = git_ref.clone();
+
+            async move {
+                // clone requires empty directory, so each attempt uses new one
+                let temp_dir = tempfile::tempdir()?;
+
+                let target_dir = temp_dir.path().to_owned();
+                let version = tokio::task::spawn_blocking(move || PluginLoader::download(&target_dir, plugin_id, git_ref))
+                    .await??;
+
+                Ok::<_, anyhow::Error>((temp_dir, version))
+            }
+        }).await
+    }
+
+    // returns commit id of downloaded revision
+    fn download(target_dir: &Path, plugin_id: PluginId, git_ref: Option<String>) -> anyhow::Result<String> {
+        let url = plugin_id.try_to_git_url()?;
+
+        let version = match git_ref {
+            None => {
+                let repo = git2::build::RepoBuilder::new()
+                    .branch(RELEASE_BRANCH)
+                    .clone(&url, target_dir)?;
+
+                let commit_id = repo.head()?.peel_to_commit()?.id();
+
+                commit_id.to_string()
+            }
+            Some(git_ref) => {
+                let repo = git2::build::RepoBuilder::new()
+                    .clone(&url, target_dir)?;
+
+                // branches are only available as remote refs after clone, tags and commits are resolved as is
+                let object = repo.revparse_single(&format!("origin/{}", git_ref))
+                    .or_else(|_| repo.revparse_single(&git_ref))
+                    .context(format!("Unable to find git ref '{}' in {}", git_ref, url))?;
+
+                let commit_id = object.peel_to_commit()?.id();
+
+                repo.checkout_tree(&object, Some(git2::build::CheckoutBuilder::new().force()))?;
+                repo.set_head_detached(commit_id)?;
+
+                commit_id.to_string()
+            }
+        };
+
+        Ok(version)
+    }
+
+    pub async fn check_for_updates(&self) -> anyhow::Result<Vec<PluginUpdate>> {
+        // config plugins are pinned to git ref specified in config, so only plugins downloaded by user are checked
+        let plugins = self.db_repository.list_plugins()
+            .await?
+            .into_iter()
+            .filter(|plugin| plugin.plugin_type == db_plugin_type_to_str(DbPluginType::Normal))
+            .filter_map(|plugin| plugin.version.clone().map(|version| (plugin, version)))
+            .collect::<Vec<_>>();
+
+        let mut updates = vec![];
+
+        for (plugin, current_version) in plugins {
+            let plugin_id = PluginId::from_string(plugin.id);
+
+            let latest_version = {
+                let plugin_id = plugin_id.clone();
+
+                tokio::task::spawn_blocking(move || PluginLoader::latest_version(plugin_id))
+                    .await?
+            };
+
+            match latest_version {
+                Ok(latest_version) => {
+                    if latest_version != current_version {
+                        updates.push(PluginUpdate {
+                            plugin_id,
+                            plugin_name: plugin.name,
+                            current_version,
+                            latest_version,
+                        })
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("Unable to check for updates of plugin {:?}: {:?}", plugin_id, err);
+                }
+            }
+        }
+
+        Ok(updates)
+    }
+
+    // only refs are fetched, without downloading the whole repository
+    fn latest_version(plugin_id: PluginId) -> anyhow::Result<String> {
+        let url = plugin_id.try_to_git_url()?;
+
+        let mut remote = git2::Remote::create_detached(url.as_str())?;
+
+        remote.connect(git2::Direction::Fetch)?;
+
+        let release_ref = format!("refs/heads/{}", RELEASE_BRANCH);
+
+        let commit_id = remote.list()?
+            .iter()
+            .find(|head| head.name() == release_ref)
+            .map(|head| head.oid().to_string())
+            .ok_or_else(|| anyhow!("Unable to find branch '{}' in {}", RELEASE_BRANCH, url))?;
+
+        Ok(commit_id)
+    }
+
+    pub fn read_plugin_js(plugin_dir: &Path) -> anyhow::Result<HashMap<String, String>> {
         let js_dir = plugin_dir.join("js");
-        let assets = plugin_dir.join("assets");
 
         let js_dir_context = js_dir.display().to_string();
         let js_files = std::fs::read_dir(js_dir).context(js_dir_context)?;
 
-        let js: HashMap<_, _> = js_files.into_iter()
+        let js = js_files.into_iter()
             .collect::<std::io::Result<Vec<DirEntry>>>()
             .context("Unable to get list of plugin js files")?
             .into_iter()
@@ -179,6 +585,24 @@ impl PluginLoader {
             .into_iter()
             .collect();
 
+        Ok(js)
+    }
+
+    pub fn read_plugin_code(code: DbCode) -> anyhow::Result<HashMap<String, String>> {
+        match code.linked_path {
+            Some(linked_path) => {
+                PluginLoader::read_plugin_js(Path::new(&linked_path))
+                    .context(format!("Unable to read code of linked plugin from: {}", linked_path))
+            }
+            None => Ok(code.js)
+        }
+    }
+
+    async fn read_plugin_dir(plugin_dir: &Path, plugin_id: PluginId) -> anyhow::Result<PluginDownloadData> {
+        let assets = plugin_dir.join("assets");
+
+        let js = PluginLoader::read_plugin_js(plugin_dir)?;
+
         let asset_data = WalkDir::new(&assets)
             .into_iter()
             .collect::<walkdir::Result<Vec<walkdir::DirEntry>>>()
@@ -212,15 +636,7 @@ impl PluginLoader {
             .into_iter()
             .collect();
 
-        let plugin_manifest_path = plugin_dir.join("gauntlet.toml");
-        let plugin_manifest_path_context = plugin_manifest_path.display().to_string();
-        let plugin_manifest_content = std::fs::read_to_string(plugin_manifest_path).context(plugin_manifest_path_context)?;
-        let plugin_manifest: PluginManifest = toml::from_str(&plugin_manifest_content)
-            .context("Unable to read plugin manifest")?;
-
-        tracing::debug!("Plugin config read: {:?}", plugin_manifest);
-
-        Self::validate_manifest(&plugin_manifest)?;
+        let plugin_manifest = Self::read_manifest(plugin_dir)?;
 
         let plugin_name = plugin_manifest.gauntlet.name;
         let plugin_description = plugin_manifest.gauntlet.description;
@@ -337,14 +753,20 @@ impl PluginLoader {
             system: plugin_manifest.permissions.system,
             clipboard,
             main_search_bar,
+            high_resolution_time: plugin_manifest.permissions.high_resolution_time,
+            list_entrypoints: plugin_manifest.permissions.list_entrypoints,
+            ffi: plugin_manifest.permissions.ffi,
         };
 
         Ok(PluginDownloadData {
             id: plugin_id.to_string(),
             name: plugin_name,
             description: plugin_description,
+            min_app_version: plugin_manifest.gauntlet.min_app_version,
+            max_app_version: plugin_manifest.gauntlet.max_app_version,
             code: DbCode {
-                js
+                js,
+                linked_path: None,
             },
             entrypoints,
             asset_data,
@@ -354,7 +776,116 @@ impl PluginLoader {
         })
     }
 
+    pub fn check_app_version(min_app_version: Option<u32>, max_app_version: Option<u32>) -> anyhow::Result<()> {
+        let app_version = *APP_VERSION;
+
+        if let Some(min_app_version) = min_app_version {
+            if app_version < min_app_version {
+                return Err(anyhow!("Plugin requires Gauntlet version {} or newer, current version is {}", min_app_version, app_version))
+            }
+        }
+
+        if let Some(max_app_version) = max_app_version {
+            if app_version > max_app_version {
+                return Err(anyhow!("Plugin supports Gauntlet version {} or older, current version is {}", max_app_version, app_version))
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_manifest_in_dir(plugin_dir: &Path) -> anyhow::Result<()> {
+        Self::read_manifest(plugin_dir)?;
+
+        Ok(())
+    }
+
+    // same validations as when loading plugin, plus style warnings that don't prevent plugin from loading
+    pub fn lint_manifest_in_dir(plugin_dir: &Path) -> Vec<PluginManifestLintIssue> {
+        let plugin_manifest = match Self::read_manifest(plugin_dir) {
+            Ok(plugin_manifest) => plugin_manifest,
+            Err(err) => return vec![PluginManifestLintIssue::error(format!("{:#}", err))]
+        };
+
+        let mut issues = vec![];
+
+        if plugin_manifest.gauntlet.description.trim().is_empty() {
+            issues.push(PluginManifestLintIssue::warning("Plugin description is empty".to_string()))
+        }
+
+        for entrypoint in &plugin_manifest.entrypoint {
+            if entrypoint.description.trim().is_empty() {
+                issues.push(PluginManifestLintIssue::warning(format!("Entrypoint '{}' description is empty", entrypoint.id)))
+            }
+        }
+
+        let permissions = &plugin_manifest.permissions;
+
+        for value in &permissions.network {
+            // e.g. "*.com" allows access to every domain in top-level domain
+            let is_broad = value.strip_prefix("*.")
+                .map(|parent_domain| !parent_domain.contains('.'))
+                .unwrap_or(false);
+
+            if is_broad {
+                issues.push(PluginManifestLintIssue::warning(format!("Network permission is overly broad: {}", value)))
+            }
+        }
+
+        let paths = permissions.filesystem.read.iter()
+            .chain(permissions.filesystem.write.iter());
+
+        for path in paths {
+            if Self::is_broad_path(path) {
+                issues.push(PluginManifestLintIssue::warning(format!("Filesystem permission is overly broad: {}", path)))
+            }
+        }
+
+        issues
+    }
+
+    // file system root or whole user home directory
+    fn is_broad_path(path: &str) -> bool {
+        let path = path.trim_end_matches(['/', '\\']);
+
+        if path.is_empty() {
+            return true
+        }
+
+        if VARIABLE_PATTERN.is_match(path) {
+            return VARIABLE_PATTERN.replace(path, "").is_empty() && path.ends_with(":user-home}")
+        }
+
+        // windows drive root, e.g. "C:"
+        path.len() == 2 && path.ends_with(':')
+    }
+
+    fn read_manifest(plugin_dir: &Path) -> anyhow::Result<PluginManifest> {
+        let plugin_manifest_path = plugin_dir.join("gauntlet.toml");
+        let plugin_manifest_path_context = plugin_manifest_path.display().to_string();
+        let plugin_manifest_content = std::fs::read_to_string(plugin_manifest_path).context(plugin_manifest_path_context)?;
+        let plugin_manifest: PluginManifest = toml::from_str(&plugin_manifest_content)
+            .context("Unable to read plugin manifest")?;
+
+        tracing::debug!("Plugin config read: {:?}", plugin_manifest);
+
+        Self::validate_manifest(&plugin_manifest)?;
+
+        Ok(plugin_manifest)
+    }
+
     fn validate_manifest(plugin_manifest: &PluginManifest) -> anyhow::Result<()> {
+        let min_app_version = plugin_manifest.gauntlet.min_app_version;
+        let max_app_version = plugin_manifest.gauntlet.max_app_version;
+
+        if let (Some(min_app_version), Some(max_app_version)) = (min_app_version, max_app_version) {
+            if min_app_version > max_app_version {
+                return Err(anyhow!("Plugin manifest 'min_app_version' ({}) is greater than 'max_app_version' ({})", min_app_version, max_app_version))
+            }
+        }
+
+        PluginLoader::check_app_version(min_app_version, max_app_version)?;
+
         let supported_systems = &plugin_manifest.supported_system;
         let supported_systems_str = supported_systems.iter().format(", ");
 
@@ -370,6 +901,7 @@ impl PluginLoader {
         Self::validate_path_permissions(&permissions.filesystem.write, supports_linux, supports_macos, supports_windows)?;
         Self::validate_string_permissions(&permissions.exec.command)?;
         Self::validate_path_permissions(&permissions.exec.executable, supports_linux, supports_macos, supports_windows)?;
+        Self::validate_path_permissions(&permissions.ffi, supports_linux, supports_macos, supports_windows)?;
 
         // even though system accepts a list of predefined values
         // unknown values are ignored to allow for easier
@@ -383,8 +915,9 @@ impl PluginLoader {
         let command_exists = !permissions.exec.command.is_empty();
         let executable_exists = !permissions.exec.executable.is_empty();
         let system_exists = !permissions.system.is_empty();
+        let ffi_exists = !permissions.ffi.is_empty();
 
-        let os_required = env_exists || fs_read_exists || fs_write_exists || command_exists || executable_exists || system_exists;
+        let os_required = env_exists || fs_read_exists || fs_write_exists || command_exists || executable_exists || system_exists || ffi_exists;
 
         if os_required {
             let current_system = if cfg!(target_os = "linux") {
@@ -556,7 +1089,15 @@ impl PluginLoader {
                 Err(anyhow!("Empty string value is not allowed in permissions"))?
             }
 
-            let url = url::Url::parse(&format!("http://{value}"))?;
+            // wildcard is only allowed as a first label, e.g. *.example.com,
+            // it is matched only by fetchUrl ops, because deno permissions used by global fetch support only exact hosts
+            let value_without_wildcard = value.strip_prefix("*.").unwrap_or(value);
+
+            if value_without_wildcard.contains('*') {
+                Err(anyhow!("Wildcard is only allowed as a first part of domain in network permission: {}", value))?
+            }
+
+            let url = url::Url::parse(&format!("http://{value_without_wildcard}"))?;
 
             let contains_username = !url.username().is_empty();
             let contains_password = matches!(url.password(), Some(_));
@@ -573,10 +1114,40 @@ impl PluginLoader {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginManifestLintSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct PluginManifestLintIssue {
+    pub severity: PluginManifestLintSeverity,
+    pub message: String,
+}
+
+impl PluginManifestLintIssue {
+    fn error(message: String) -> Self {
+        Self {
+            severity: PluginManifestLintSeverity::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            severity: PluginManifestLintSeverity::Warning,
+            message,
+        }
+    }
+}
+
 struct PluginDownloadData {
     pub id: String,
     pub name: String,
     pub description: String,
+    pub min_app_version: Option<u32>,
+    pub max_app_version: Option<u32>,
     pub code: DbCode,
     pub entrypoints: Vec<DbWritePluginEntrypoint>,
     pub asset_data: Vec<DbWritePluginAssetData>,
@@ -1026,6 +1597,8 @@ impl std::fmt::Display for PluginManifestSupportedSystem {
 struct PluginManifestMetadata {
     name: String,
     description: String,
+    min_app_version: Option<u32>,
+    max_app_version: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -1044,6 +1617,12 @@ pub struct PluginManifestPermissions {
     clipboard: Vec<PluginManifestClipboardPermissions>,
     #[serde(default)]
     main_search_bar: Vec<PluginManifestMainSearchBarPermissions>,
+    #[serde(default)]
+    high_resolution_time: bool,
+    #[serde(default)]
+    list_entrypoints: bool,
+    #[serde(default)]
+    ffi: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -1078,3 +1657,474 @@ pub enum PluginManifestMainSearchBarPermissions {
     Read,
 }
 
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use deno_core::futures::future::join_all;
+
+    use super::*;
+
+    const MANIFEST: &str = r#"[gauntlet]
+name = 'Config Plugin'
+description = 'Plugin from config'
+
+[[entrypoint]]
+id = 'command'
+name = 'Command'
+path = 'src/command.ts'
+type = 'command'
+description = 'Command'
+
+[[supported_system]]
+os = 'linux'
+
+[[supported_system]]
+os = 'macos'
+
+[[supported_system]]
+os = 'windows'
+"#;
+
+    // local repository which serves as plugin git source, release content is committed to branch with given name
+    fn create_git_source(source_dir: &Path, branch: &str) {
+        std::fs::create_dir_all(source_dir.join("js")).unwrap();
+        std::fs::write(source_dir.join("gauntlet.toml"), MANIFEST).unwrap();
+        std::fs::write(source_dir.join("js").join("command.js"), "export default function command() {}").unwrap();
+
+        let repo = git2::Repository::init(source_dir).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let commit_id = repo.commit(Some("HEAD"), &signature, &signature, "release", &tree, &[]).unwrap();
+
+        repo.branch(branch, &repo.find_commit(commit_id).unwrap(), false).unwrap();
+    }
+
+    // returns commit id of new revision
+    fn commit_to_branch(source_dir: &Path, branch: &str) -> String {
+        std::fs::write(source_dir.join("js").join("command.js"), "export default function command() { return 1 }").unwrap();
+
+        let repo = git2::Repository::open(source_dir).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.find_branch(branch, git2::BranchType::Local).unwrap().get().peel_to_commit().unwrap();
+        let commit_id = repo.commit(Some(&format!("refs/heads/{}", branch)), &signature, &signature, "new release", &tree, &[&parent]).unwrap();
+
+        commit_id.to_string()
+    }
+
+    #[tokio::test]
+    async fn config_plugin_is_downloaded_from_git_ref() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let db_dir = tempfile::tempdir().unwrap();
+
+        create_git_source(source_dir.path(), "v1");
+
+        let repository = DataDbRepository::open(&db_dir.path().join("data.db")).await.unwrap();
+
+        let plugin_id = PluginId::from_string(format!("file://{}", source_dir.path().display()));
+
+        PluginLoader::download_and_save(&repository, plugin_id.clone(), Some("v1".to_string()), true, DbPluginType::Config)
+            .await
+            .unwrap();
+
+        let plugin = repository.get_plugin_by_id(&plugin_id.to_string()).await.unwrap();
+
+        assert_eq!(plugin.name, "Config Plugin");
+        assert_eq!(plugin.plugin_type, db_plugin_type_to_str(DbPluginType::Config));
+        assert!(plugin.enabled);
+        assert!(plugin.code.js.contains_key("command"));
+    }
+
+    #[tokio::test]
+    async fn config_plugin_with_unknown_git_ref_is_not_saved() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let db_dir = tempfile::tempdir().unwrap();
+
+        create_git_source(source_dir.path(), "v1");
+
+        let repository = DataDbRepository::open(&db_dir.path().join("data.db")).await.unwrap();
+
+        let plugin_id = PluginId::from_string(format!("file://{}", source_dir.path().display()));
+
+        let result = PluginLoader::download_and_save(&repository, plugin_id.clone(), Some("v2".to_string()), true, DbPluginType::Config).await;
+
+        assert!(result.is_err());
+        assert!(!repository.does_plugin_exist(&plugin_id.to_string()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn newer_release_of_downloaded_plugin_is_reported() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let db_dir = tempfile::tempdir().unwrap();
+
+        create_git_source(source_dir.path(), RELEASE_BRANCH);
+
+        let repository = DataDbRepository::open(&db_dir.path().join("data.db")).await.unwrap();
+
+        let plugin_id = PluginId::from_string(format!("file://{}", source_dir.path().display()));
+
+        PluginLoader::download_and_save(&repository, plugin_id.clone(), None, true, DbPluginType::Normal)
+            .await
+            .unwrap();
+
+        let loader = PluginLoader::new(repository.clone(), DownloadRetryPolicy::default(), 3).await.unwrap();
+
+        assert!(loader.check_for_updates().await.unwrap().is_empty());
+
+        let latest_version = commit_to_branch(source_dir.path(), RELEASE_BRANCH);
+
+        let updates = loader.check_for_updates().await.unwrap();
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].plugin_id, plugin_id);
+        assert_eq!(updates[0].plugin_name, "Config Plugin");
+        assert_eq!(updates[0].latest_version, latest_version);
+        assert_ne!(updates[0].current_version, latest_version);
+    }
+
+    #[tokio::test]
+    async fn config_plugin_pinned_to_git_ref_is_not_checked_for_updates() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let db_dir = tempfile::tempdir().unwrap();
+
+        create_git_source(source_dir.path(), "v1");
+
+        let repository = DataDbRepository::open(&db_dir.path().join("data.db")).await.unwrap();
+
+        let plugin_id = PluginId::from_string(format!("file://{}", source_dir.path().display()));
+
+        PluginLoader::download_and_save(&repository, plugin_id, Some("v1".to_string()), true, DbPluginType::Config)
+            .await
+            .unwrap();
+
+        commit_to_branch(source_dir.path(), "v1");
+
+        let loader = PluginLoader::new(repository.clone(), DownloadRetryPolicy::default(), 3).await.unwrap();
+
+        assert!(loader.check_for_updates().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn wildcard_is_allowed_only_as_first_label_of_network_permission() {
+        assert!(PluginLoader::validate_network_permissions(&["*.example.com".to_string()]).is_ok());
+        assert!(PluginLoader::validate_network_permissions(&["*.example.com:8080".to_string()]).is_ok());
+        assert!(PluginLoader::validate_network_permissions(&["api.*.example.com".to_string()]).is_err());
+        assert!(PluginLoader::validate_network_permissions(&["*".to_string()]).is_err());
+    }
+
+    fn no_delay_policy() -> DownloadRetryPolicy {
+        DownloadRetryPolicy {
+            max_attempts: 4,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    fn network_error() -> anyhow::Error {
+        git2::Error::new(git2::ErrorCode::GenericError, git2::ErrorClass::Net, "connection reset").into()
+    }
+
+    fn not_found_error() -> anyhow::Error {
+        git2::Error::new(git2::ErrorCode::GenericError, git2::ErrorClass::Http, "unexpected http status code: 404").into()
+    }
+
+    #[test]
+    fn retry_delay_grows_up_to_max() {
+        let policy = DownloadRetryPolicy::default();
+
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(2), Duration::from_secs(2));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(10), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn server_errors_are_transient() {
+        let server_error: anyhow::Error = git2::Error::new(git2::ErrorCode::GenericError, git2::ErrorClass::Http, "unexpected http status code: 503").into();
+
+        assert!(is_transient_download_error(&network_error()));
+        assert!(is_transient_download_error(&server_error));
+        assert!(!is_transient_download_error(&not_found_error()));
+        assert!(!is_transient_download_error(&anyhow!("checksum mismatch")));
+    }
+
+    #[tokio::test]
+    async fn transient_errors_are_retried() {
+        let attempts = AtomicU32::new(0);
+        let plugin_id = PluginId::from_string("https://github.com/project-gauntlet/plugin");
+
+        let result = retry_transient_download(&no_delay_policy(), &plugin_id, || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+
+            async move {
+                if attempt <= 2 {
+                    Err(network_error())
+                } else {
+                    Ok(attempt)
+                }
+            }
+        }).await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn permanent_errors_are_not_retried() {
+        let attempts = AtomicU32::new(0);
+        let plugin_id = PluginId::from_string("https://github.com/project-gauntlet/plugin");
+
+        let result: anyhow::Result<()> = retry_transient_download(&no_delay_policy(), &plugin_id, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+
+            async { Err(not_found_error()) }
+        }).await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retries_stop_after_max_attempts() {
+        let attempts = AtomicU32::new(0);
+        let plugin_id = PluginId::from_string("https://github.com/project-gauntlet/plugin");
+
+        let result: anyhow::Result<()> = retry_transient_download(&no_delay_policy(), &plugin_id, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+
+            async { Err(network_error()) }
+        }).await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn downloads_over_the_limit_are_queued() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let download_status_holder = DownloadStatusHolder::new(repository).await.unwrap();
+        let download_semaphore = Semaphore::new(2);
+
+        let running = AtomicU32::new(0);
+        let max_running = AtomicU32::new(0);
+        let max_in_progress = AtomicU32::new(0);
+
+        let mut guards = vec![];
+        for index in 0..5 {
+            let plugin_id = PluginId::from_string(format!("plugin-{}", index));
+            guards.push(download_status_holder.download_queued(plugin_id).await);
+        }
+
+        let downloads = guards.iter()
+            .map(|guard| {
+                let download = async {
+                    let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now_running, Ordering::SeqCst);
+
+                    let in_progress = download_status_holder.download_status()
+                        .values()
+                        .filter(|status| matches!(status, DownloadStatus::InProgress))
+                        .count();
+                    max_in_progress.fetch_max(in_progress as u32, Ordering::SeqCst);
+
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+
+                    running.fetch_sub(1, Ordering::SeqCst);
+                };
+
+                run_in_download_slot(&download_semaphore, guard, download)
+            });
+
+        join_all(downloads).await;
+
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+        assert_eq!(max_in_progress.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn linked_plugin_code_is_read_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let loader = PluginLoader::new(repository.clone(), DownloadRetryPolicy::default(), 3).await.unwrap();
+
+        let plugin_dir = dir.path().join("plugin");
+        let js_dir = plugin_dir.join("dist").join("js");
+        std::fs::create_dir_all(&js_dir).unwrap();
+        std::fs::write(plugin_dir.join("dist").join("gauntlet.toml"), MANIFEST).unwrap();
+        std::fs::write(js_dir.join("command.js"), "export default function command() {}").unwrap();
+
+        let plugin_id = loader.save_local_plugin(plugin_dir.to_str().unwrap(), true).await.unwrap();
+
+        std::fs::write(js_dir.join("command.js"), "export default function command() { return 1 }").unwrap();
+
+        let plugin = repository.get_plugin_by_id(&plugin_id.to_string()).await.unwrap();
+        let js = PluginLoader::read_plugin_code(plugin.code).unwrap();
+
+        assert_eq!(plugin.plugin_type, db_plugin_type_to_str(DbPluginType::Linked));
+        assert_eq!(js.get("command").map(String::as_str), Some("export default function command() { return 1 }"));
+    }
+
+    #[tokio::test]
+    async fn plugin_id_reserved_for_bundled_plugins_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let plugin_id = PluginId::from_string("bundled://gauntlet");
+
+        let result = PluginLoader::validate_plugin_id(&repository, &plugin_id, &DbPluginType::Normal).await;
+
+        assert!(result.unwrap_err().to_string().contains("reserved for bundled plugins"));
+    }
+
+    #[tokio::test]
+    async fn malformed_plugin_id_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+
+        let plugin_id = PluginId::from_string("bundled://Not A Slug");
+
+        let result = PluginLoader::validate_plugin_id(&repository, &plugin_id, &DbPluginType::Bundled).await;
+
+        assert!(result.unwrap_err().to_string().contains("should contain only lowercase ascii letters"));
+    }
+
+    static BUNDLED_PLUGIN: Dir = Dir::new("", &[
+        include_dir::DirEntry::File(include_dir::File::new("gauntlet.toml", MANIFEST.as_bytes())),
+        include_dir::DirEntry::Dir(Dir::new("js", &[
+            include_dir::DirEntry::File(include_dir::File::new("js/command.js", b"export default function command() {}")),
+        ])),
+    ]);
+
+    #[tokio::test]
+    async fn reinstalled_bundled_plugin_keeps_preferences() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let loader = PluginLoader::new(repository.clone(), DownloadRetryPolicy::default(), 3).await.unwrap();
+
+        let plugin_id = loader.save_bundled_plugin("test", &BUNDLED_PLUGIN).await.unwrap();
+
+        repository.set_preference_value(plugin_id.to_string(), None, "greeting".to_string(), DbPluginPreferenceUserData::String { value: Some("hello".to_string()) }).await.unwrap();
+
+        // corrupt stored code, as if plugin got into a bad state
+        let plugin = repository.get_plugin_by_id(&plugin_id.to_string()).await.unwrap();
+        repository.save_plugin(DbWritePlugin {
+            id: plugin.id,
+            name: plugin.name,
+            description: plugin.description,
+            enabled: plugin.enabled,
+            code: DbCode { js: HashMap::new(), linked_path: None },
+            entrypoints: vec![],
+            asset_data: vec![],
+            permissions: plugin.permissions,
+            plugin_type: plugin.plugin_type,
+            preferences: plugin.preferences,
+            version: None,
+            min_app_version: None,
+            max_app_version: None,
+        }).await.unwrap();
+
+        loader.save_bundled_plugin("test", &BUNDLED_PLUGIN).await.unwrap();
+
+        let plugin = repository.get_plugin_by_id(&plugin_id.to_string()).await.unwrap();
+
+        assert_eq!(plugin.code.js.get("command").map(String::as_str), Some("export default function command() {}"));
+        assert!(matches!(plugin.preferences_user_data.get("greeting"), Some(DbPluginPreferenceUserData::String { value: Some(value) }) if value == "hello"));
+    }
+
+    fn manifest_with_app_version(app_version: &str) -> String {
+        MANIFEST.replace("description = 'Plugin from config'\n", &format!("description = 'Plugin from config'\n{}\n", app_version))
+    }
+
+    #[test]
+    fn plugin_for_newer_app_version_is_rejected() {
+        let plugin_dir = tempfile::tempdir().unwrap();
+
+        let manifest = manifest_with_app_version(&format!("min_app_version = {}", *APP_VERSION + 1));
+        std::fs::write(plugin_dir.path().join("gauntlet.toml"), manifest).unwrap();
+
+        let err = PluginLoader::validate_manifest_in_dir(plugin_dir.path()).unwrap_err();
+
+        assert!(err.to_string().contains(&format!("requires Gauntlet version {} or newer", *APP_VERSION + 1)));
+    }
+
+    #[test]
+    fn plugin_for_current_app_version_is_accepted() {
+        let plugin_dir = tempfile::tempdir().unwrap();
+
+        let manifest = manifest_with_app_version(&format!("min_app_version = {}\nmax_app_version = {}", *APP_VERSION, *APP_VERSION));
+        std::fs::write(plugin_dir.path().join("gauntlet.toml"), manifest).unwrap();
+
+        assert!(PluginLoader::validate_manifest_in_dir(plugin_dir.path()).is_ok());
+    }
+
+    #[test]
+    fn installed_plugin_for_older_app_version_is_not_started() {
+        assert!(PluginLoader::check_app_version(None, Some(*APP_VERSION - 1)).is_err());
+        assert!(PluginLoader::check_app_version(None, None).is_ok());
+    }
+
+    fn lint_manifest(manifest: &str) -> Vec<PluginManifestLintIssue> {
+        let plugin_dir = tempfile::tempdir().unwrap();
+
+        std::fs::write(plugin_dir.path().join("gauntlet.toml"), manifest).unwrap();
+
+        PluginLoader::lint_manifest_in_dir(plugin_dir.path())
+    }
+
+    #[test]
+    fn clean_manifest_has_no_lint_issues() {
+        assert!(lint_manifest(MANIFEST).is_empty());
+    }
+
+    #[test]
+    fn broad_network_permission_is_lint_warning() {
+        let manifest = format!("{}\n[permissions]\nnetwork = ['*.com', 'api.example.com']\n", MANIFEST);
+
+        let issues = lint_manifest(&manifest);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, PluginManifestLintSeverity::Warning);
+        assert!(issues[0].message.contains("*.com"));
+    }
+
+    #[test]
+    fn invalid_manifest_is_lint_error() {
+        // bare wildcard is rejected by manifest validation, not just reported as broad
+        let manifest = format!("{}\n[permissions]\nnetwork = ['*']\n", MANIFEST);
+
+        let issues = lint_manifest(&manifest);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, PluginManifestLintSeverity::Error);
+    }
+
+    #[tokio::test]
+    async fn unchanged_bundled_plugin_is_not_saved_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let loader = PluginLoader::new(repository.clone(), DownloadRetryPolicy::default(), 3).await.unwrap();
+
+        let (plugin_id, saved) = loader.save_bundled_plugin_if_changed("test", &BUNDLED_PLUGIN).await.unwrap();
+        assert!(saved);
+
+        // user disabled the plugin, unnecessary re-save would have enabled it back
+        repository.set_plugin_enabled(&plugin_id.to_string(), false).await.unwrap();
+
+        let (_, saved) = loader.save_bundled_plugin_if_changed("test", &BUNDLED_PLUGIN).await.unwrap();
+        assert!(!saved);
+
+        assert!(!repository.is_plugin_enabled(&plugin_id.to_string()).await.unwrap());
+    }
+}
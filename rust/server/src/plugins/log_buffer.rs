@@ -0,0 +1,148 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use common::model::{PluginId, PluginLogLevel, PluginLogLine};
+
+const MAX_LINES_PER_PLUGIN: usize = 500;
+const MIN_LEVEL_ENV: &'static str = "GAUNTLET_PLUGIN_LOG_LEVEL";
+
+#[derive(Clone)]
+pub struct PluginLogBuffer {
+    logs: Arc<Mutex<HashMap<PluginId, VecDeque<PluginLogLine>>>>,
+    min_level: PluginLogLevel,
+}
+
+impl PluginLogBuffer {
+    pub fn new() -> Self {
+        let min_level = std::env::var(MIN_LEVEL_ENV)
+            .ok()
+            .and_then(|level| log_level_from_str(&level))
+            .unwrap_or(PluginLogLevel::Debug);
+
+        Self {
+            logs: Arc::new(Mutex::new(HashMap::new())),
+            min_level,
+        }
+    }
+
+    pub fn push(&self, plugin_id: &PluginId, level: PluginLogLevel, message: impl ToString) {
+        if level < self.min_level {
+            return;
+        }
+
+        let mut logs = self.logs.lock().expect("lock is poisoned");
+
+        let lines = logs.entry(plugin_id.clone())
+            .or_insert_with(|| VecDeque::with_capacity(MAX_LINES_PER_PLUGIN));
+
+        if lines.len() == MAX_LINES_PER_PLUGIN {
+            lines.pop_front();
+        }
+
+        lines.push_back(PluginLogLine {
+            timestamp: SystemTime::now(),
+            level,
+            message: message.to_string(),
+        });
+    }
+
+    // returns last `limit` lines, oldest first
+    pub fn lines(&self, plugin_id: &PluginId, limit: usize) -> Vec<PluginLogLine> {
+        let logs = self.logs.lock().expect("lock is poisoned");
+
+        match logs.get(plugin_id) {
+            None => vec![],
+            Some(lines) => {
+                let skip = lines.len().saturating_sub(limit);
+
+                lines.iter()
+                    .skip(skip)
+                    .cloned()
+                    .collect()
+            }
+        }
+    }
+
+    pub fn remove(&self, plugin_id: &PluginId) {
+        let mut logs = self.logs.lock().expect("lock is poisoned");
+
+        logs.remove(plugin_id);
+    }
+}
+
+pub fn log_level_from_str(value: &str) -> Option<PluginLogLevel> {
+    match value.to_lowercase().as_str() {
+        "trace" => Some(PluginLogLevel::Trace),
+        "debug" => Some(PluginLogLevel::Debug),
+        "info" | "log" => Some(PluginLogLevel::Info),
+        "warn" => Some(PluginLogLevel::Warn),
+        "error" => Some(PluginLogLevel::Error),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(lines: Vec<PluginLogLine>) -> Vec<String> {
+        lines.into_iter()
+            .map(|line| line.message)
+            .collect()
+    }
+
+    #[test]
+    fn returns_last_lines_in_order() {
+        let log_buffer = PluginLogBuffer::new();
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        for index in 0..10 {
+            log_buffer.push(&plugin_id, PluginLogLevel::Info, format!("line {}", index));
+        }
+
+        assert_eq!(messages(log_buffer.lines(&plugin_id, 3)), vec!["line 7", "line 8", "line 9"]);
+        assert_eq!(log_buffer.lines(&plugin_id, 100).len(), 10);
+    }
+
+    #[test]
+    fn drops_oldest_lines_when_full() {
+        let log_buffer = PluginLogBuffer::new();
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        for index in 0..MAX_LINES_PER_PLUGIN + 5 {
+            log_buffer.push(&plugin_id, PluginLogLevel::Info, format!("line {}", index));
+        }
+
+        let lines = messages(log_buffer.lines(&plugin_id, usize::MAX));
+
+        assert_eq!(lines.len(), MAX_LINES_PER_PLUGIN);
+        assert_eq!(lines.first().unwrap(), "line 5");
+        assert_eq!(lines.last().unwrap(), &format!("line {}", MAX_LINES_PER_PLUGIN + 4));
+    }
+
+    #[test]
+    fn keeps_lines_of_plugins_separate() {
+        let log_buffer = PluginLogBuffer::new();
+        let first_plugin_id = PluginId::from_string("first-plugin");
+        let second_plugin_id = PluginId::from_string("second-plugin");
+
+        log_buffer.push(&first_plugin_id, PluginLogLevel::Info, "first");
+        log_buffer.push(&second_plugin_id, PluginLogLevel::Error, "second");
+
+        assert_eq!(messages(log_buffer.lines(&first_plugin_id, 10)), vec!["first"]);
+        assert_eq!(messages(log_buffer.lines(&second_plugin_id, 10)), vec!["second"]);
+
+        log_buffer.remove(&first_plugin_id);
+
+        assert!(log_buffer.lines(&first_plugin_id, 10).is_empty());
+        assert_eq!(log_buffer.lines(&second_plugin_id, 10).len(), 1);
+    }
+
+    #[test]
+    fn parses_console_log_levels() {
+        assert_eq!(log_level_from_str("log"), Some(PluginLogLevel::Info));
+        assert_eq!(log_level_from_str("WARN"), Some(PluginLogLevel::Warn));
+        assert_eq!(log_level_from_str("table"), None);
+    }
+}
@@ -1,42 +1,57 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use anyhow::anyhow;
 use deno_core::futures::channel::mpsc::Sender;
 use global_hotkey::GlobalHotKeyManager;
 use global_hotkey::hotkey::HotKey;
 use include_dir::{Dir, include_dir};
+use once_cell::sync::OnceCell;
 use tokio::runtime::Handle;
 
-use common::model::{DownloadStatus, EntrypointId, KeyboardEventOrigin, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginPreference, PluginPreferenceUserData, PreferenceEnumValue, SearchResult, SettingsEntrypoint, SettingsEntrypointType, SettingsPlugin, UiPropertyValue, UiRequestData, UiResponseData, UiWidgetId};
+use common::model::{DownloadStatus, EntrypointId, GauntletMetrics, HealthStatus, KeyboardEventOrigin, LocalSaveData, PhysicalKey, PhysicalShortcut, PluginId, PluginAuditEntry, PluginLogLevel, PluginLogLine, PluginPreference, PluginPreferenceUserData, PluginResourceUsage, PluginUpdate, PreferenceEnumValue, PreferenceSearchResult, SearchResult, SettingsEntrypoint, SettingsEntrypointType, SettingsPlugin, UiPropertyValue, UiRequestData, UiResponseData, UiWidgetId, UpdateResult};
 use common::rpc::frontend_api::FrontendApi;
 use common::{settings_env_data_to_string, SettingsEnvData};
 use utils::channel::RequestSender;
 use common::dirs::Dirs;
 use crate::model::{ActionShortcutKey, JsKeyboardEventOrigin};
-use crate::plugins::config_reader::ConfigReader;
-use crate::plugins::data_db_repository::{DataDbRepository, db_entrypoint_from_str, DbPluginActionShortcutKind, DbPluginEntrypointType, DbPluginPreference, DbPluginPreferenceUserData, DbReadPluginEntrypoint, DbPluginClipboardPermissions, DbPluginMainSearchBarPermissions};
+use crate::plugins::config_reader::{read_timezone_config, ConfigReader};
+use crate::plugins::data_db_repository::{DataDbRepository, db_entrypoint_from_str, db_plugin_type_from_str, DbPluginType, DbPluginAction, DbPluginActionShortcutKind, DbPluginEntrypointType, DbPluginPreference, DbPluginPreferenceUserData, DbReadPlugin, DbReadPluginEntrypoint, DbSettingsFallbackEntrypointData, DbPluginClipboardPermissions, DbPluginMainSearchBarPermissions};
 use crate::plugins::global_shortcut::{convert_physical_shortcut_to_hotkey, register_listener};
 use crate::plugins::icon_cache::IconCache;
+use crate::plugins::log_buffer::PluginLogBuffer;
+use crate::plugins::audit_log::PluginAuditLog;
 use crate::plugins::js::{AllPluginCommandData, OnePluginCommandData, PluginCode, PluginCommand, PluginRuntimeData, start_plugin_runtime};
 use crate::plugins::js::permissions::{PluginPermissions, PluginPermissionsClipboard, PluginPermissionsExec, PluginPermissionsFileSystem, PluginPermissionsMainSearchBar};
-use crate::plugins::loader::PluginLoader;
-use crate::plugins::run_status::RunStatusHolder;
+use crate::plugins::loader::{DEFAULT_MAX_CONCURRENT_DOWNLOADS, DownloadRetryPolicy, PluginLoader};
+use crate::plugins::search_reload::{SearchIndexReloadCoalescer, SearchIndexUpdateKind};
+use crate::plugins::enabled_cache::EnabledStateCache;
+use crate::plugins::run_status::{start_health_pings, RunStatusHolder};
+use crate::plugins::frecency::start_frecency_flush;
 use crate::search::SearchIndex;
 use crate::SETTINGS_ENV;
 
 pub mod js;
 mod data_db_repository;
 mod config_reader;
-mod loader;
+pub(super) mod config_watcher;
+pub(super) mod applications_watcher;
+pub(super) mod loader;
 mod run_status;
 mod download_status;
 mod applications;
 mod icon_cache;
 pub(super) mod frecency;
 mod global_shortcut;
+mod log_buffer;
+mod audit_log;
+mod search_reload;
+mod enabled_cache;
+mod export;
+pub(super) mod scaffold;
 
 static BUNDLED_PLUGINS: [(&str, Dir); 1] = [
     ("gauntlet", include_dir!("$CARGO_MANIFEST_DIR/../../bundled_plugins/gauntlet/dist")),
@@ -53,7 +68,11 @@ pub struct ApplicationManager {
     frontend_api: FrontendApi,
     global_hotkey_manager: GlobalHotKeyManager,
     current_hotkey: Mutex<Option<HotKey>>,
-    dirs: Dirs
+    dirs: Dirs,
+    log_buffer: PluginLogBuffer,
+    audit_log: PluginAuditLog,
+    search_index_reload_coalescer: SearchIndexReloadCoalescer,
+    enabled_state_cache: EnabledStateCache,
 }
 
 impl ApplicationManager {
@@ -61,17 +80,27 @@ impl ApplicationManager {
         let frontend_api = FrontendApi::new(frontend_sender);
         let dirs = Dirs::new();
         let db_repository = DataDbRepository::new(dirs.clone()).await?;
-        let plugin_downloader = PluginLoader::new(db_repository.clone());
+        let plugin_downloader = PluginLoader::new(db_repository.clone(), DownloadRetryPolicy::default(), DEFAULT_MAX_CONCURRENT_DOWNLOADS).await?;
         let config_reader = ConfigReader::new(dirs.clone(), db_repository.clone());
         let icon_cache = IconCache::new(dirs.clone());
         let run_status_holder = RunStatusHolder::new();
-        let search_index = SearchIndex::create_index(frontend_api.clone())?;
+        let search_index = SearchIndex::open_or_create(frontend_api.clone(), &dirs.search_index_dir()?)?;
         let global_hotkey_manager = GlobalHotKeyManager::new()?;
 
         let (command_broadcaster, _) = tokio::sync::broadcast::channel::<PluginCommand>(100);
 
+        let search_index_reload_coalescer = SearchIndexReloadCoalescer::new(command_broadcaster.clone());
+
         register_listener(frontend_api.clone());
 
+        start_health_pings(command_broadcaster.clone());
+
+        start_frecency_flush(db_repository.clone());
+
+        let enabled_state_cache = EnabledStateCache::new(db_repository.clone());
+
+        let audit_log = PluginAuditLog::new(db_repository.clone());
+
         let manager = Self {
             config_reader,
             search_index,
@@ -83,13 +112,24 @@ impl ApplicationManager {
             frontend_api,
             global_hotkey_manager,
             current_hotkey: Mutex::new(None),
-            dirs
+            dirs,
+            log_buffer: PluginLogBuffer::new(),
+            audit_log,
+            search_index_reload_coalescer,
+            enabled_state_cache,
         };
 
         if let Err(err) = manager.register_global_shortcut().await {
             tracing::warn!(target = "rpc", "error occurred when registering shortcut {:?}", err)
         }
 
+        match manager.db_repository.get_plugin_boosts().await {
+            Ok(plugin_boosts) => manager.search_index.set_plugin_boosts(plugin_boosts_from_db(plugin_boosts)),
+            Err(err) => tracing::warn!("Unable to load plugin ranking boosts: {:?}", err),
+        }
+
+        manager.search_index.warmup();
+
         Ok(manager)
     }
 
@@ -107,14 +147,49 @@ impl ApplicationManager {
         self.plugin_downloader.download_status()
     }
 
-    pub fn search(&self, text: &str, render_inline_view: bool) -> anyhow::Result<Vec<SearchResult>> {
+    pub async fn updates_available(&self) -> anyhow::Result<Vec<PluginUpdate>> {
+        self.plugin_downloader.check_for_updates().await
+    }
+
+    // config plugins are pinned to version in config and are not considered to have updates
+    pub async fn update_all_plugins(&self) -> anyhow::Result<Vec<(PluginId, UpdateResult)>> {
+        let plugin_ids = self.updates_available()
+            .await?
+            .into_iter()
+            .map(|update| update.plugin_id)
+            .collect();
+
+        Ok(update_plugins(plugin_ids, |plugin_id| self.update_plugin(plugin_id)).await)
+    }
+
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
+    async fn update_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        tracing::info!(target = "plugin", "Updating plugin");
+
+        self.plugin_downloader.update_plugin(plugin_id.clone()).await?;
+
+        self.reload_plugin(plugin_id).await?;
+
+        Ok(())
+    }
+
+    // suggestion is only provided when query doesn't match anything
+    pub fn search(&self, text: &str, render_inline_view: bool) -> anyhow::Result<(Vec<SearchResult>, Option<String>)> {
         let result = self.search_index.search(&text);
 
         if render_inline_view {
             self.handle_inline_view(&text);
         }
 
-        result
+        let result = result?;
+
+        let suggestion = if result.is_empty() {
+            self.search_index.suggest(&text)?
+        } else {
+            None
+        };
+
+        Ok((result, suggestion))
     }
 
     pub async fn show_window(&self) -> anyhow::Result<()> {
@@ -126,10 +201,11 @@ impl ApplicationManager {
     pub async fn save_local_plugin(
         &self,
         path: &str,
+        linked: bool,
     ) -> anyhow::Result<LocalSaveData> {
-        tracing::info!(target = "plugin", "Saving local plugin at path: {:?}", path);
+        tracing::info!(target = "plugin", "Saving local plugin at path: {:?}, linked: {:?}", path, linked);
 
-        let plugin_id = self.plugin_downloader.save_local_plugin(path).await?;
+        let plugin_id = self.plugin_downloader.save_local_plugin(path, linked).await?;
 
         let plugin = self.db_repository.get_plugin_by_id(&plugin_id.to_string())
             .await?;
@@ -146,9 +222,13 @@ impl ApplicationManager {
 
     pub async fn load_bundled_plugins(&self) -> anyhow::Result<()> {
         for (id, dir) in &BUNDLED_PLUGINS {
-            tracing::info!(target = "plugin", "Saving builtin plugin with id: {:?}", id);
+            let (plugin_id, saved) = self.plugin_downloader.save_bundled_plugin_if_changed(id, dir).await?;
 
-            let plugin_id = self.plugin_downloader.save_bundled_plugin(id, dir).await?;
+            if saved {
+                tracing::info!(target = "plugin", "Saved builtin plugin with id: {:?}", id);
+            } else {
+                tracing::debug!(target = "plugin", "Builtin plugin with id {:?} is unchanged, skipping save", id);
+            }
 
             self.reload_plugin(plugin_id).await?;
         }
@@ -156,60 +236,53 @@ impl ApplicationManager {
         Ok(())
     }
 
+    // saves bundled code again over whatever is stored, user preferences and enabled state are preserved
+    pub async fn reinstall_bundled_plugin(&self, id: &str) -> anyhow::Result<()> {
+        let (id, dir) = BUNDLED_PLUGINS.iter()
+            .find(|(bundled_id, _)| *bundled_id == id)
+            .ok_or_else(|| anyhow!("There is no bundled plugin with id: {:?}", id))?;
+
+        tracing::info!(target = "plugin", "Reinstalling builtin plugin with id: {:?}", id);
+
+        let plugin_id = self.plugin_downloader.save_bundled_plugin(id, dir).await?;
+
+        self.reload_plugin(plugin_id).await?;
+
+        Ok(())
+    }
+
     pub async fn plugins(&self) -> anyhow::Result<Vec<SettingsPlugin>> {
+        settings_plugins(&self.db_repository).await
+    }
+
+    pub async fn plugins_page(&self, offset: u32, limit: u32) -> anyhow::Result<Vec<SettingsPlugin>> {
         let result = self.db_repository
-            .list_plugins_and_entrypoints()
+            .list_plugins_and_entrypoints_page(offset, limit)
             .await?
             .into_iter()
-            .map(|(plugin, entrypoints)| {
-                let entrypoints = entrypoints
-                    .into_iter()
-                    .map(|entrypoint| {
-                        let entrypoint_id = EntrypointId::from_string(entrypoint.id);
-
-                        let entrypoint = SettingsEntrypoint {
-                            enabled: entrypoint.enabled,
-                            entrypoint_id: entrypoint_id.clone(),
-                            entrypoint_name: entrypoint.name,
-                            entrypoint_description: entrypoint.description,
-                            entrypoint_type: match db_entrypoint_from_str(&entrypoint.entrypoint_type) {
-                                DbPluginEntrypointType::Command => SettingsEntrypointType::Command,
-                                DbPluginEntrypointType::View => SettingsEntrypointType::View,
-                                DbPluginEntrypointType::InlineView => SettingsEntrypointType::InlineView,
-                                DbPluginEntrypointType::CommandGenerator => SettingsEntrypointType::CommandGenerator,
-                            }.into(),
-                            preferences: entrypoint.preferences.into_iter()
-                                .map(|(key, value)| {
-                                    let preference = plugin_preference_from_db(&key, value);
-                                    (key, preference)
-                                })
-                                .collect(),
-                            preferences_user_data: entrypoint.preferences_user_data.into_iter()
-                                .map(|(key, value)| (key, plugin_preference_user_data_from_db(value)))
-                                .collect(),
-                        };
-
-                        (entrypoint_id, entrypoint)
-                    })
-                    .collect();
-
-                SettingsPlugin {
-                    plugin_id: PluginId::from_string(plugin.id),
-                    plugin_name: plugin.name,
-                    plugin_description: plugin.description,
-                    enabled: plugin.enabled,
-                    entrypoints,
-                    preferences: plugin.preferences.into_iter()
-                        .map(|(key, value)| {
-                            let preference = plugin_preference_from_db(&key, value);
-                            (key, preference)
-                        })
-                        .collect(),
-                    preferences_user_data: plugin.preferences_user_data.into_iter()
-                        .map(|(key, value)| (key, plugin_preference_user_data_from_db(value)))
-                        .collect(),
-                }
-            })
+            .map(|(plugin, entrypoints)| settings_plugin_from_db(plugin, entrypoints))
+            .collect();
+
+        Ok(result)
+    }
+
+    pub async fn plugin(&self, plugin_id: PluginId) -> anyhow::Result<SettingsPlugin> {
+        settings_plugin(&self.db_repository, &plugin_id).await
+    }
+
+    pub async fn entrypoints_of_type(&self, entrypoint_type: SettingsEntrypointType) -> anyhow::Result<Vec<(PluginId, SettingsEntrypoint)>> {
+        let entrypoint_type = match entrypoint_type {
+            SettingsEntrypointType::Command => DbPluginEntrypointType::Command,
+            SettingsEntrypointType::View => DbPluginEntrypointType::View,
+            SettingsEntrypointType::InlineView => DbPluginEntrypointType::InlineView,
+            SettingsEntrypointType::CommandGenerator => DbPluginEntrypointType::CommandGenerator,
+        };
+
+        let result = self.db_repository
+            .list_entrypoints_by_type(entrypoint_type)
+            .await?
+            .into_iter()
+            .map(|entrypoint| (PluginId::from_string(entrypoint.plugin_id.clone()), settings_entrypoint_from_db(entrypoint)))
             .collect();
 
         Ok(result)
@@ -221,9 +294,13 @@ impl ApplicationManager {
 
         tracing::info!(target = "plugin", "Setting plugin state for plugin id: {:?}, currently_running: {}, currently_enabled: {}, set_enabled: {}", plugin_id, currently_running, currently_enabled, set_enabled);
 
+        // explicitly changing plugin state overrides temporary suspension and errored state after force stop
+        self.run_status_holder.resume_plugin(&plugin_id);
+        self.run_status_holder.clear_plugin_errored(&plugin_id);
+
         match (currently_running, currently_enabled, set_enabled) {
             (false, false, true) => {
-                self.db_repository.set_plugin_enabled(&plugin_id.to_string(), true)
+                self.enabled_state_cache.set_plugin_enabled(&plugin_id, true)
                     .await?;
 
                 self.start_plugin(plugin_id).await?;
@@ -232,7 +309,7 @@ impl ApplicationManager {
                 self.start_plugin(plugin_id).await?;
             }
             (true, true, false) => {
-                self.db_repository.set_plugin_enabled(&plugin_id.to_string(), false)
+                self.enabled_state_cache.set_plugin_enabled(&plugin_id, false)
                     .await?;
 
                 self.stop_plugin(plugin_id.clone()).await;
@@ -247,10 +324,75 @@ impl ApplicationManager {
         Ok(())
     }
 
+    pub async fn count_plugins(&self) -> anyhow::Result<usize> {
+        self.db_repository.count_plugins().await
+    }
+
+    pub async fn count_entrypoints(&self) -> anyhow::Result<usize> {
+        self.db_repository.count_entrypoints().await
+    }
+
+    pub async fn metrics(&self) -> anyhow::Result<GauntletMetrics> {
+        gather_metrics(&self.db_repository, &self.run_status_holder, |percentile| self.search_index.search_latency_percentile(percentile))
+            .await
+    }
+
+    pub fn plugin_health(&self, plugin_id: PluginId) -> HealthStatus {
+        self.run_status_holder.plugin_health(&plugin_id)
+    }
+
+    pub fn plugin_resource_usage(&self, plugin_id: PluginId) -> Option<PluginResourceUsage> {
+        self.run_status_holder.plugin_resource_usage(&plugin_id)
+    }
+
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
+    pub async fn suspend_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        tracing::info!(target = "plugin", "Suspending plugin until restart");
+
+        self.run_status_holder.suspend_plugin(plugin_id.clone());
+
+        if self.run_status_holder.is_plugin_running(&plugin_id) {
+            self.stop_plugin(plugin_id.clone()).await;
+        }
+
+        self.search_index.remove_for_plugin(plugin_id)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
+    pub async fn resume_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        tracing::info!(target = "plugin", "Resuming plugin");
+
+        if !self.run_status_holder.resume_plugin(&plugin_id) {
+            return Err(anyhow!("Plugin with id '{}' is not suspended", plugin_id.to_string()))
+        }
+
+        let running = self.run_status_holder.is_plugin_running(&plugin_id);
+
+        if !running && should_plugin_run(&self.db_repository, &self.run_status_holder, &plugin_id).await? {
+            self.start_plugin(plugin_id).await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn set_entrypoint_state(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, enabled: bool) -> anyhow::Result<()> {
         tracing::debug!(target = "plugin", "Setting entrypoint state for plugin id: {:?}, entrypoint_id: {:?}, enabled: {}", plugin_id, entrypoint_id, enabled);
 
-        self.db_repository.set_plugin_entrypoint_enabled(&plugin_id.to_string(), &entrypoint_id.to_string(), enabled)
+        self.enabled_state_cache.set_entrypoint_enabled(&plugin_id, &entrypoint_id, enabled)
+            .await?;
+
+        self.request_search_index_reload(plugin_id);
+
+        Ok(())
+    }
+
+    // non-searchable entrypoints are hidden from search, but can still be run directly
+    pub async fn set_entrypoint_searchable(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, searchable: bool) -> anyhow::Result<()> {
+        tracing::debug!(target = "plugin", "Setting entrypoint searchable for plugin id: {:?}, entrypoint_id: {:?}, searchable: {}", plugin_id, entrypoint_id, searchable);
+
+        self.db_repository.set_plugin_entrypoint_searchable(&plugin_id.to_string(), &entrypoint_id.to_string(), searchable)
             .await?;
 
         self.request_search_index_reload(plugin_id);
@@ -272,6 +414,70 @@ impl ApplicationManager {
         self.db_repository.get_global_shortcut().await
     }
 
+    pub async fn set_fallback_entrypoint(&self, fallback: Option<(PluginId, EntrypointId)>) -> anyhow::Result<()> {
+        if let Some((plugin_id, entrypoint_id)) = &fallback {
+            let entrypoint = self.db_repository.get_entrypoint_by_id_option(&plugin_id.to_string(), &entrypoint_id.to_string())
+                .await?
+                .ok_or_else(|| anyhow!("Entrypoint with id '{}' doesn't exist in plugin '{}'", entrypoint_id.to_string(), plugin_id.to_string()))?;
+
+            if !matches!(db_entrypoint_from_str(&entrypoint.entrypoint_type), DbPluginEntrypointType::Command) {
+                return Err(anyhow!("Entrypoint with id '{}' is not a command", entrypoint_id.to_string()))
+            }
+        }
+
+        let fallback = fallback.map(|(plugin_id, entrypoint_id)| DbSettingsFallbackEntrypointData {
+            plugin_id: plugin_id.to_string(),
+            entrypoint_id: entrypoint_id.to_string(),
+        });
+
+        self.db_repository.set_fallback_entrypoint(fallback).await
+    }
+
+    // factor multiplies ranking score of all plugin entrypoints, 1.0 removes the boost
+    pub async fn set_plugin_boost(&self, plugin_id: PluginId, factor: f64) -> anyhow::Result<()> {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Err(anyhow!("Plugin boost factor should be a positive number, got: {}", factor))
+        }
+
+        self.db_repository.get_plugin_by_id_option(&plugin_id.to_string())
+            .await?
+            .ok_or_else(|| anyhow!("Plugin with id '{}' doesn't exist", plugin_id.to_string()))?;
+
+        let mut plugin_boosts = self.db_repository.get_plugin_boosts().await?;
+
+        if factor == 1.0 {
+            plugin_boosts.remove(&plugin_id.to_string());
+        } else {
+            plugin_boosts.insert(plugin_id.to_string(), factor);
+        }
+
+        self.db_repository.set_plugin_boosts(plugin_boosts.clone()).await?;
+
+        self.search_index.set_plugin_boosts(plugin_boosts_from_db(plugin_boosts));
+
+        Ok(())
+    }
+
+    pub async fn get_fallback_entrypoint(&self) -> anyhow::Result<Option<(PluginId, EntrypointId)>> {
+        let fallback = self.db_repository.get_fallback_entrypoint()
+            .await?
+            .map(|fallback| (PluginId::from_string(fallback.plugin_id), EntrypointId::from_string(fallback.entrypoint_id)));
+
+        Ok(fallback)
+    }
+
+    // runs configured fallback command with the search query, used when search yields nothing
+    pub async fn run_fallback(&self, text: &str) -> anyhow::Result<()> {
+        let Some((plugin_id, entrypoint_id)) = send_fallback_command(&self.db_repository, &self.run_status_holder, &self.command_broadcaster, text).await? else {
+            tracing::debug!("no fallback entrypoint configured, ignoring query: {:?}", text);
+            return Ok(())
+        };
+
+        self.mark_entrypoint_frecency(plugin_id, entrypoint_id).await;
+
+        Ok(())
+    }
+
     pub async fn set_preference_value(&self, plugin_id: PluginId, entrypoint_id: Option<EntrypointId>, preference_id: String, preference_value: PluginPreferenceUserData) -> anyhow::Result<()> {
         tracing::debug!(target = "plugin", "Setting preference value for plugin id: {:?}, entrypoint_id: {:?}, preference_id: {}", plugin_id, entrypoint_id, preference_id);
 
@@ -283,6 +489,54 @@ impl ApplicationManager {
         Ok(())
     }
 
+    pub async fn set_preference_values(&self, plugin_id: PluginId, entrypoint_id: Option<EntrypointId>, preference_values: HashMap<String, PluginPreferenceUserData>) -> anyhow::Result<()> {
+        tracing::debug!(target = "plugin", "Setting {} preference values for plugin id: {:?}, entrypoint_id: {:?}", preference_values.len(), plugin_id, entrypoint_id);
+
+        let user_data = preference_values.into_iter()
+            .map(|(preference_id, value)| (preference_id, plugin_preference_user_data_to_db(value)))
+            .collect();
+
+        self.db_repository.set_preference_values(plugin_id.to_string(), entrypoint_id.map(|id| id.to_string()), user_data)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn search_all_preferences(&self, text: &str) -> anyhow::Result<Vec<PreferenceSearchResult>> {
+        let result = self.db_repository.search_preferences(text)
+            .await?
+            .into_iter()
+            .map(|preference| {
+                PreferenceSearchResult {
+                    plugin_id: PluginId::from_string(preference.plugin_id),
+                    plugin_name: preference.plugin_name,
+                    entrypoint_id: preference.entrypoint_id.map(|id| EntrypointId::from_string(id)),
+                    entrypoint_name: preference.entrypoint_name,
+                    preference_id: preference.preference_id,
+                    preference_name: preference.preference_name,
+                    description: preference.description,
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    // preferences without user data fall back to defaults declared in manifest
+    pub async fn reset_plugin_preferences(&self, plugin_id: PluginId, entrypoint_id: Option<EntrypointId>) -> anyhow::Result<()> {
+        tracing::info!(target = "plugin", "Resetting preferences for plugin id: {:?}, entrypoint_id: {:?}", plugin_id, entrypoint_id);
+
+        self.db_repository.clear_preference_values(&plugin_id.to_string(), entrypoint_id.map(|id| id.to_string()).as_deref())
+            .await?;
+
+        // generated commands can depend on preference values
+        if self.run_status_holder.is_plugin_running(&plugin_id) {
+            self.request_search_index_reload(plugin_id);
+        }
+
+        Ok(())
+    }
+
     async fn register_global_shortcut(&self) -> anyhow::Result<()> {
         let shortcut = self.db_repository.get_global_shortcut().await?;
 
@@ -303,13 +557,176 @@ impl ApplicationManager {
         Ok(())
     }
 
-    pub async fn reload_config(&self) -> anyhow::Result<()> {
-        self.config_reader.reload_config().await?;
+    pub async fn reload_config(self: &Arc<Self>) -> anyhow::Result<()> {
+        let config = match self.config_reader.reload_config().await {
+            Ok(config) => config,
+            Err(err) => {
+                tracing::error!("Unable to reload config, config plugins are left as is: {:?}", err);
+
+                self.report_config_errors(vec![format!("{:#}", err)]);
+
+                return Ok(())
+            }
+        };
+
+        warn_if_timezone_changed(&self.config_reader);
+
+        let config_plugins = config.plugins;
+        let has_errors = !config.errors.is_empty();
+
+        if has_errors {
+            self.report_config_errors(config.errors);
+        }
+
+        for plugin in self.db_repository.list_plugins().await? {
+            // plugin can be missing from config only because its entry is malformed
+            if has_errors {
+                break
+            }
+
+            if let DbPluginType::Config = db_plugin_type_from_str(&plugin.plugin_type) {
+                let in_config = config_plugins.iter().any(|config_plugin| config_plugin.id == plugin.id);
+                if !in_config {
+                    tracing::info!(target = "plugin", "Plugin {:?} was removed from config, removing", plugin.id);
+
+                    self.remove_plugin(PluginId::from_string(plugin.id)).await?;
+                }
+            }
+        }
+
+        for config_plugin in config_plugins {
+            let pending = self.db_repository.is_plugin_pending(&config_plugin.id).await?;
+
+            if !pending {
+                // already installed plugin is downloaded again only if its ref was changed in config
+                let git_ref = self.db_repository.get_plugin_git_ref(&config_plugin.id).await?;
+
+                if git_ref == config_plugin.git_ref {
+                    continue
+                }
+
+                tracing::info!(target = "plugin", "Ref of config plugin {:?} was changed from {:?} to {:?}, downloading", config_plugin.id, git_ref, config_plugin.git_ref);
+            }
+
+            let plugin_id = PluginId::from_string(&config_plugin.id);
+
+            // download started by previous reload is still queued or running
+            if matches!(self.plugin_downloader.download_status().get(&plugin_id), Some(DownloadStatus::Pending | DownloadStatus::InProgress)) {
+                continue
+            }
+
+            // downloads wait for a free download slot, so they are not awaited to not block startup or config reload
+            let application_manager = self.clone();
+            tokio::spawn(async move {
+                application_manager.download_config_plugin(plugin_id, config_plugin.git_ref, pending).await
+            });
+        }
 
         Ok(())
     }
 
-    pub async fn reload_all_plugins(&self) -> anyhow::Result<()> {
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
+    async fn download_config_plugin(&self, plugin_id: PluginId, git_ref: Option<String>, pending: bool) {
+        let result = match self.plugin_downloader.download_config_plugin(plugin_id.clone(), git_ref).await {
+            Ok(()) => {
+                if pending {
+                    self.db_repository.remove_pending_plugin(&plugin_id.to_string()).await
+                } else {
+                    Ok(())
+                }
+            }
+            Err(err) => Err(err),
+        };
+
+        // plugin is started here, because it may not have existed yet when all plugins were reloaded
+        let result = match result {
+            Ok(()) => self.reload_plugin(plugin_id).await,
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = result {
+            tracing::warn!(target = "plugin", "Unable to download config plugin: {:?}", err)
+        }
+    }
+
+    // pending rows are left behind if plugin was removed from config or app was closed mid download
+    pub async fn prune_pending_plugins(&self) -> anyhow::Result<()> {
+        let Some(config_plugin_ids) = self.config_reader.read_plugin_ids()? else {
+            tracing::debug!(target = "plugin", "Config contains errors, pending plugins are not pruned");
+            return Ok(())
+        };
+
+        let pruned = self.db_repository.prune_pending_plugins(&config_plugin_ids)
+            .await?;
+
+        for plugin_id in pruned {
+            tracing::info!(target = "plugin", "Removed stale pending plugin: {:?}", plugin_id);
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
+    pub async fn reload_config_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        let plugin_id_str = plugin_id.to_string();
+
+        let plugin = self.db_repository.get_plugin_by_id_option(&plugin_id_str)
+            .await?;
+
+        let is_pending = self.db_repository.is_plugin_pending(&plugin_id_str)
+            .await?;
+
+        match plugin {
+            Some(plugin) => {
+                if !matches!(db_plugin_type_from_str(&plugin.plugin_type), DbPluginType::Config) {
+                    return Err(anyhow!("Plugin with id '{}' is not managed by config", plugin_id_str))
+                }
+            }
+            None => {
+                if !is_pending {
+                    return Err(anyhow!("Plugin with id '{}' is not managed by config", plugin_id_str))
+                }
+            }
+        }
+
+        tracing::info!(target = "plugin", "Reloading config plugin");
+
+        match self.config_reader.read_plugin_entry(&plugin_id_str)? {
+            None => {
+                if is_pending {
+                    self.db_repository.remove_pending_plugin(&plugin_id_str).await?;
+                } else {
+                    self.remove_plugin(plugin_id).await?;
+                }
+            }
+            Some(config_plugin) => {
+                self.plugin_downloader.download_config_plugin(plugin_id.clone(), config_plugin.git_ref).await?;
+
+                if is_pending {
+                    self.db_repository.remove_pending_plugin(&plugin_id_str).await?;
+                }
+
+                self.reload_plugin(plugin_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn report_config_errors(&self, errors: Vec<String>) {
+        let mut frontend_api = self.frontend_api.clone();
+
+        // frontend may not be ready yet when config is read on startup, so do not wait for it
+        tokio::spawn(async move {
+            let display = format!("Config contains errors: {}", errors.join("; "));
+
+            if let Err(err) = frontend_api.show_hud(display).await {
+                tracing::warn!("Unable to show config errors: {:?}", err);
+            }
+        });
+    }
+
+    pub async fn reload_all_plugins(self: &Arc<Self>) -> anyhow::Result<()> {
         tracing::info!("Reloading all plugins");
 
         self.reload_config().await?;
@@ -317,7 +734,9 @@ impl ApplicationManager {
         for plugin in self.db_repository.list_plugins().await? {
             let plugin_id = PluginId::from_string(plugin.id);
             let running = self.run_status_holder.is_plugin_running(&plugin_id);
-            match (running, plugin.enabled) {
+            let suspended = self.run_status_holder.is_plugin_suspended(&plugin_id);
+            let errored = self.run_status_holder.is_plugin_errored(&plugin_id);
+            match (running, plugin.enabled && !suspended && !errored) {
                 (false, true) => {
                     self.start_plugin(plugin_id).await?;
                 }
@@ -332,18 +751,85 @@ impl ApplicationManager {
         Ok(())
     }
 
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
     pub async fn remove_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
-        tracing::info!(target = "plugin", "Removing plugin with id: {:?}", plugin_id);
+        tracing::info!(target = "plugin", "Removing plugin");
 
         let running = self.run_status_holder.is_plugin_running(&plugin_id);
         if running {
             self.stop_plugin(plugin_id.clone()).await;
         }
+        self.run_status_holder.resume_plugin(&plugin_id);
+        self.run_status_holder.clear_plugin_errored(&plugin_id);
         self.db_repository.remove_plugin(&plugin_id.to_string()).await?;
+        self.enabled_state_cache.invalidate_plugin(&plugin_id).await;
+        self.log_buffer.remove(&plugin_id);
         self.search_index.remove_for_plugin(plugin_id)?;
         Ok(())
     }
 
+    pub fn plugin_logs(&self, plugin_id: PluginId, limit: usize) -> Vec<PluginLogLine> {
+        self.log_buffer.lines(&plugin_id, limit)
+    }
+
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
+    pub async fn export_plugin(&self, plugin_id: PluginId) -> anyhow::Result<Vec<u8>> {
+        tracing::info!(target = "plugin", "Exporting plugin");
+
+        export::export_plugin(&self.db_repository, &plugin_id).await
+    }
+
+    pub async fn import_plugin(&self, bytes: &[u8]) -> anyhow::Result<PluginId> {
+        let plugin_id = export::import_plugin(&self.db_repository, bytes).await?;
+
+        tracing::info!(target = "plugin", "Imported plugin with id: {:?}", plugin_id);
+
+        self.reload_plugin(plugin_id.clone()).await?;
+
+        Ok(plugin_id)
+    }
+
+    pub async fn export_settings(&self) -> anyhow::Result<Vec<u8>> {
+        tracing::info!(target = "plugin", "Exporting settings");
+
+        export::export_settings(&self.db_repository).await
+    }
+
+    pub async fn import_settings(&self, bytes: &[u8]) -> anyhow::Result<()> {
+        let plugins = export::import_settings(&self.db_repository, bytes).await?;
+
+        tracing::info!(target = "plugin", "Imported settings for {} plugins", plugins.len());
+
+        for (plugin_id, enabled) in plugins {
+            // entrypoint enabled state is written directly to db during import
+            self.enabled_state_cache.invalidate_plugin(&plugin_id).await;
+
+            self.set_plugin_state(plugin_id.clone(), enabled).await?;
+
+            if self.run_status_holder.is_plugin_running(&plugin_id) {
+                self.request_search_index_reload(plugin_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn audit_log(&self, plugin_id: PluginId) -> anyhow::Result<Vec<PluginAuditEntry>> {
+        let entries = self.db_repository.list_audit_entries(&plugin_id.to_string())
+            .await?
+            .into_iter()
+            .map(|entry| {
+                PluginAuditEntry {
+                    timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(entry.timestamp as u64),
+                    action: entry.action,
+                    target: entry.target,
+                }
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
     pub fn handle_inline_view(&self, text: &str) {
         self.send_command(PluginCommand::All {
             data: AllPluginCommandData::OpenInlineView {
@@ -352,27 +838,51 @@ impl ApplicationManager {
         })
     }
 
-    pub async fn handle_run_command(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) {
-        self.send_command(PluginCommand::One {
-            id: plugin_id.clone(),
-            data: OnePluginCommandData::RunCommand {
-                entrypoint_id: entrypoint_id.to_string(),
-            }
-        });
+    pub async fn run_entrypoint(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> anyhow::Result<()> {
+        // entrypoint only exists if plugin exists
+        let entrypoint = self.db_repository.get_entrypoint_by_id_option(&plugin_id.to_string(), &entrypoint_id.to_string())
+            .await?
+            .ok_or_else(|| anyhow!("Entrypoint with id '{}' doesn't exist in plugin '{}'", entrypoint_id.to_string(), plugin_id.to_string()))?;
+
+        if !self.is_plugin_enabled(&plugin_id).await? {
+            return Err(anyhow!("Plugin with id '{}' is disabled", plugin_id.to_string()))
+        }
+
+        if !self.enabled_state_cache.is_entrypoint_enabled(&plugin_id, &entrypoint_id).await? {
+            return Err(anyhow!("Entrypoint with id '{}' is disabled", entrypoint_id.to_string()))
+        }
+
+        match db_entrypoint_from_str(&entrypoint.entrypoint_type) {
+            DbPluginEntrypointType::Command => {
+                self.handle_run_command(plugin_id, entrypoint_id, false, false, false, false).await
+            }
+            _ => Err(anyhow!("Entrypoint with id '{}' is not a command", entrypoint_id.to_string()))
+        }
+    }
+
+    pub async fn handle_run_command(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool) -> anyhow::Result<()> {
+        let action_id = self.command_action_for_modifiers(&plugin_id, &entrypoint_id, modifier_shift, modifier_control, modifier_alt, modifier_meta)
+            .await?;
+
+        self.send_command_to_plugin(plugin_id.clone(), OnePluginCommandData::RunCommand {
+            entrypoint_id: entrypoint_id.to_string(),
+            action_id,
+        })?;
+
+        self.mark_entrypoint_frecency(plugin_id, entrypoint_id).await;
 
-        self.mark_entrypoint_frecency(plugin_id, entrypoint_id).await
+        Ok(())
     }
 
-    pub async fn handle_run_generated_command(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, action_index: Option<usize>) {
-        self.send_command(PluginCommand::One {
-            id: plugin_id.clone(),
-            data: OnePluginCommandData::RunGeneratedCommand {
-                entrypoint_id: entrypoint_id.to_string(),
-                action_index,
-            }
-        });
+    pub async fn handle_run_generated_command(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, action_index: Option<usize>) -> anyhow::Result<()> {
+        self.send_command_to_plugin(plugin_id.clone(), OnePluginCommandData::RunGeneratedCommand {
+            entrypoint_id: entrypoint_id.to_string(),
+            action_index,
+        })?;
 
-        self.mark_entrypoint_frecency(plugin_id, entrypoint_id).await
+        self.mark_entrypoint_frecency(plugin_id, entrypoint_id).await;
+
+        Ok(())
     }
 
     pub async fn handle_render_view(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> anyhow::Result<HashMap<String, PhysicalShortcut>> {
@@ -408,7 +918,28 @@ impl ApplicationManager {
         })
     }
 
-    pub fn handle_keyboard_event(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, origin: KeyboardEventOrigin, key: PhysicalKey, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool) {
+    pub async fn handle_keyboard_event(&self, plugin_id: PluginId, entrypoint_id: EntrypointId, origin: KeyboardEventOrigin, key: PhysicalKey, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool) -> anyhow::Result<bool> {
+        // in plugin view, entrypoint captures only the shortcuts of actions it declared in manifest,
+        // everything else is left to launcher navigation.
+        // main view events are for generated entrypoints whose actions are only known at runtime
+        if let KeyboardEventOrigin::PluginView = origin {
+            let shortcut = PhysicalShortcut {
+                physical_key: key.clone(),
+                modifier_shift,
+                modifier_control,
+                modifier_alt,
+                modifier_meta,
+            };
+
+            let action_shortcuts = self.action_shortcuts(plugin_id.clone(), entrypoint_id.clone())
+                .await?;
+
+            if !is_shortcut_captured(&action_shortcuts, &shortcut) {
+                tracing::debug!("keyboard event is not captured by entrypoint {:?} of plugin {:?}, not forwarding", entrypoint_id, plugin_id);
+                return Ok(false)
+            }
+        }
+
         self.send_command(PluginCommand::One {
             id: plugin_id,
             data: OnePluginCommandData::HandleKeyboardEvent {
@@ -420,21 +951,17 @@ impl ApplicationManager {
                 modifier_alt,
                 modifier_meta,
             }
-        })
+        });
+
+        Ok(true)
     }
 
     pub fn request_search_index_reload(&self, plugin_id: PluginId) {
-        self.send_command(PluginCommand::One {
-            id: plugin_id,
-            data: OnePluginCommandData::ReloadSearchIndex
-        })
+        self.search_index_reload_coalescer.request(plugin_id, SearchIndexUpdateKind::Reload)
     }
 
     pub fn request_search_index_refresh(&self, plugin_id: PluginId) {
-        self.send_command(PluginCommand::One {
-            id: plugin_id,
-            data: OnePluginCommandData::RefreshSearchIndex
-        })
+        self.search_index_reload_coalescer.request(plugin_id, SearchIndexUpdateKind::Refresh)
     }
 
     pub fn handle_open(&self, href: String) {
@@ -476,15 +1003,39 @@ impl ApplicationManager {
             .expect("failed to execute settings process"); // this can fail in dev if binary was replaced by frontend compilation
     }
 
-    async fn reload_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
-        tracing::info!(target = "plugin", "Reloading plugin with id: {:?}", plugin_id);
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
+    pub fn force_stop_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        if !self.run_status_holder.is_plugin_running(&plugin_id) {
+            return Err(anyhow!("Plugin with id '{}' is not running", plugin_id.to_string()))
+        }
+
+        tracing::warn!(target = "plugin", "Forcefully stopping plugin");
+
+        self.log_buffer.push(&plugin_id, PluginLogLevel::Error, "Plugin was forcefully stopped, it will not be started again until its state is changed in settings");
+
+        self.run_status_holder.force_stop_plugin(&plugin_id);
+
+        // same as when plugin is disabled, entrypoints of not running plugin cannot be launched from search
+        self.search_index.remove_for_plugin(plugin_id)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
+    pub async fn reload_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
+        tracing::info!(target = "plugin", "Reloading plugin");
+
+        // plugin is reloaded after it was saved to db, which could change enabled state
+        self.enabled_state_cache.invalidate_plugin(&plugin_id).await;
+
+        self.log_buffer.push(&plugin_id, PluginLogLevel::Info, "Reloading plugin");
 
         let running = self.run_status_holder.is_plugin_running(&plugin_id);
         if running {
             self.stop_plugin(plugin_id.clone()).await;
         }
 
-        if self.is_plugin_enabled(&plugin_id).await? {
+        if should_plugin_run(&self.db_repository, &self.run_status_holder, &plugin_id).await? {
             self.start_plugin(plugin_id).await?;
         }
 
@@ -492,7 +1043,7 @@ impl ApplicationManager {
     }
 
     async fn is_plugin_enabled(&self, plugin_id: &PluginId) -> anyhow::Result<bool> {
-        self.db_repository.is_plugin_enabled(&plugin_id.to_string())
+        self.enabled_state_cache.is_plugin_enabled(plugin_id)
             .await
     }
 
@@ -500,14 +1051,34 @@ impl ApplicationManager {
         self.db_repository.action_shortcuts(&plugin_id.to_string(), &entrypoint_id.to_string()).await
     }
 
+    async fn command_action_for_modifiers(&self, plugin_id: &PluginId, entrypoint_id: &EntrypointId, modifier_shift: bool, modifier_control: bool, modifier_alt: bool, modifier_meta: bool) -> anyhow::Result<Option<String>> {
+        let Some(kind) = action_kind_for_modifiers(modifier_control, modifier_alt, modifier_meta) else {
+            if modifier_shift || modifier_control || modifier_alt || modifier_meta {
+                tracing::debug!("no action kind matches modifiers for entrypoint {:?}, running command itself", entrypoint_id);
+            }
+            return Ok(None)
+        };
+
+        let entrypoint = self.db_repository.get_entrypoint_by_id(&plugin_id.to_string(), &entrypoint_id.to_string())
+            .await?;
+
+        Ok(action_id_of_kind(entrypoint.actions, kind))
+    }
+
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
     async fn start_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
-        tracing::info!(target = "plugin", "Starting plugin with id: {:?}", plugin_id);
+        tracing::info!(target = "plugin", "Starting plugin");
+
+        self.log_buffer.push(&plugin_id, PluginLogLevel::Info, "Starting plugin");
 
         let plugin_id_str = plugin_id.to_string();
 
         let plugin = self.db_repository.get_plugin_by_id(&plugin_id_str)
             .await?;
 
+        // app could have been updated since plugin was installed
+        PluginLoader::check_app_version(plugin.min_app_version, plugin.max_app_version)?;
+
         let entrypoint_names = self.db_repository.get_entrypoints_by_plugin_id(&plugin_id_str)
             .await?
             .into_iter()
@@ -519,6 +1090,10 @@ impl ApplicationManager {
 
         let receiver = self.command_broadcaster.subscribe();
 
+        let locale_config = self.config_reader.read_locale_config();
+
+        let js = PluginLoader::read_plugin_code(plugin.code)?;
+
         let clipboard_permissions = plugin.permissions
             .clipboard
             .into_iter()
@@ -542,7 +1117,7 @@ impl ApplicationManager {
             uuid: plugin.uuid,
             name: plugin.name,
             entrypoint_names,
-            code: PluginCode { js: plugin.code.js },
+            code: PluginCode { js },
             inline_view_entrypoint_id,
             permissions: PluginPermissions {
                 environment: plugin.permissions.environment,
@@ -557,14 +1132,21 @@ impl ApplicationManager {
                 },
                 system: plugin.permissions.system,
                 clipboard: clipboard_permissions,
-                main_search_bar: main_search_bar_permissions
+                main_search_bar: main_search_bar_permissions,
+                high_resolution_time: plugin.permissions.high_resolution_time,
+                list_entrypoints: plugin.permissions.list_entrypoints,
+                ffi: plugin.permissions.ffi,
             },
             command_receiver: receiver,
             db_repository: self.db_repository.clone(),
             search_index: self.search_index.clone(),
             icon_cache: self.icon_cache.clone(),
             frontend_api: self.frontend_api.clone(),
-            dirs: self.dirs.clone()
+            dirs: self.dirs.clone(),
+            log_buffer: self.log_buffer.clone(),
+            audit_log: self.audit_log.clone(),
+            enabled_state_cache: self.enabled_state_cache.clone(),
+            locale: locale_config.locale,
         };
 
         self.start_plugin_runtime(data);
@@ -572,8 +1154,11 @@ impl ApplicationManager {
         Ok(())
     }
 
+    #[tracing::instrument(target = "plugin", skip_all, fields(plugin_id = plugin_id.to_string()))]
     async fn stop_plugin(&self, plugin_id: PluginId) {
-        tracing::info!(target = "plugin", "Stopping plugin with id: {:?}", plugin_id);
+        tracing::info!(target = "plugin", "Stopping plugin");
+
+        self.log_buffer.push(&plugin_id, PluginLogLevel::Info, "Stopping plugin");
 
         self.run_status_holder.stop_plugin(&plugin_id)
     }
@@ -588,20 +1173,66 @@ impl ApplicationManager {
         });
     }
 
+    fn send_command_to_plugin(&self, plugin_id: PluginId, data: OnePluginCommandData) -> anyhow::Result<()> {
+        send_command_to_plugin(&self.run_status_holder, &self.command_broadcaster, plugin_id, data)
+    }
+
     fn send_command(&self, command: PluginCommand) {
-        // it is possible to have 0 plugins
-        let _ = self.command_broadcaster.send(command);
+        broadcast_command(&self.command_broadcaster, command)
+    }
+
+    // data buffered in memory is written before process exits
+    pub async fn shutdown(&self) {
+        if let Err(err) = self.db_repository.flush_frecency().await {
+            tracing::warn!("error occurred when writing frecency to db on shutdown {:?}", err)
+        }
     }
 
     async fn mark_entrypoint_frecency(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) {
-        let result = self.db_repository.mark_entrypoint_frecency(&plugin_id.to_string(), &entrypoint_id.to_string())
-            .await;
+        self.db_repository.mark_entrypoint_frecency(&plugin_id.to_string(), &entrypoint_id.to_string());
+
+        self.request_search_index_refresh(plugin_id);
+    }
 
-        if let Err(err) = &result {
-            tracing::warn!(target = "rpc", "error occurred when marking entrypoint frecency {:?}", err)
+    // applications are listed by entrypoint generator of bundled plugin, so regenerating its entrypoints picks up changes
+    pub fn refresh_applications(&self) {
+        let plugin_id = PluginLoader::bundled_plugin_id("gauntlet");
+
+        if self.run_status_holder.is_plugin_running(&plugin_id) {
+            self.request_search_index_reload(plugin_id);
+        } else {
+            tracing::debug!("bundled plugin is not running, skipping applications refresh");
         }
+    }
 
-        self.request_search_index_refresh(plugin_id);
+    pub async fn forget_entrypoint_frecency(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> anyhow::Result<()> {
+        tracing::info!(target = "plugin", "Forgetting usage history of entrypoint id: {:?}, plugin id: {:?}", entrypoint_id, plugin_id);
+
+        self.db_repository.remove_entrypoint_frecency(&plugin_id.to_string(), &entrypoint_id.to_string())
+            .await?;
+
+        if self.run_status_holder.is_plugin_running(&plugin_id) {
+            self.request_search_index_refresh(plugin_id);
+        }
+
+        Ok(())
+    }
+
+    pub async fn clear_all_frecency(&self) -> anyhow::Result<()> {
+        tracing::info!(target = "plugin", "Forgetting usage history of all entrypoints");
+
+        self.db_repository.remove_all_frecency()
+            .await?;
+
+        for plugin in self.db_repository.list_plugins().await? {
+            let plugin_id = PluginId::from_string(plugin.id);
+
+            if self.run_status_holder.is_plugin_running(&plugin_id) {
+                self.request_search_index_refresh(plugin_id);
+            }
+        }
+
+        Ok(())
     }
 
     pub async fn inline_view_shortcuts(&self) -> anyhow::Result<HashMap<PluginId, HashMap<String, PhysicalShortcut>>> {
@@ -615,6 +1246,108 @@ impl ApplicationManager {
     }
 }
 
+async fn settings_plugins(db_repository: &DataDbRepository) -> anyhow::Result<Vec<SettingsPlugin>> {
+    let result = db_repository
+        .list_plugins_and_entrypoints()
+        .await?
+        .into_iter()
+        .map(|(plugin, entrypoints)| settings_plugin_from_db(plugin, entrypoints))
+        .collect();
+
+    Ok(result)
+}
+
+// only requested plugin is read from db, unlike settings_plugins
+async fn settings_plugin(db_repository: &DataDbRepository, plugin_id: &PluginId) -> anyhow::Result<SettingsPlugin> {
+    let plugin_id = plugin_id.to_string();
+
+    let plugin = db_repository.get_plugin_by_id(&plugin_id)
+        .await?;
+
+    let entrypoints = db_repository.get_entrypoints_by_plugin_id(&plugin_id)
+        .await?;
+
+    Ok(settings_plugin_from_db(plugin, entrypoints))
+}
+
+static APPLIED_TIMEZONE: OnceCell<Option<String>> = OnceCell::new();
+
+// time zone used by v8 is process-wide and is read from TZ variable once,
+// so it has to be set at process start, before any other thread is spawned
+pub fn apply_timezone(dirs: &Dirs) {
+    let timezone = read_timezone_config(dirs);
+
+    if let Some(timezone) = &timezone {
+        tracing::info!("Using time zone from config: {}", timezone);
+
+        std::env::set_var("TZ", timezone);
+    }
+
+    let _ = APPLIED_TIMEZONE.set(timezone);
+}
+
+fn warn_if_timezone_changed(config_reader: &ConfigReader) {
+    let timezone = config_reader.read_locale_config().timezone;
+
+    if let Some(applied_timezone) = APPLIED_TIMEZONE.get() {
+        if applied_timezone != &timezone {
+            tracing::warn!("Time zone in config was changed, it will be applied after application restart");
+        }
+    }
+}
+
+fn settings_plugin_from_db(plugin: DbReadPlugin, entrypoints: Vec<DbReadPluginEntrypoint>) -> SettingsPlugin {
+    let entrypoints = entrypoints
+        .into_iter()
+        .map(|entrypoint| {
+            let entrypoint = settings_entrypoint_from_db(entrypoint);
+
+            (entrypoint.entrypoint_id.clone(), entrypoint)
+        })
+        .collect();
+
+    SettingsPlugin {
+        plugin_id: PluginId::from_string(plugin.id),
+        plugin_name: plugin.name,
+        plugin_description: plugin.description,
+        enabled: plugin.enabled,
+        entrypoints,
+        preferences: plugin.preferences.into_iter()
+            .map(|(key, value)| {
+                let preference = plugin_preference_from_db(&key, value);
+                (key, preference)
+            })
+            .collect(),
+        preferences_user_data: plugin.preferences_user_data.into_iter()
+            .map(|(key, value)| (key, plugin_preference_user_data_from_db(value)))
+            .collect(),
+    }
+}
+
+entrypoint: DbReadPluginEntrypoint) -> SettingsEntrypoint {
+    SettingsEntrypoint {
+        enabled: entrypoint.enabled,
+        entrypoint_id: EntrypointId::from_string(entrypoint.id),
+        entrypoint_name: entrypoint.name,
+        entrypoint_description: entrypoint.description,
+        entrypoint_type: match db_entrypoint_from_str(&entrypoint.entrypoint_type) {
+            DbPluginEntrypointType::Command => SettingsEntrypointType::Command,
+            DbPluginEntrypointType::View => SettingsEntrypointType::View,
+            DbPluginEntrypointType::InlineView => SettingsEntrypointType::InlineView,
+            DbPluginEntrypointType::CommandGenerator => SettingsEntrypointType::CommandGenerator,
+        },
+        preferences: entrypoint.preferences.into_iter()
+            .map(|(key, value)| {
+                let preference = plugin_preference_from_db(&key, value);
+                (key, preference)
+            })
+            .collect(),
+        preferences_user_data: entrypoint.preferences_user_data.into_iter()
+            .map(|(key, value)| (key, plugin_preference_user_data_from_db(value)))
+            .collect(),
+    }
+}
+
 fn plugin_preference_from_db(id: &str, value: DbPluginPreference) -> PluginPreference {
     match value {
         DbPluginPreference::Number { name, default, description } => {
@@ -679,6 +1412,130 @@ fn plugin_preference_from_db(id: &str, value: DbPluginPreference) -> PluginPrefe
     }
 }
 
+// runtime of the plugin holds its receiver for as long as the plugin is running,
+// so the command is delivered only if the plugin is running
+async fn gather_metrics(
+    db_repository: &DataDbRepository,
+    run_status_holder: &RunStatusHolder,
+    search_latency_percentile: impl Fn(f64) -> Option<Duration>,
+) -> anyhow::Result<GauntletMetrics> {
+    Ok(GauntletMetrics {
+        installed_plugins: db_repository.count_plugins().await?,
+        enabled_plugins: db_repository.count_enabled_plugins().await?,
+        running_plugins: run_status_holder.running_plugins_count(),
+        total_entrypoints: db_repository.count_entrypoints().await?,
+        search_latency_p50: search_latency_percentile(0.5),
+        search_latency_p95: search_latency_percentile(0.95),
+        search_latency_p99: search_latency_percentile(0.99),
+    })
+}
+
+// suspended plugins stay stopped regardless of persisted enabled state
+// platform main modifier selects main action, alt selects alternative one, no modifiers run command itself
+fn action_kind_for_modifiers(modifier_control: bool, modifier_alt: bool, modifier_meta: bool) -> Option<DbPluginActionShortcutKind> {
+    let main_modifier = if cfg!(target_os = "macos") {
+        modifier_meta && !modifier_control
+    } else {
+        modifier_control && !modifier_meta
+    };
+
+    match (main_modifier, modifier_alt) {
+        (true, false) => Some(DbPluginActionShortcutKind::Main),
+        (false, true) if !modifier_control && !modifier_meta => Some(DbPluginActionShortcutKind::Alternative),
+        _ => None
+    }
+}
+
+fn action_id_of_kind(actions: Vec<DbPluginAction>, kind: DbPluginActionShortcutKind) -> Option<String> {
+    actions.into_iter()
+        .find(|action| action.kind == kind)
+        .map(|action| action.id)
+}
+
+fn is_shortcut_captured(action_shortcuts: &HashMap<String, PhysicalShortcut>, shortcut: &PhysicalShortcut) -> bool {
+    action_shortcuts.values()
+        .any(|action_shortcut| action_shortcut == shortcut)
+}
+
+async fn should_plugin_run(db_repository: &DataDbRepository, run_status_holder: &RunStatusHolder, plugin_id: &PluginId) -> anyhow::Result<bool> {
+    let enabled = db_repository.is_plugin_enabled(&plugin_id.to_string())
+        .await?;
+
+    Ok(enabled && !run_status_holder.is_plugin_suspended(plugin_id) && !run_status_holder.is_plugin_errored(plugin_id))
+}
+
+async fn send_fallback_command(
+    db_repository: &DataDbRepository,
+    run_status_holder: &RunStatusHolder,
+    command_broadcaster: &tokio::sync::broadcast::Sender<PluginCommand>,
+    text: &str
+) -> anyhow::Result<Option<(PluginId, EntrypointId)>> {
+    let Some(fallback) = db_repository.get_fallback_entrypoint().await? else {
+        return Ok(None)
+    };
+
+    let plugin_id = PluginId::from_string(fallback.plugin_id);
+    let entrypoint_id = EntrypointId::from_string(fallback.entrypoint_id);
+
+    send_command_to_plugin(run_status_holder, command_broadcaster, plugin_id.clone(), OnePluginCommandData::RunFallbackCommand {
+        entrypoint_id: entrypoint_id.to_string(),
+        text: text.to_owned(),
+    })?;
+
+    Ok(Some((plugin_id, entrypoint_id)))
+}
+
+fn send_command_to_plugin(
+    run_status_holder: &RunStatusHolder,
+    command_broadcaster: &tokio::sync::broadcast::Sender<PluginCommand>,
+    plugin_id: PluginId,
+    data: OnePluginCommandData
+) -> anyhow::Result<()> {
+    if !run_status_holder.is_plugin_running(&plugin_id) {
+        return Err(anyhow!("Plugin with id '{}' is not running, command was not delivered", plugin_id.to_string()))
+    }
+
+    broadcast_command(command_broadcaster, PluginCommand::One {
+        id: plugin_id,
+        data,
+    });
+
+    Ok(())
+}
+
+fn broadcast_command(command_broadcaster: &tokio::sync::broadcast::Sender<PluginCommand>, command: PluginCommand) {
+    // it is possible to have 0 plugins running, e.g. all of them are disabled
+    if let Err(err) = command_broadcaster.send(command) {
+        tracing::debug!(target = "plugin", "no running plugins to receive command, dropping: {:?}", err.0);
+    }
+}
+
+// failure of one plugin doesn't stop update of the rest
+async fn update_plugins<F, Fut>(plugin_ids: Vec<PluginId>, update_plugin: F) -> Vec<(PluginId, UpdateResult)>
+where
+    F: Fn(PluginId) -> Fut,
+    Fut: Future<Output=anyhow::Result<()>>,
+{
+    let mut results = vec![];
+
+    for plugin_id in plugin_ids {
+        let result = match update_plugin(plugin_id.clone()).await {
+            Ok(()) => UpdateResult::Updated,
+            Err(err) => {
+                tracing::warn!(target = "plugin", "Unable to update plugin {:?}: {:?}", plugin_id, err);
+
+                UpdateResult::Failed {
+                    message: format!("{:#}", err)
+                }
+            }
+        };
+
+        results.push((plugin_id, result));
+    }
+
+    results
+}
+
 fn plugin_preference_user_data_to_db(value: PluginPreferenceUserData) -> DbPluginPreferenceUserData {
     match value {
         PluginPreferenceUserData::Number { value } => DbPluginPreferenceUserData::Number { value },
@@ -703,3 +1560,326 @@ fn plugin_preference_user_data_from_db(value: DbPluginPreferenceUserData) -> Plu
     }
 }
 
+fn plugin_boosts_from_db(plugin_boosts: HashMap<String, f64>) -> HashMap<PluginId, f64> {
+    plugin_boosts.into_iter()
+        .map(|(plugin_id, factor)| (PluginId::from_string(plugin_id), factor))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plugins::data_db_repository::{db_plugin_type_to_str, DbCode, DbWritePlugin, DbWritePluginEntrypoint};
+
+    use super::*;
+
+    #[test]
+    fn command_without_running_plugins_is_dropped() {
+        let (command_broadcaster, command_receiver) = tokio::sync::broadcast::channel::<PluginCommand>(100);
+
+        drop(command_receiver);
+
+        broadcast_command(&command_broadcaster, PluginCommand::All {
+            data: AllPluginCommandData::OpenInlineView {
+                text: "text".to_string(),
+            }
+        });
+    }
+
+    #[test]
+    fn command_to_stopped_plugin_is_not_delivered() {
+        let run_status_holder = RunStatusHolder::new();
+        let (command_broadcaster, mut command_receiver) = tokio::sync::broadcast::channel::<PluginCommand>(100);
+
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        let result = send_command_to_plugin(&run_status_holder, &command_broadcaster, plugin_id, OnePluginCommandData::CloseView);
+
+        assert!(result.is_err());
+        assert!(command_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn command_to_running_plugin_is_delivered() {
+        let run_status_holder = RunStatusHolder::new();
+        let (command_broadcaster, mut command_receiver) = tokio::sync::broadcast::channel::<PluginCommand>(100);
+
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        let _run_status_guard = run_status_holder.start_block(plugin_id.clone());
+
+        send_command_to_plugin(&run_status_holder, &command_broadcaster, plugin_id.clone(), OnePluginCommandData::CloseView).unwrap();
+
+        assert!(matches!(command_receiver.try_recv(), Ok(PluginCommand::One { id, data: OnePluginCommandData::CloseView }) if id == plugin_id));
+    }
+
+    #[tokio::test]
+    async fn failed_update_does_not_stop_other_updates() {
+        let failing_plugin_id = PluginId::from_string("failing-plugin");
+        let updated_plugin_id = PluginId::from_string("updated-plugin");
+
+        let results = update_plugins(vec![failing_plugin_id.clone(), updated_plugin_id.clone()], |plugin_id| {
+            let failing_plugin_id = failing_plugin_id.clone();
+
+            async move {
+                if plugin_id == failing_plugin_id {
+                    Err(anyhow!("download failed"))
+                } else {
+                    Ok(())
+                }
+            }
+        }).await;
+
+        assert_eq!(results.len(), 2);
+        assert!(matches!(&results[0], (plugin_id, UpdateResult::Failed { message }) if *plugin_id == failing_plugin_id && message == "download failed"));
+        assert!(matches!(&results[1], (plugin_id, UpdateResult::Updated) if *plugin_id == updated_plugin_id));
+    }
+
+    fn test_plugin(plugin_id: &str, enabled: bool, entrypoint_ids: &[&str]) -> DbWritePlugin {
+        let entrypoints = entrypoint_ids.iter()
+            .map(|entrypoint_id| DbWritePluginEntrypoint {
+                id: entrypoint_id.to_string(),
+                name: entrypoint_id.to_string(),
+                description: String::new(),
+                icon_path: None,
+                entrypoint_type: "command".to_string(),
+                preferences: HashMap::new(),
+                actions: vec![],
+            })
+            .collect();
+
+        DbWritePlugin {
+            id: plugin_id.to_string(),
+            name: "Test Plugin".to_string(),
+            description: String::new(),
+            enabled,
+            code: DbCode { js: HashMap::new(), linked_path: None },
+            entrypoints,
+            asset_data: vec![],
+            permissions: serde_json::from_str("{}").unwrap(),
+            plugin_type: db_plugin_type_to_str(DbPluginType::Normal).to_string(),
+            preferences: HashMap::new(),
+            version: None,
+            min_app_version: None,
+            max_app_version: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn suspended_plugin_stays_enabled_but_does_not_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let run_status_holder = RunStatusHolder::new();
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        repository.save_plugin(test_plugin(&plugin_id.to_string(), true, &[])).await.unwrap();
+
+        let _run_status_guard = run_status_holder.start_block(plugin_id.clone());
+
+        run_status_holder.suspend_plugin(plugin_id.clone());
+        run_status_holder.stop_plugin(&plugin_id);
+
+        assert!(!run_status_holder.is_plugin_running(&plugin_id));
+        assert!(repository.is_plugin_enabled(&plugin_id.to_string()).await.unwrap());
+        assert!(!should_plugin_run(&repository, &run_status_holder, &plugin_id).await.unwrap());
+
+        assert!(run_status_holder.resume_plugin(&plugin_id));
+        assert!(should_plugin_run(&repository, &run_status_holder, &plugin_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn force_stopped_plugin_is_not_started_again_until_errored_state_is_cleared() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let run_status_holder = RunStatusHolder::new();
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        repository.save_plugin(test_plugin(&plugin_id.to_string(), true, &[])).await.unwrap();
+
+        let _run_status_guard = run_status_holder.start_block(plugin_id.clone());
+
+        run_status_holder.force_stop_plugin(&plugin_id);
+
+        assert!(!run_status_holder.is_plugin_running(&plugin_id));
+        assert!(!should_plugin_run(&repository, &run_status_holder, &plugin_id).await.unwrap());
+
+        assert!(run_status_holder.clear_plugin_errored(&plugin_id));
+        assert!(should_plugin_run(&repository, &run_status_holder, &plugin_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn metrics_count_installed_plugins() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let run_status_holder = RunStatusHolder::new();
+
+        repository.save_plugin(test_plugin("first-plugin", true, &["first", "second"])).await.unwrap();
+        repository.save_plugin(test_plugin("second-plugin", true, &["first"])).await.unwrap();
+        repository.save_plugin(test_plugin("disabled-plugin", false, &["first", "second", "third"])).await.unwrap();
+
+        let _run_status_guard = run_status_holder.start_block(PluginId::from_string("first-plugin"));
+
+        let metrics = gather_metrics(&repository, &run_status_holder, |_| None).await.unwrap();
+
+        assert_eq!(metrics.installed_plugins, 3);
+        assert_eq!(metrics.enabled_plugins, 2);
+        assert_eq!(metrics.running_plugins, 1);
+        assert_eq!(metrics.total_entrypoints, 6);
+        assert_eq!(metrics.search_latency_p50, None);
+    }
+
+    #[tokio::test]
+    async fn single_plugin_settings_match_settings_of_all_plugins() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+
+        repository.save_plugin(test_plugin("first-plugin", true, &["first"])).await.unwrap();
+        repository.save_plugin(test_plugin("second-plugin", false, &["first", "second"])).await.unwrap();
+
+        let plugin_id = PluginId::from_string("second-plugin");
+
+        let expected = settings_plugins(&repository)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|plugin| plugin.plugin_id == plugin_id)
+            .unwrap();
+
+        let actual = settings_plugin(&repository, &plugin_id).await.unwrap();
+
+        assert_eq!(actual.plugin_id, expected.plugin_id);
+        assert_eq!(actual.plugin_name, expected.plugin_name);
+        assert_eq!(actual.plugin_description, expected.plugin_description);
+        assert_eq!(actual.enabled, expected.enabled);
+
+        let mut actual_entrypoints = actual.entrypoints.values()
+            .map(|entrypoint| (entrypoint.entrypoint_id.to_string(), entrypoint.entrypoint_name.clone(), entrypoint.enabled))
+            .collect::<Vec<_>>();
+        let mut expected_entrypoints = expected.entrypoints.values()
+            .map(|entrypoint| (entrypoint.entrypoint_id.to_string(), entrypoint.entrypoint_name.clone(), entrypoint.enabled))
+            .collect::<Vec<_>>();
+
+        actual_entrypoints.sort();
+        expected_entrypoints.sort();
+
+        assert_eq!(actual_entrypoints, expected_entrypoints);
+    }
+
+    #[test]
+    fn only_declared_action_shortcuts_are_captured() {
+        let shortcut = |physical_key, modifier_control| PhysicalShortcut {
+            physical_key,
+            modifier_shift: false,
+            modifier_control,
+            modifier_alt: false,
+            modifier_meta: false,
+        };
+
+        let action_shortcuts = HashMap::from([
+            ("copy".to_string(), shortcut(PhysicalKey::KeyC, true)),
+        ]);
+
+        assert!(is_shortcut_captured(&action_shortcuts, &shortcut(PhysicalKey::KeyC, true)));
+        assert!(!is_shortcut_captured(&action_shortcuts, &shortcut(PhysicalKey::KeyC, false)));
+        assert!(!is_shortcut_captured(&action_shortcuts, &shortcut(PhysicalKey::ArrowDown, false)));
+    }
+
+    #[test]
+    fn alternative_modifier_selects_alternative_action() {
+        let action = |id: &str, kind| DbPluginAction {
+            id: id.to_string(),
+            description: id.to_string(),
+            key: "Enter".to_string(),
+            kind,
+        };
+
+        let actions = vec![
+            action("open", DbPluginActionShortcutKind::Main),
+            action("copy", DbPluginActionShortcutKind::Alternative),
+        ];
+
+        let kind = action_kind_for_modifiers(false, true, false).unwrap();
+
+        assert_eq!(action_id_of_kind(actions, kind), Some("copy".to_string()));
+        assert_eq!(action_kind_for_modifiers(false, false, false), None);
+    }
+
+    #[tokio::test]
+    async fn configured_fallback_is_run_with_query_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let run_status_holder = RunStatusHolder::new();
+        let (command_broadcaster, mut command_receiver) = tokio::sync::broadcast::channel::<PluginCommand>(100);
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        repository.save_plugin(test_plugin(&plugin_id.to_string(), true, &["web-search"])).await.unwrap();
+
+        assert_eq!(send_fallback_command(&repository, &run_status_holder, &command_broadcaster, "rust").await.unwrap(), None);
+
+        repository.set_fallback_entrypoint(Some(DbSettingsFallbackEntrypointData {
+            plugin_id: plugin_id.to_string(),
+            entrypoint_id: "web-search".to_string(),
+        })).await.unwrap();
+
+        let _run_status_guard = run_status_holder.start_block(plugin_id.clone());
+
+        let fallback = send_fallback_command(&repository, &run_status_holder, &command_broadcaster, "rust").await.unwrap();
+
+        assert_eq!(fallback, Some((plugin_id.clone(), EntrypointId::from_string("web-search"))));
+        assert!(matches!(
+            command_receiver.try_recv(),
+            Ok(PluginCommand::One { id, data: OnePluginCommandData::RunFallbackCommand { entrypoint_id, text } }) if id == plugin_id && entrypoint_id == "web-search" && text == "rust"
+        ));
+    }
+
+    #[tokio::test]
+    async fn enabled_state_cache_follows_toggles() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let plugin_id = PluginId::from_string("test-plugin");
+        let entrypoint_id = EntrypointId::from_string("command");
+
+        repository.save_plugin(test_plugin(&plugin_id.to_string(), true, &["command"])).await.unwrap();
+
+        let cache = EnabledStateCache::new(repository.clone());
+
+        assert!(cache.is_plugin_enabled(&plugin_id).await.unwrap());
+        assert!(cache.is_entrypoint_enabled(&plugin_id, &entrypoint_id).await.unwrap());
+
+        cache.set_plugin_enabled(&plugin_id, false).await.unwrap();
+        cache.set_entrypoint_enabled(&plugin_id, &entrypoint_id, false).await.unwrap();
+
+        assert!(!cache.is_plugin_enabled(&plugin_id).await.unwrap());
+        assert!(!cache.is_entrypoint_enabled(&plugin_id, &entrypoint_id).await.unwrap());
+        assert!(!repository.is_plugin_enabled(&plugin_id.to_string()).await.unwrap());
+        assert!(!repository.is_plugin_entrypoint_enabled(&plugin_id.to_string(), &entrypoint_id.to_string()).await.unwrap());
+
+        // read racing with toggle doesn't put old value back into cache
+        let (_, toggle_result) = tokio::join!(
+            cache.is_plugin_enabled(&plugin_id),
+            cache.set_plugin_enabled(&plugin_id, true)
+        );
+        toggle_result.unwrap();
+
+        assert!(cache.is_plugin_enabled(&plugin_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn enabled_state_written_directly_to_db_is_read_after_invalidation() {
+        let dir = tempfile::tempdir().unwrap();
+        let repository = DataDbRepository::open(&dir.path().join("data.db")).await.unwrap();
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        repository.save_plugin(test_plugin(&plugin_id.to_string(), true, &[])).await.unwrap();
+
+        let cache = EnabledStateCache::new(repository.clone());
+
+        assert!(cache.is_plugin_enabled(&plugin_id).await.unwrap());
+
+        // e.g. import or reinstall
+        repository.set_plugin_enabled(&plugin_id.to_string(), false).await.unwrap();
+
+        cache.invalidate_plugin(&plugin_id).await;
+
+        assert!(!cache.is_plugin_enabled(&plugin_id).await.unwrap());
+    }
+}
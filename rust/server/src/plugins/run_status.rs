@@ -1,26 +1,70 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use deno_core::v8;
 use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
 
-use common::model::PluginId;
+use common::model::{HealthStatus, PluginId, PluginResourceUsage};
+
+use crate::plugins::js::{AllPluginCommandData, PluginCommand};
+
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+// a few missed pings are tolerated, e.g. plugin is busy with long-running command
+const UNRESPONSIVE_AFTER: Duration = Duration::from_secs(20);
+
+// plugin runtime answers ping when it picks up the next event, so stuck runtime stops answering
+pub fn start_health_pings(command_broadcaster: tokio::sync::broadcast::Sender<PluginCommand>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PING_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            // it is possible to have 0 plugins running, e.g. all of them are disabled
+            let _ = command_broadcaster.send(PluginCommand::All {
+                data: AllPluginCommandData::Ping
+            });
+        }
+    });
+}
 
 pub struct RunStatusHolder {
-    running_plugins: Arc<Mutex<HashMap<PluginId, CancellationToken>>>
+    running_plugins: Arc<Mutex<HashMap<PluginId, CancellationToken>>>,
+    last_responses: Arc<Mutex<HashMap<PluginId, Instant>>>,
+    resource_usage: Arc<Mutex<HashMap<PluginId, PluginResourceUsage>>>,
+    isolate_handles: Arc<Mutex<HashMap<PluginId, v8::IsolateHandle>>>,
+    // plugins stopped until restart of the server, without changing their persisted enabled state
+    suspended_plugins: Mutex<HashSet<PluginId>>,
+    // forcefully stopped plugins, not started again until user explicitly changes their state
+    errored_plugins: Mutex<HashSet<PluginId>>,
 }
 
 impl RunStatusHolder {
     pub fn new() -> Self {
         Self {
-            running_plugins: Arc::new(Mutex::new(HashMap::new()))
+            running_plugins: Arc::new(Mutex::new(HashMap::new())),
+            last_responses: Arc::new(Mutex::new(HashMap::new())),
+            resource_usage: Arc::new(Mutex::new(HashMap::new())),
+            isolate_handles: Arc::new(Mutex::new(HashMap::new())),
+            suspended_plugins: Mutex::new(HashSet::new()),
+            errored_plugins: Mutex::new(HashSet::new()),
         }
     }
 
     pub fn start_block(&self, plugin_id: PluginId) -> RunStatusGuard {
         let mut running_plugins = self.running_plugins.lock().expect("lock is poisoned");
         running_plugins.insert(plugin_id.clone(), CancellationToken::new());
+
+        // startup counts as response, so plugin isn't reported as unresponsive before first ping
+        let mut last_responses = self.last_responses.lock().expect("lock is poisoned");
+        last_responses.insert(plugin_id.clone(), Instant::now());
+
         RunStatusGuard {
             running_plugins: self.running_plugins.clone(),
+            last_responses: self.last_responses.clone(),
+            resource_usage: self.resource_usage.clone(),
+            isolate_handles: self.isolate_handles.clone(),
             id: plugin_id,
         }
     }
@@ -30,19 +74,103 @@ impl RunStatusHolder {
         running_plugins.contains_key(plugin_id)
     }
 
+    pub fn running_plugins_count(&self) -> usize {
+        let running_plugins = self.running_plugins.lock().expect("lock is poisoned");
+        running_plugins.len()
+    }
+
     pub fn stop_plugin(&self, plugin_id: &PluginId) {
         let mut running_plugins = self.running_plugins.lock().expect("lock is poisoned");
 
-        running_plugins
-            .remove(plugin_id)
-            .expect("value should always exist for specified id")
-            .cancel()
+        self.last_responses.lock()
+            .expect("lock is poisoned")
+            .remove(plugin_id);
+
+        self.resource_usage.lock()
+            .expect("lock is poisoned")
+            .remove(plugin_id);
+
+        self.isolate_handles.lock()
+            .expect("lock is poisoned")
+            .remove(plugin_id);
+
+        // runtime could have already stopped on its own, e.g. after it was forcefully stopped
+        if let Some(cancellation_token) = running_plugins.remove(plugin_id) {
+            cancellation_token.cancel()
+        }
+    }
+
+    // cooperative stop is handled only when runtime gets to await something,
+    // so js which is stuck in a loop is terminated first
+    pub fn force_stop_plugin(&self, plugin_id: &PluginId) {
+        let isolate_handle = self.isolate_handles.lock()
+            .expect("lock is poisoned")
+            .remove(plugin_id);
+
+        if let Some(isolate_handle) = isolate_handle {
+            isolate_handle.terminate_execution();
+        }
+
+        self.errored_plugins.lock()
+            .expect("lock is poisoned")
+            .insert(plugin_id.clone());
+
+        self.stop_plugin(plugin_id)
+    }
+
+    // runtime which stopped unexpectedly stays in running plugins and is reported as unresponsive
+    pub fn plugin_health(&self, plugin_id: &PluginId) -> HealthStatus {
+        if !self.is_plugin_running(plugin_id) {
+            return HealthStatus::NotRunning
+        }
+
+        let last_responses = self.last_responses.lock().expect("lock is poisoned");
+
+        match last_responses.get(plugin_id) {
+            Some(last_response) if last_response.elapsed() < UNRESPONSIVE_AFTER => HealthStatus::Responsive,
+            _ => HealthStatus::Unresponsive
+        }
+    }
+
+    // None until the first sample is taken
+    pub fn plugin_resource_usage(&self, plugin_id: &PluginId) -> Option<PluginResourceUsage> {
+        let resource_usage = self.resource_usage.lock().expect("lock is poisoned");
+
+        resource_usage.get(plugin_id).cloned()
+    }
+
+    pub fn suspend_plugin(&self, plugin_id: PluginId) {
+        let mut suspended_plugins = self.suspended_plugins.lock().expect("lock is poisoned");
+        suspended_plugins.insert(plugin_id);
+    }
+
+    pub fn resume_plugin(&self, plugin_id: &PluginId) -> bool {
+        let mut suspended_plugins = self.suspended_plugins.lock().expect("lock is poisoned");
+        suspended_plugins.remove(plugin_id)
+    }
+
+    pub fn is_plugin_suspended(&self, plugin_id: &PluginId) -> bool {
+        let suspended_plugins = self.suspended_plugins.lock().expect("lock is poisoned");
+        suspended_plugins.contains(plugin_id)
+    }
+
+    pub fn clear_plugin_errored(&self, plugin_id: &PluginId) -> bool {
+        let mut errored_plugins = self.errored_plugins.lock().expect("lock is poisoned");
+        errored_plugins.remove(plugin_id)
+    }
+
+    pub fn is_plugin_errored(&self, plugin_id: &PluginId) -> bool {
+        let errored_plugins = self.errored_plugins.lock().expect("lock is poisoned");
+        errored_plugins.contains(plugin_id)
     }
 }
 
 pub struct RunStatusGuard {
     id: PluginId,
     running_plugins: Arc<Mutex<HashMap<PluginId, CancellationToken>>>,
+    last_responses: Arc<Mutex<HashMap<PluginId, Instant>>>,
+    resource_usage: Arc<Mutex<HashMap<PluginId, PluginResourceUsage>>>,
+    isolate_handles: Arc<Mutex<HashMap<PluginId, v8::IsolateHandle>>>,
 }
 
 impl RunStatusGuard {
@@ -55,4 +183,164 @@ impl RunStatusGuard {
             .clone()
             .cancelled_owned()
     }
+
+    pub fn health_reporter(&self) -> PluginHealthReporter {
+        PluginHealthReporter {
+            id: self.id.clone(),
+            last_responses: self.last_responses.clone(),
+        }
+    }
+
+    pub fn resource_usage_reporter(&self) -> PluginResourceUsageReporter {
+        PluginResourceUsageReporter {
+            id: self.id.clone(),
+            running_plugins: self.running_plugins.clone(),
+            resource_usage: self.resource_usage.clone(),
+            isolate_handles: self.isolate_handles.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PluginHealthReporter {
+    id: PluginId,
+    last_responses: Arc<Mutex<HashMap<PluginId, Instant>>>,
+}
+
+impl PluginHealthReporter {
+    pub fn responded(&self) {
+        let mut last_responses = self.last_responses.lock().expect("lock is poisoned");
+
+        // entry is removed when plugin is stopped, don't bring it back
+        if let Some(last_response) = last_responses.get_mut(&self.id) {
+            *last_response = Instant::now();
+        }
+    }
+}
+
+pub struct PluginResourceUsageReporter {
+    id: PluginId,
+    running_plugins: Arc<Mutex<HashMap<PluginId, CancellationToken>>>,
+    resource_usage: Arc<Mutex<HashMap<PluginId, PluginResourceUsage>>>,
+    isolate_handles: Arc<Mutex<HashMap<PluginId, v8::IsolateHandle>>>,
+}
+
+impl PluginResourceUsageReporter {
+    pub fn register_isolate(&self, isolate_handle: v8::IsolateHandle) {
+        let running_plugins = self.running_plugins.lock().expect("lock is poisoned");
+
+        if !running_plugins.contains_key(&self.id) {
+            return
+        }
+
+        let mut isolate_handles = self.isolate_handles.lock().expect("lock is poisoned");
+        isolate_handles.insert(self.id.clone(), isolate_handle);
+    }
+
+    pub fn report(&self, usage: PluginResourceUsage) {
+        let running_plugins = self.running_plugins.lock().expect("lock is poisoned");
+
+        // sample taken right before stop shouldn't outlive the plugin
+        if !running_plugins.contains_key(&self.id) {
+            return
+        }
+
+        let mut resource_usage = self.resource_usage.lock().expect("lock is poisoned");
+        resource_usage.insert(self.id.clone(), usage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_which_stopped_answering_pings_is_unresponsive() {
+        let run_status_holder = RunStatusHolder::new();
+        let responsive_plugin_id = PluginId::from_string("responsive-plugin");
+        let unresponsive_plugin_id = PluginId::from_string("unresponsive-plugin");
+
+        let responsive_guard = run_status_holder.start_block(responsive_plugin_id.clone());
+        let _unresponsive_guard = run_status_holder.start_block(unresponsive_plugin_id.clone());
+
+        // last answer of stuck plugin was long ago
+        let long_ago = Instant::now().checked_sub(UNRESPONSIVE_AFTER + Duration::from_secs(1)).unwrap();
+        run_status_holder.last_responses.lock().unwrap().insert(unresponsive_plugin_id.clone(), long_ago);
+        run_status_holder.last_responses.lock().unwrap().insert(responsive_plugin_id.clone(), long_ago);
+
+        responsive_guard.health_reporter().responded();
+
+        assert_eq!(run_status_holder.plugin_health(&responsive_plugin_id), HealthStatus::Responsive);
+        assert_eq!(run_status_holder.plugin_health(&unresponsive_plugin_id), HealthStatus::Unresponsive);
+        assert_eq!(run_status_holder.plugin_health(&PluginId::from_string("stopped-plugin")), HealthStatus::NotRunning);
+    }
+
+    #[test]
+    fn resource_usage_is_reported_only_while_plugin_is_running() {
+        let run_status_holder = RunStatusHolder::new();
+        let plugin_id = PluginId::from_string("plugin");
+
+        let guard = run_status_holder.start_block(plugin_id.clone());
+        let reporter = guard.resource_usage_reporter();
+
+        assert!(run_status_holder.plugin_resource_usage(&plugin_id).is_none());
+
+        reporter.report(PluginResourceUsage { heap_used_bytes: 10, heap_total_bytes: 20 });
+
+        let usage = run_status_holder.plugin_resource_usage(&plugin_id).unwrap();
+        assert_eq!(usage.heap_used_bytes, 10);
+        assert_eq!(usage.heap_total_bytes, 20);
+
+        run_status_holder.stop_plugin(&plugin_id);
+
+        // late sample from runtime which is shutting down
+        reporter.report(PluginResourceUsage { heap_used_bytes: 10, heap_total_bytes: 20 });
+
+        assert!(run_status_holder.plugin_resource_usage(&plugin_id).is_none());
+    }
+
+    #[test]
+    fn force_stop_terminates_plugin_which_ignores_cooperative_stop() {
+        let run_status_holder = RunStatusHolder::new();
+        let plugin_id = PluginId::from_string("stuck-plugin");
+
+        let guard = run_status_holder.start_block(plugin_id.clone());
+        let reporter = guard.resource_usage_reporter();
+
+        let (isolate_handle_sender, isolate_handle_receiver) = std::sync::mpsc::channel();
+
+        let runtime_thread = std::thread::spawn(move || {
+            let mut runtime = deno_core::JsRuntime::new(deno_core::RuntimeOptions::default());
+
+            isolate_handle_sender.send(runtime.v8_isolate().thread_safe_handle()).unwrap();
+
+            // never yields, so cancellation of the run status guard alone is never noticed
+            runtime.execute_script_static("stuck.js", "while (true) {}").is_err()
+        });
+
+        reporter.register_isolate(isolate_handle_receiver.recv().unwrap());
+
+        // let the script enter the loop
+        std::thread::sleep(Duration::from_millis(100));
+
+        run_status_holder.force_stop_plugin(&plugin_id);
+
+        assert!(runtime_thread.join().unwrap());
+        assert!(!run_status_holder.is_plugin_running(&plugin_id));
+        assert!(run_status_holder.is_plugin_errored(&plugin_id));
+    }
+
+    #[test]
+    fn stopping_plugin_which_is_not_running_is_ignored() {
+        let run_status_holder = RunStatusHolder::new();
+        let plugin_id = PluginId::from_string("plugin");
+
+        let _guard = run_status_holder.start_block(plugin_id.clone());
+
+        run_status_holder.stop_plugin(&plugin_id);
+        run_status_holder.stop_plugin(&plugin_id);
+        run_status_holder.force_stop_plugin(&PluginId::from_string("never-started-plugin"));
+
+        assert!(!run_status_holder.is_plugin_running(&plugin_id));
+    }
 }
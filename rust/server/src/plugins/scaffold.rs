@@ -0,0 +1,166 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+
+use crate::plugins::loader::PluginLoader;
+
+const JS_PACKAGE_VERSION: &str = "^0.10.0";
+
+pub fn scaffold_plugin(parent_dir: &Path, plugin_name: &str) -> anyhow::Result<PathBuf> {
+    let package_name = plugin_name_to_package_name(plugin_name)?;
+
+    let plugin_dir = parent_dir.join(&package_name);
+
+    if plugin_dir.exists() {
+        return Err(anyhow!("Directory {:?} already exists", plugin_dir))
+    }
+
+    std::fs::create_dir_all(plugin_dir.join("src"))
+        .context("Unable to create plugin directory")?;
+
+    std::fs::write(plugin_dir.join("gauntlet.toml"), manifest_content(plugin_name))?;
+    std::fs::write(plugin_dir.join("package.json"), package_json_content(&package_name))?;
+    std::fs::write(plugin_dir.join("tsconfig.json"), TSCONFIG_CONTENT)?;
+    std::fs::write(plugin_dir.join(".gitignore"), GITIGNORE_CONTENT)?;
+    std::fs::write(plugin_dir.join("src").join("main-view.tsx"), VIEW_CONTENT)?;
+
+    PluginLoader::validate_manifest_in_dir(&plugin_dir)
+        .context("Generated plugin manifest is invalid")?;
+
+    Ok(plugin_dir)
+}
+
+fn plugin_name_to_package_name(plugin_name: &str) -> anyhow::Result<String> {
+    let package_name = plugin_name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|char| if char.is_ascii_alphanumeric() { char } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if package_name.is_empty() {
+        return Err(anyhow!("Plugin name '{}' should contain at least one ascii alphanumeric character", plugin_name))
+    }
+
+    Ok(package_name)
+}
+
+fn manifest_content(plugin_name: &str) -> String {
+    let plugin_name = plugin_name.trim().replace('\'', "");
+
+    format!(r#"[gauntlet]
+name = '{plugin_name}'
+description = """
+Plugin description
+"""
+
+[[entrypoint]]
+id = 'main-view'
+name = 'Main View'
+path = 'src/main-view.tsx'
+type = 'view'
+description = 'Sample view entrypoint'
+
+[[supported_system]]
+os = 'linux'
+
+[[supported_system]]
+os = 'macos'
+
+[[supported_system]]
+os = 'windows'
+"#)
+}
+
+fn package_json_content(package_name: &str) -> String {
+    format!(r#"{{
+  "name": "{package_name}",
+  "private": true,
+  "scripts": {{
+    "build": "gauntlet build",
+    "dev": "gauntlet dev"
+  }},
+  "dependencies": {{
+    "@project-gauntlet/api": "{JS_PACKAGE_VERSION}"
+  }},
+  "devDependencies": {{
+    "@types/react": "^18.2.14",
+    "@project-gauntlet/deno": "{JS_PACKAGE_VERSION}",
+    "@project-gauntlet/tools": "*",
+    "typescript": "^5.3.3"
+  }}
+}}
+"#)
+}
+
+const TSCONFIG_CONTENT: &str = r#"{
+  "compilerOptions": {
+    "strict": true,
+    "module": "ES2022",
+    "esModuleInterop": true,
+    "target": "ES2022",
+    "moduleResolution": "bundler",
+    "jsx": "react-jsx",
+    "types": ["@project-gauntlet/deno"]
+  },
+  "lib": ["ES2020"]
+}
+"#;
+
+const GITIGNORE_CONTENT: &str = r#"dist
+node_modules
+"#;
+
+const VIEW_CONTENT: &str = r#"import { ReactElement } from "react";
+import { Detail } from "@project-gauntlet/api/components";
+
+export default function MainView(): ReactElement {
+    return (
+        <Detail>
+            <Detail.Content>
+                <Detail.Content.Paragraph>
+                    Hello from your new plugin!
+                </Detail.Content.Paragraph>
+            </Detail.Content>
+        </Detail>
+    )
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaffolded_plugin_passes_validation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let plugin_dir = scaffold_plugin(temp_dir.path(), "My Plugin").unwrap();
+
+        assert_eq!(plugin_dir, temp_dir.path().join("my-plugin"));
+        assert!(plugin_dir.join("package.json").is_file());
+        assert!(plugin_dir.join("tsconfig.json").is_file());
+        assert!(plugin_dir.join("src").join("main-view.tsx").is_file());
+
+        PluginLoader::validate_manifest_in_dir(&plugin_dir).unwrap();
+    }
+
+    #[test]
+    fn scaffold_does_not_overwrite_existing_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        scaffold_plugin(temp_dir.path(), "plugin").unwrap();
+
+        assert!(scaffold_plugin(temp_dir.path(), "plugin").is_err());
+    }
+
+    #[test]
+    fn package_name_is_derived_from_plugin_name() {
+        assert_eq!(plugin_name_to_package_name(" My  Great_Plugin! ").unwrap(), "my-great-plugin");
+        assert!(plugin_name_to_package_name("!!!").is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use common::model::PluginId;
+
+use crate::plugins::js::{OnePluginCommandData, PluginCommand};
+
+const COALESCE_DURATION: Duration = Duration::from_millis(300);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchIndexUpdateKind {
+    // re-read all search items from plugin
+    Reload,
+    // only update frecency and other server-side data
+    Refresh,
+}
+
+// multiple requests for the same plugin within short window are collapsed into one
+pub struct SearchIndexReloadCoalescer {
+    command_broadcaster: tokio::sync::broadcast::Sender<PluginCommand>,
+    pending: Arc<Mutex<HashMap<PluginId, SearchIndexUpdateKind>>>,
+}
+
+impl SearchIndexReloadCoalescer {
+    pub fn new(command_broadcaster: tokio::sync::broadcast::Sender<PluginCommand>) -> Self {
+        Self {
+            command_broadcaster,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn request(&self, plugin_id: PluginId, kind: SearchIndexUpdateKind) {
+        {
+            let mut pending = self.pending.lock().expect("lock is poisoned");
+
+            match pending.get_mut(&plugin_id) {
+                Some(pending_kind) => {
+                    // reload includes everything refresh does
+                    if kind == SearchIndexUpdateKind::Reload {
+                        *pending_kind = SearchIndexUpdateKind::Reload
+                    }
+
+                    tracing::trace!(target = "plugin", "search index update for plugin {:?} is already pending, coalescing", plugin_id);
+
+                    return;
+                }
+                None => {
+                    pending.insert(plugin_id.clone(), kind);
+                }
+            }
+        }
+
+        let pending = self.pending.clone();
+        let command_broadcaster = self.command_broadcaster.clone();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(COALESCE_DURATION).await;
+
+            let kind = pending.lock()
+                .expect("lock is poisoned")
+                .remove(&plugin_id);
+
+            let data = match kind {
+                Some(SearchIndexUpdateKind::Reload) => OnePluginCommandData::ReloadSearchIndex,
+                Some(SearchIndexUpdateKind::Refresh) => OnePluginCommandData::RefreshSearchIndex,
+                None => return,
+            };
+
+            let command = PluginCommand::One {
+                id: plugin_id,
+                data,
+            };
+
+            // it is possible to have 0 plugins running, e.g. all of them are disabled
+            if let Err(err) = command_broadcaster.send(command) {
+                tracing::debug!(target = "plugin", "no running plugins to receive command, dropping: {:?}", err.0);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn burst_of_requests_is_coalesced_into_one_reload() {
+        let (command_broadcaster, mut command_receiver) = tokio::sync::broadcast::channel(100);
+        let coalescer = SearchIndexReloadCoalescer::new(command_broadcaster);
+        let plugin_id = PluginId::from_string("test-plugin");
+
+        coalescer.request(plugin_id.clone(), SearchIndexUpdateKind::Refresh);
+        coalescer.request(plugin_id.clone(), SearchIndexUpdateKind::Reload);
+        coalescer.request(plugin_id.clone(), SearchIndexUpdateKind::Refresh);
+
+        let command = tokio::time::timeout(COALESCE_DURATION * 4, command_receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(command, PluginCommand::One { id, data: OnePluginCommandData::ReloadSearchIndex } if id == plugin_id));
+
+        tokio::time::sleep(COALESCE_DURATION * 2).await;
+
+        assert!(command_receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn requests_for_different_plugins_are_not_coalesced() {
+        let (command_broadcaster, mut command_receiver) = tokio::sync::broadcast::channel(100);
+        let coalescer = SearchIndexReloadCoalescer::new(command_broadcaster);
+
+        coalescer.request(PluginId::from_string("first-plugin"), SearchIndexUpdateKind::Reload);
+        coalescer.request(PluginId::from_string("second-plugin"), SearchIndexUpdateKind::Refresh);
+
+        for _ in 0..2 {
+            tokio::time::timeout(COALESCE_DURATION * 4, command_receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        }
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
 use common::{settings_env_data_to_string, SettingsEnvData};
-use common::model::{DownloadStatus, EntrypointId, PluginId, PluginPreferenceUserData, SettingsPlugin, UiPropertyValue, SearchResult, UiWidgetId, PhysicalKey, PhysicalShortcut, LocalSaveData};
+use common::model::{DownloadStatus, EntrypointId, PluginId, PluginPreferenceUserData, SettingsPlugin, UiPropertyValue, SearchResult, UiWidgetId, PhysicalKey, PhysicalShortcut, LocalSaveData, PluginLogLine, PluginUpdate, UpdateResult};
 use common::rpc::backend_server::BackendServer;
 
 use crate::plugins::ApplicationManager;
@@ -34,6 +34,28 @@ impl BackendServer for BackendServerImpl {
         Ok(())
     }
 
+    async fn run_entrypoint(&self, plugin_id: PluginId, entrypoint_id: EntrypointId) -> anyhow::Result<()> {
+        let result = self.application_manager.run_entrypoint(plugin_id, entrypoint_id)
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'run_entrypoint' request {:?}", err)
+        }
+
+        result
+    }
+
+    async fn search(&self, text: String) -> anyhow::Result<Vec<SearchResult>> {
+        let result = self.application_manager.search(&text, false)
+            .map(|(results, _suggestion)| results);
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'search' request {:?}", err)
+        }
+
+        result
+    }
+
     async fn plugins(&self) -> anyhow::Result<Vec<SettingsPlugin>> {
         let result = self.application_manager.plugins()
             .await;
@@ -111,6 +133,28 @@ impl BackendServer for BackendServerImpl {
         Ok(self.application_manager.download_status())
     }
 
+    async fn updates_available(&self) -> anyhow::Result<Vec<PluginUpdate>> {
+        let result = self.application_manager.updates_available()
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'updates_available' request {:?}", err)
+        }
+
+        result
+    }
+
+    async fn update_all_plugins(&self) -> anyhow::Result<Vec<(PluginId, UpdateResult)>> {
+        let result = self.application_manager.update_all_plugins()
+            .await;
+
+        if let Err(err) = &result {
+            tracing::warn!(target = "rpc", "error occurred when handling 'update_all_plugins' request {:?}", err)
+        }
+
+        result
+    }
+
     async fn remove_plugin(&self, plugin_id: PluginId) -> anyhow::Result<()> {
         let result = self.application_manager.remove_plugin(plugin_id)
             .await;
@@ -122,8 +166,12 @@ impl BackendServer for BackendServerImpl {
         Ok(())
     }
 
-    async fn save_local_plugin(&self, path: String) -> anyhow::Result<LocalSaveData> {
-        let result = self.application_manager.save_local_plugin(&path)
+    async fn plugin_logs(&self, plugin_id: PluginId, limit: usize) -> anyhow::Result<Vec<PluginLogLine>> {
+        Ok(self.application_manager.plugin_logs(plugin_id, limit))
+    }
+
+    async fn save_local_plugin(&self, path: String, linked: bool) -> anyhow::Result<LocalSaveData> {
+        let result = self.application_manager.save_local_plugin(&path, linked)
             .await?;
 
         Ok(result)
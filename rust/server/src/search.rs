@@ -1,12 +1,16 @@
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use anyhow::Context;
+use thiserror::Error;
 use tantivy::{doc, Index, IndexReader, ReloadPolicy, Searcher};
-use tantivy::collector::TopDocs;
-use tantivy::query::{AllQuery, BooleanQuery, FuzzyTermQuery, Query, RegexQuery, TermQuery};
+use tantivy::collector::{DocSetCollector, TopDocs};
+use tantivy::query::{AllQuery, BooleanQuery, BoostQuery, FuzzyTermQuery, Query, RegexQuery, TermQuery};
 use tantivy::schema::*;
-use tantivy::tokenizer::TokenizerManager;
-use common::model::{EntrypointId, PhysicalShortcut, PluginId, SearchResult, SearchResultEntrypointAction, SearchResultEntrypointType};
+use tantivy::tokenizer::{AsciiFoldingFilter, LowerCaser, RemoveLongFilter, SimpleTokenizer, TextAnalyzer, TokenizerManager};
+use common::model::{EntrypointId, PhysicalShortcut, PluginId, SearchResult, SearchResultEntrypointAction, SearchResultEntrypointType, SearchResultKind};
 use common::rpc::frontend_api::FrontendApi;
 
 #[derive(Clone)]
@@ -18,14 +22,36 @@ pub struct SearchIndex {
 
     entrypoint_data: Arc<Mutex<HashMap<PluginId, HashMap<EntrypointId, EntrypointData>>>>,
 
+    // ranking multipliers of plugins favorited by user, plugins not in the map have multiplier of 1.0
+    plugin_boosts: Arc<Mutex<HashMap<PluginId, f64>>>,
+
+    // latencies of the most recent searches, oldest first
+    search_latencies: Arc<Mutex<VecDeque<Duration>>>,
+
     entrypoint_name: Field,
     entrypoint_id: Field,
+    entrypoint_type: Field,
+    entrypoint_icon_path: Field,
+    entrypoint_description: Field,
     plugin_name: Field,
     plugin_id: Field,
 }
 
+// increment when schema changes, index is recreated if version on disk is different
+const SEARCH_INDEX_SCHEMA_VERSION: u32 = 3;
+const SEARCH_INDEX_SCHEMA_VERSION_FILE: &str = "schema_version";
+
+const SEARCH_LATENCY_WINDOW: usize = 200;
+
+// suggestions further than that from the query are not useful
+const SUGGESTION_MAX_DISTANCE: usize = 3;
+
+// same as tantivy's default tokenizer but with diacritics folded, e.g. "Café" is indexed as "cafe"
+const SEARCH_TOKENIZER: &str = "gauntlet";
+
 struct EntrypointData {
     entrypoint_type: SearchResultEntrypointType,
+    subtitle: Option<String>,
     icon_path: Option<String>,
     frecency: f64,
     actions: Vec<EntrypointActionData>,
@@ -40,6 +66,7 @@ struct EntrypointActionData {
 pub struct SearchIndexItem {
     pub entrypoint_type: SearchResultEntrypointType,
     pub entrypoint_name: String,
+    pub entrypoint_description: String,
     pub entrypoint_id: EntrypointId,
     pub entrypoint_icon_path: Option<String>,
     pub entrypoint_frecency: f64,
@@ -53,25 +80,101 @@ pub struct SearchIndexItemAction {
 }
 
 impl SearchIndex {
-    pub fn create_index(frontend_api: FrontendApi) -> tantivy::Result<Self> {
-        let schema = {
-            let mut schema_builder = Schema::builder();
+    // index is persisted so search results are available right after launch,
+    // before plugins are started and have reloaded their search items
+    pub fn open_or_create(frontend_api: FrontendApi, index_dir: &Path) -> anyhow::Result<Self> {
+        let schema = Self::schema();
+
+        let version_file = index_dir.join(SEARCH_INDEX_SCHEMA_VERSION_FILE);
+
+        let version_matches = std::fs::read_to_string(&version_file)
+            .ok()
+            .and_then(|version| version.trim().parse::<u32>().ok())
+            .map(|version| version == SEARCH_INDEX_SCHEMA_VERSION)
+            .unwrap_or(false);
+
+        let existing_index = if version_matches {
+            // index is only a cache of data in db, so it is recreated instead of failing to start
+            match Index::open_in_dir(index_dir) {
+                Ok(index) => Some(index),
+                Err(err) => {
+                    tracing::warn!("Unable to open search index, recreating search index: {:?}", err);
+                    None
+                }
+            }
+        } else {
+            tracing::info!("Search index schema version changed, recreating search index");
+            None
+        };
 
-            schema_builder.add_text_field("entrypoint_name", TEXT | STORED);
-            schema_builder.add_text_field("entrypoint_id", STRING | STORED);
-            schema_builder.add_text_field("plugin_name", TEXT | STORED);
-            schema_builder.add_text_field("plugin_id", STRING | STORED);
+        let index = if let Some(index) = existing_index {
+            index
+        } else {
+            if index_dir.exists() {
+                std::fs::remove_dir_all(index_dir)
+                    .context("Unable to remove outdated search index")?;
+            }
+
+            std::fs::create_dir_all(index_dir)
+                .context("Unable to create search index directory")?;
+
+            let index = Index::create_in_dir(index_dir, schema)
+                .context("Unable to create search index")?;
 
-            schema_builder.build()
+            std::fs::write(&version_file, SEARCH_INDEX_SCHEMA_VERSION.to_string())
+                .context("Unable to write search index schema version")?;
+
+            index
         };
 
+        Ok(Self::from_index(frontend_api, index)?)
+    }
+
+    fn schema() -> Schema {
+        let mut schema_builder = Schema::builder();
+
+        let text_indexing = TextFieldIndexing::default()
+            .set_tokenizer(SEARCH_TOKENIZER)
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+
+        let text_options = TextOptions::default()
+            .set_indexing_options(text_indexing.clone())
+            .set_stored();
+
+        let not_stored_text_options = TextOptions::default()
+            .set_indexing_options(text_indexing);
+
+        schema_builder.add_text_field("entrypoint_name", text_options.clone());
+        schema_builder.add_text_field("entrypoint_id", STRING | STORED);
+        schema_builder.add_text_field("entrypoint_type", STRING | STORED);
+        schema_builder.add_text_field("entrypoint_icon_path", STORED);
+        schema_builder.add_text_field("entrypoint_description", not_stored_text_options);
+        schema_builder.add_text_field("plugin_name", text_options);
+        schema_builder.add_text_field("plugin_id", STRING | STORED);
+
+        schema_builder.build()
+    }
+
+    fn from_index(frontend_api: FrontendApi, index: Index) -> tantivy::Result<Self> {
+        // tokenizers are not persisted, so they need to be registered every time index is opened
+        let tokenizer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(RemoveLongFilter::limit(40))
+            .filter(LowerCaser)
+            .filter(AsciiFoldingFilter)
+            .build();
+
+        index.tokenizers().register(SEARCH_TOKENIZER, tokenizer);
+
+        let schema = index.schema();
+
         let entrypoint_name = schema.get_field("entrypoint_name").expect("entrypoint_name field should exist");
         let entrypoint_id = schema.get_field("entrypoint_id").expect("entrypoint_id field should exist");
+        let entrypoint_type = schema.get_field("entrypoint_type").expect("entrypoint_type field should exist");
+        let entrypoint_icon_path = schema.get_field("entrypoint_icon_path").expect("entrypoint_icon_path field should exist");
+        let entrypoint_description = schema.get_field("entrypoint_description").expect("entrypoint_description field should exist");
         let plugin_name = schema.get_field("plugin_name").expect("plugin_name field should exist");
         let plugin_id = schema.get_field("plugin_id").expect("plugin_id field should exist");
 
-        let index = Index::create_in_ram(schema.clone());
-
         let index_reader = index
             .reader_builder()
             .reload_policy(ReloadPolicy::OnCommit)
@@ -83,13 +186,37 @@ impl SearchIndex {
             index_reader,
             index_writer_mutex: Arc::new(Mutex::new(())),
             entrypoint_data: Arc::new(Mutex::new(HashMap::new())),
+            plugin_boosts: Arc::new(Mutex::new(HashMap::new())),
+            search_latencies: Arc::new(Mutex::new(VecDeque::with_capacity(SEARCH_LATENCY_WINDOW))),
             entrypoint_name,
             entrypoint_id,
+            entrypoint_type,
+            entrypoint_icon_path,
+            entrypoint_description,
             plugin_name,
             plugin_id,
         })
     }
 
+    // runs in background so startup is not blocked
+    pub fn warmup(&self) -> tokio::task::JoinHandle<()> {
+        let search_index = self.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let start = std::time::Instant::now();
+
+            if let Err(err) = search_index.index_reader.reload() {
+                tracing::warn!("error occurred when reloading search index reader during warmup {:?}", err);
+                return;
+            }
+
+            match search_index.search("") {
+                Ok(_) => tracing::debug!("search index warmup finished in {:?}", start.elapsed()),
+                Err(err) => tracing::warn!("error occurred when warming up search index {:?}", err),
+            }
+        })
+    }
+
     pub fn remove_for_plugin(&self, plugin_id: PluginId) -> tantivy::Result<()> {
         // writer panics if another writer exists
         let _guard = self.index_writer_mutex.lock().expect("lock is poisoned");
@@ -124,6 +251,9 @@ impl SearchIndex {
             index_writer.add_document(doc!(
                 self.entrypoint_name => search_item.entrypoint_name.clone(),
                 self.entrypoint_id => search_item.entrypoint_id.to_string(),
+                self.entrypoint_type => entrypoint_type_to_str(&search_item.entrypoint_type),
+                self.entrypoint_icon_path => search_item.entrypoint_icon_path.clone().unwrap_or_default(),
+                self.entrypoint_description => search_item.entrypoint_description.clone(),
                 self.plugin_name => plugin_name.clone(),
                 self.plugin_id => plugin_id.to_string(),
             ))?;
@@ -142,6 +272,7 @@ impl SearchIndex {
 
                 let data = EntrypointData {
                     entrypoint_type: item.entrypoint_type.clone(),
+                    subtitle: Some(plugin_name.clone()),
                     icon_path: item.entrypoint_icon_path.clone(),
                     frecency: item.entrypoint_frecency,
                     actions,
@@ -171,19 +302,29 @@ impl SearchIndex {
     }
 
     pub fn search(&self, query: &str) -> anyhow::Result<Vec<SearchResult>> {
+        let start = Instant::now();
+
+        let (query, filters) = SearchFilters::parse(query)?;
+
         let searcher = self.index_reader.searcher();
 
         let query_parser = QueryParser::new(
             self.index.tokenizers().clone(),
             self.entrypoint_name,
             self.plugin_name,
+            self.entrypoint_description,
         );
 
-        let query = query_parser.create_query(query);
+        let query_terms = query_parser.tokenize(&query);
+
+        // without search text there is nothing to rank by text, so default list is ordered by frecency only
+        let empty_query = query.is_empty();
+
+        let query = query_parser.create_query(&query);
 
         let mut index = 0;
 
-        let fetch = std::iter::from_fn(|| -> Option<anyhow::Result<Vec<(SearchResult, f64)>>> {
+        let fetch = std::iter::from_fn(|| -> Option<anyhow::Result<Vec<Option<(SearchResult, f64)>>>> {
             let result = self.fetch(&query, TopDocs::with_limit(20).and_offset(index * 20), &searcher);
 
             index += 1;
@@ -204,20 +345,146 @@ impl SearchIndex {
 
         let result = fetch.collect::<Result<Vec<Vec<_>>, _>>()?;
 
+        let plugin_boosts = self.plugin_boosts.lock().expect("lock is poisoned");
+
+        // results matched only by description are shown after results matched by name
         let mut result = result.into_iter()
             .flatten()
+            .flatten()
+            .map(|(item, frecency)| {
+                let name_match = query_parser.contains_terms(&query_terms, &item.entrypoint_name)
+                    || query_parser.contains_terms(&query_terms, &item.plugin_name);
+
+                // offset so that boost also affects entrypoints which were never used
+                let score = match plugin_boosts.get(&item.plugin_id) {
+                    Some(boost) => (frecency + 1.0) * boost,
+                    None => frecency + 1.0,
+                };
+
+                (item, name_match, score)
+            })
             .collect::<Vec<_>>();
 
-        result.sort_by(|(_, score_a), (_, score_b)| score_b.partial_cmp(score_a).unwrap_or(Ordering::Less));
+        drop(plugin_boosts);
+
+        // ties are broken by name and ids, so results with equal score don't change order between searches
+        if empty_query {
+            result.sort_by(|(item_a, _, score_a), (item_b, _, score_b)| {
+                score_b.total_cmp(score_a)
+                    .then_with(|| search_result_tie_break(item_a, item_b))
+            });
+        } else {
+            result.sort_by(|(item_a, name_match_a, score_a), (item_b, name_match_b, score_b)| {
+                name_match_b.cmp(name_match_a)
+                    .then_with(|| score_b.total_cmp(score_a))
+                    .then_with(|| search_result_tie_break(item_a, item_b))
+            });
+        }
 
         let result = result.into_iter()
-            .map(|(item, _)| item)
+            .map(|(item, _, _)| item)
+            .filter(|item| filters.matches(item))
             .collect::<Vec<_>>();
 
+        self.record_search_latency(start.elapsed());
+
         Ok(result)
     }
 
-    fn fetch(&self, query: &dyn Query, collector: TopDocs, searcher: &Searcher) -> anyhow::Result<Vec<(SearchResult, f64)>> {
+    pub fn set_plugin_boosts(&self, plugin_boosts: HashMap<PluginId, f64>) {
+        *self.plugin_boosts.lock().expect("lock is poisoned") = plugin_boosts;
+    }
+
+    fn record_search_latency(&self, latency: Duration) {
+        let mut search_latencies = self.search_latencies.lock().expect("lock is poisoned");
+
+        if search_latencies.len() == SEARCH_LATENCY_WINDOW {
+            search_latencies.pop_front();
+        }
+
+        search_latencies.push_back(latency);
+    }
+
+    // percentile is in 0.0..=1.0 range, None if there were no searches yet
+    pub fn search_latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        let mut search_latencies = self.search_latencies.lock()
+            .expect("lock is poisoned")
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if search_latencies.is_empty() {
+            return None
+        }
+
+        search_latencies.sort();
+
+        let index = ((search_latencies.len() - 1) as f64 * percentile.clamp(0.0, 1.0)).round() as usize;
+
+        Some(search_latencies[index])
+    }
+
+    // closest entrypoint name to the query, used to show "did you mean" when query returns nothing
+    pub fn suggest(&self, text: &str) -> anyhow::Result<Option<String>> {
+        // query with invalid operators is reported as error by search, nothing to suggest
+        let Ok((text, _)) = SearchFilters::parse(text) else {
+            return Ok(None)
+        };
+
+        let query_parser = QueryParser::new(
+            self.index.tokenizers().clone(),
+            self.entrypoint_name,
+            self.plugin_name,
+            self.entrypoint_description,
+        );
+
+        let query = query_parser.tokenize(&text).join(" ");
+
+        if query.is_empty() {
+            return Ok(None)
+        }
+
+        // allow roughly one typo for every three characters
+        let max_distance = (query.chars().count() / 3).clamp(1, SUGGESTION_MAX_DISTANCE);
+
+        let searcher = self.index_reader.searcher();
+
+        let mut best: Option<(usize, String)> = None;
+
+        for doc_address in searcher.search(&AllQuery, &DocSetCollector)? {
+            let retrieved_doc = searcher.doc(doc_address)?;
+
+            let Some(entrypoint_name) = retrieved_doc.get_first(self.entrypoint_name).and_then(|value| value.as_text()) else {
+                continue
+            };
+
+            let name_tokens = query_parser.tokenize(entrypoint_name);
+
+            // query is compared with whole name and with every word of it separately
+            let distance = std::iter::once(name_tokens.join(" "))
+                .chain(name_tokens.into_iter())
+                .filter_map(|candidate| bounded_levenshtein(&query, &candidate, max_distance))
+                .min();
+
+            let Some(distance) = distance else {
+                continue
+            };
+
+            let is_better = match &best {
+                None => true,
+                Some((best_distance, best_name)) => distance < *best_distance || (distance == *best_distance && entrypoint_name < best_name.as_str()),
+            };
+
+            if is_better {
+                best = Some((distance, entrypoint_name.to_owned()));
+            }
+        }
+
+        Ok(best.map(|(_, name)| name))
+    }
+
+    // None is returned for documents that cannot be shown, so it doesn't affect pagination
+    fn fetch(&self, query: &dyn Query, collector: TopDocs, searcher: &Searcher) -> anyhow::Result<Vec<Option<(SearchResult, f64)>>> {
         let entrypoint_data = self.entrypoint_data.lock().expect("lock is poisoned");
 
         let get_str_field = |retrieved_doc: &Document, field: Field| -> String {
@@ -241,9 +508,34 @@ impl SearchIndex {
 
                 let entrypoint_data = entrypoint_data
                     .get(&plugin_id)
-                    .expect("Plugin should always exist in entrypoint data")
-                    .get(&entrypoint_id)
-                    .expect("Plugin should always exist in entrypoint data");
+                    .and_then(|data| data.get(&entrypoint_id));
+
+                // data is missing if index was loaded from disk and plugin hasn't reloaded its search items yet
+                let entrypoint_data = match entrypoint_data {
+                    Some(entrypoint_data) => entrypoint_data,
+                    None => {
+                        let entrypoint_type = get_str_field(&retrieved_doc, self.entrypoint_type);
+                        let icon_path = get_str_field(&retrieved_doc, self.entrypoint_icon_path);
+
+                        let Some(entrypoint_type) = entrypoint_type_from_str(&entrypoint_type) else {
+                            return None
+                        };
+
+                        let result_item = SearchResult {
+                            result_kind: entrypoint_type_to_result_kind(&entrypoint_type),
+                            entrypoint_type,
+                            subtitle: Some(plugin_name.clone()),
+                            entrypoint_name,
+                            entrypoint_id,
+                            entrypoint_icon: if icon_path.is_empty() { None } else { Some(icon_path) },
+                            plugin_name,
+                            plugin_id,
+                            entrypoint_actions: vec![],
+                        };
+
+                        return Some((result_item, 0.0))
+                    }
+                };
 
                 let entrypoint_actions = entrypoint_data.actions.iter()
                     .map(|data| SearchResultEntrypointAction {
@@ -252,8 +544,12 @@ impl SearchIndex {
                     })
                     .collect();
 
+                let result_kind = entrypoint_type_to_result_kind(&entrypoint_data.entrypoint_type);
+
                 let result_item = SearchResult {
                     entrypoint_type: entrypoint_data.entrypoint_type.clone(),
+                    subtitle: entrypoint_data.subtitle.clone(),
+                    result_kind,
                     entrypoint_name,
                     entrypoint_id,
                     entrypoint_icon: entrypoint_data.icon_path.clone(),
@@ -262,7 +558,7 @@ impl SearchIndex {
                     entrypoint_actions,
                 };
 
-                (result_item, entrypoint_data.frecency)
+                Some((result_item, entrypoint_data.frecency))
             })
             .collect::<Vec<_>>();
 
@@ -270,18 +566,163 @@ impl SearchIndex {
     }
 }
 
+// levenshtein distance which gives up as soon as it is known that distance is larger than max_distance
+fn bounded_levenshtein(left: &str, right: &str, max_distance: usize) -> Option<usize> {
+    let left = left.chars().collect::<Vec<_>>();
+    let right = right.chars().collect::<Vec<_>>();
+
+    if left.len().abs_diff(right.len()) > max_distance {
+        return None
+    }
+
+    let mut previous_row = (0..=right.len()).collect::<Vec<_>>();
+    let mut current_row = vec![0; right.len() + 1];
+
+    for (left_index, left_char) in left.iter().enumerate() {
+        current_row[0] = left_index + 1;
+
+        for (right_index, right_char) in right.iter().enumerate() {
+            let substitution_cost = if left_char == right_char { 0 } else { 1 };
+
+            current_row[right_index + 1] = (previous_row[right_index] + substitution_cost)
+                .min(previous_row[right_index + 1] + 1)
+                .min(current_row[right_index] + 1);
+        }
+
+        if current_row.iter().min().is_some_and(|min| *min > max_distance) {
+            return None
+        }
+
+        std::mem::swap(&mut previous_row, &mut current_row);
+    }
+
+    let distance = previous_row[right.len()];
+
+    if distance <= max_distance {
+        Some(distance)
+    } else {
+        None
+    }
+}
+
+fn search_result_tie_break(item_a: &SearchResult, item_b: &SearchResult) -> Ordering {
+    item_a.entrypoint_name.to_lowercase().cmp(&item_b.entrypoint_name.to_lowercase())
+        .then_with(|| item_a.plugin_id.to_string().cmp(&item_b.plugin_id.to_string()))
+        .then_with(|| item_a.entrypoint_id.to_string().cmp(&item_b.entrypoint_id.to_string()))
+}
+
+fn entrypoint_type_to_result_kind(entrypoint_type: &SearchResultEntrypointType) -> SearchResultKind {
+    match entrypoint_type {
+        SearchResultEntrypointType::Command | SearchResultEntrypointType::GeneratedCommand => SearchResultKind::Command,
+        SearchResultEntrypointType::View => SearchResultKind::View,
+    }
+}
+
+fn entrypoint_type_to_str(entrypoint_type: &SearchResultEntrypointType) -> &'static str {
+    match entrypoint_type {
+        SearchResultEntrypointType::Command => "command",
+        SearchResultEntrypointType::View => "view",
+        SearchResultEntrypointType::GeneratedCommand => "generated-command",
+    }
+}
+
+fn entrypoint_type_from_str(value: &str) -> Option<SearchResultEntrypointType> {
+    match value {
+        "command" => Some(SearchResultEntrypointType::Command),
+        "view" => Some(SearchResultEntrypointType::View),
+        "generated-command" => Some(SearchResultEntrypointType::GeneratedCommand),
+        _ => None,
+    }
+}
+
+// operators in form of "name:value", e.g. "type:command plugin:gauntlet foo"
+#[derive(Default)]
+struct SearchFilters {
+    plugin: Option<String>,
+    result_kind: Option<SearchResultKind>,
+}
+
+#[derive(Debug, Error)]
+pub enum SearchQueryError {
+    #[error("Unknown search operator \"{0}:\", supported operators are \"plugin:\" and \"type:\"")]
+    UnknownOperator(String),
+    #[error("Unknown type \"{0}\", supported types are \"command\" and \"view\"")]
+    UnknownType(String),
+}
+
+impl SearchFilters {
+    fn parse(query: &str) -> Result<(String, SearchFilters), SearchQueryError> {
+        let mut filters = SearchFilters::default();
+        let mut text = vec![];
+
+        for part in query.split_whitespace() {
+            let Some((name, value)) = part.split_once(':') else {
+                text.push(part);
+                continue
+            };
+
+            // only "word:" prefixes are operators, so text like "10:30" or urls is still searched as text
+            if name.is_empty() || !name.chars().all(|char| char.is_ascii_lowercase()) || value.starts_with('/') {
+                text.push(part);
+                continue
+            }
+
+            // operator which is still being typed doesn't filter anything yet
+            if value.is_empty() {
+                continue
+            }
+
+            // entrypoints don't have tags, so "tag:" is rejected the same way as any other unknown operator
+            match name {
+                "plugin" => filters.plugin = Some(value.to_lowercase()),
+                "type" => {
+                    filters.result_kind = match value {
+                        "command" => Some(SearchResultKind::Command),
+                        "view" => Some(SearchResultKind::View),
+                        _ => return Err(SearchQueryError::UnknownType(value.to_string())),
+                    }
+                }
+                _ => return Err(SearchQueryError::UnknownOperator(name.to_string())),
+            }
+        }
+
+        Ok((text.join(" "), filters))
+    }
+
+    fn matches(&self, item: &SearchResult) -> bool {
+        if let Some(plugin) = &self.plugin {
+            let matches_plugin = item.plugin_name.to_lowercase().contains(plugin)
+                || item.plugin_id.to_string().to_lowercase() == *plugin;
+
+            if !matches_plugin {
+                return false
+            }
+        }
+
+        if let Some(result_kind) = &self.result_kind {
+            if &item.result_kind != result_kind {
+                return false
+            }
+        }
+
+        true
+    }
+}
+
 struct QueryParser {
     tokenizer_manager: TokenizerManager,
     entrypoint_name: Field,
     plugin_name: Field,
+    entrypoint_description: Field,
 }
 
 impl QueryParser {
-    fn new(tokenizer_manager: TokenizerManager, entrypoint_name: Field, plugin_name: Field) -> Self {
+    fn new(tokenizer_manager: TokenizerManager, entrypoint_name: Field, plugin_name: Field, entrypoint_description: Field) -> Self {
         Self {
             tokenizer_manager,
             entrypoint_name,
             plugin_name,
+            entrypoint_description,
         }
     }
 
@@ -313,20 +754,30 @@ impl QueryParser {
 
         let entrypoint_name_terms = terms_fn(self.entrypoint_name);
         let plugin_name_terms = terms_fn(self.plugin_name);
+        let entrypoint_description_terms = terms_fn(self.entrypoint_description);
 
         Box::new(
             BooleanQuery::union(vec![
                 Box::new(entrypoint_name_terms),
                 Box::new(plugin_name_terms),
+                Box::new(BoostQuery::new(entrypoint_description_terms, 0.3)),
             ]),
         )
     }
 
+    // same semantics as "contains" query, every term should be a part of some token in text
+    fn contains_terms(&self, terms: &[String], text: &str) -> bool {
+        let text_tokens = self.tokenize(text);
+
+        terms.iter()
+            .all(|term| text_tokens.iter().any(|token| token.contains(term)))
+    }
+
     fn tokenize(&self, query: &str) -> Vec<String> {
         let mut text_analyzer = self
             .tokenizer_manager
-            .get("default")
-            .expect("default tokenizer should exist");
+            .get(SEARCH_TOKENIZER)
+            .expect("search tokenizer should be registered");
 
         let mut terms: Vec<String> = Vec::new();
         let mut token_stream = text_analyzer.token_stream(query);
@@ -337,3 +788,438 @@ impl QueryParser {
         terms
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    fn create_index() -> SearchIndex {
+        let (frontend_sender, _) = utils::channel::channel();
+
+        SearchIndex::from_index(FrontendApi::new(frontend_sender), Index::create_in_ram(SearchIndex::schema())).unwrap()
+    }
+
+    fn search_item(entrypoint_id: &str, entrypoint_name: &str, entrypoint_type: SearchResultEntrypointType) -> SearchIndexItem {
+        SearchIndexItem {
+            entrypoint_type,
+            entrypoint_name: entrypoint_name.to_string(),
+            entrypoint_description: String::new(),
+            entrypoint_id: EntrypointId::from_string(entrypoint_id),
+            entrypoint_icon_path: None,
+            entrypoint_frecency: 0.0,
+            entrypoint_actions: vec![],
+        }
+    }
+
+    #[test]
+    fn search_result_has_plugin_name_as_subtitle() {
+        let mut index = create_index();
+
+        let items = vec![
+            search_item("view", "Some View", SearchResultEntrypointType::View),
+            search_item("generated", "Generated Command", SearchResultEntrypointType::GeneratedCommand),
+        ];
+
+        index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), items, false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        let results = index.search("view").unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].subtitle.as_deref(), Some("Test Plugin"));
+        assert_eq!(results[0].result_kind, SearchResultKind::View);
+
+        let results = index.search("generated").unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].result_kind, SearchResultKind::Command);
+    }
+
+    #[test]
+    fn plain_query_is_unchanged() {
+        let (text, filters) = SearchFilters::parse("open  browser").unwrap();
+
+        assert_eq!(text, "open browser");
+        assert!(filters.plugin.is_none());
+        assert!(filters.result_kind.is_none());
+    }
+
+    #[test]
+    fn plugin_operator_is_extracted() {
+        let (text, filters) = SearchFilters::parse("plugin:Gauntlet foo").unwrap();
+
+        assert_eq!(text, "foo");
+        assert_eq!(filters.plugin.as_deref(), Some("gauntlet"));
+    }
+
+    #[test]
+    fn type_operator_is_extracted() {
+        let (text, filters) = SearchFilters::parse("foo type:view").unwrap();
+
+        assert_eq!(text, "foo");
+        assert_eq!(filters.result_kind, Some(SearchResultKind::View));
+    }
+
+    #[test]
+    fn combined_operators_are_extracted() {
+        let (text, filters) = SearchFilters::parse("type:command foo plugin:gauntlet bar").unwrap();
+
+        assert_eq!(text, "foo bar");
+        assert_eq!(filters.plugin.as_deref(), Some("gauntlet"));
+        assert_eq!(filters.result_kind, Some(SearchResultKind::Command));
+    }
+
+    #[test]
+    fn unknown_operator_is_rejected() {
+        assert!(matches!(SearchFilters::parse("tag:work foo"), Err(SearchQueryError::UnknownOperator(name)) if name == "tag"));
+    }
+
+    #[test]
+    fn unknown_type_is_rejected() {
+        assert!(matches!(SearchFilters::parse("type:widget"), Err(SearchQueryError::UnknownType(value)) if value == "widget"));
+    }
+
+    #[test]
+    fn incomplete_operator_is_ignored() {
+        let (text, filters) = SearchFilters::parse("foo type:").unwrap();
+
+        assert_eq!(text, "foo");
+        assert!(filters.result_kind.is_none());
+    }
+
+    #[test]
+    fn text_with_colons_is_not_an_operator() {
+        let (text, _) = SearchFilters::parse("10:30 https://example.com").unwrap();
+
+        assert_eq!(text, "10:30 https://example.com");
+    }
+
+    #[test]
+    fn search_results_are_filtered_by_operators() {
+        let mut index = create_index();
+
+        index.save_for_plugin(PluginId::from_string("first-plugin"), "First".to_string(), vec![
+            search_item("view", "Open View", SearchResultEntrypointType::View),
+            search_item("command", "Open Command", SearchResultEntrypointType::Command),
+        ], false).unwrap();
+        index.save_for_plugin(PluginId::from_string("second-plugin"), "Second".to_string(), vec![
+            search_item("command", "Open Command", SearchResultEntrypointType::Command),
+        ], false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        assert_eq!(index.search("open").unwrap().len(), 3);
+
+        let results = index.search("type:view open").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entrypoint_name, "Open View");
+
+        let results = index.search("plugin:second open").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].plugin_name, "Second");
+
+        let results = index.search("plugin:first type:command open").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entrypoint_name, "Open Command");
+        assert_eq!(results[0].plugin_name, "First");
+    }
+
+    #[tokio::test]
+    async fn search_after_warmup_returns_promptly() {
+        let mut index = create_index();
+
+        index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), vec![
+            search_item("view", "Some View", SearchResultEntrypointType::View),
+        ], false).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), index.warmup())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let start = Instant::now();
+        let results = index.search("view").unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn reopened_index_preserves_entrypoints() {
+        let dir = tempfile::tempdir().unwrap();
+
+        {
+            let (frontend_sender, _) = utils::channel::channel();
+            let mut index = SearchIndex::open_or_create(FrontendApi::new(frontend_sender), dir.path()).unwrap();
+
+            index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), vec![
+                search_item("view", "Some View", SearchResultEntrypointType::View),
+            ], false).unwrap();
+        }
+
+        let (frontend_sender, _) = utils::channel::channel();
+        let index = SearchIndex::open_or_create(FrontendApi::new(frontend_sender), dir.path()).unwrap();
+
+        let results = index.search("view").unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entrypoint_id, EntrypointId::from_string("view"));
+        assert!(matches!(results[0].entrypoint_type, SearchResultEntrypointType::View));
+        assert_eq!(results[0].subtitle.as_deref(), Some("Test Plugin"));
+    }
+
+    #[test]
+    fn index_with_other_schema_version_is_recreated() {
+        let dir = tempfile::tempdir().unwrap();
+
+        {
+            let (frontend_sender, _) = utils::channel::channel();
+            let mut index = SearchIndex::open_or_create(FrontendApi::new(frontend_sender), dir.path()).unwrap();
+
+            index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), vec![
+                search_item("view", "Some View", SearchResultEntrypointType::View),
+            ], false).unwrap();
+        }
+
+        std::fs::write(dir.path().join(SEARCH_INDEX_SCHEMA_VERSION_FILE), "0").unwrap();
+
+        let (frontend_sender, _) = utils::channel::channel();
+        let index = SearchIndex::open_or_create(FrontendApi::new(frontend_sender), dir.path()).unwrap();
+
+        assert!(index.search("view").unwrap().is_empty());
+    }
+
+    #[test]
+    fn diacritics_are_ignored_when_searching() {
+        let mut index = create_index();
+
+        index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), vec![
+            search_item("cafe", "Café", SearchResultEntrypointType::View),
+            search_item("naive", "naive", SearchResultEntrypointType::View),
+        ], false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        let results = index.search("cafe").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entrypoint_name, "Café");
+
+        let results = index.search("café").unwrap();
+        assert_eq!(results.len(), 1);
+
+        let results = index.search("naïve").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entrypoint_name, "naive");
+    }
+
+    #[test]
+    fn cjk_text_is_not_affected_by_folding() {
+        let mut index = create_index();
+
+        index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), vec![
+            search_item("tokyo", "東京", SearchResultEntrypointType::View),
+        ], false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        let results = index.search("東京").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entrypoint_name, "東京");
+
+        assert!(index.search("京都").unwrap().is_empty());
+    }
+
+    #[test]
+    fn description_match_is_ranked_below_name_match() {
+        let mut index = create_index();
+
+        let described_item = SearchIndexItem {
+            entrypoint_description: "Open browser settings".to_string(),
+            // higher frecency should not put it above name match
+            entrypoint_frecency: 100.0,
+            ..search_item("settings", "Settings", SearchResultEntrypointType::View)
+        };
+
+        index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), vec![
+            described_item,
+            search_item("browser", "Browser", SearchResultEntrypointType::View),
+        ], false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        let results = index.search("browser").unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].entrypoint_id, EntrypointId::from_string("browser"));
+        assert_eq!(results[1].entrypoint_id, EntrypointId::from_string("settings"));
+    }
+
+    #[test]
+    fn typo_in_query_is_suggested_closest_name() {
+        let mut index = create_index();
+
+        index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), vec![
+            search_item("calculator", "Calculator", SearchResultEntrypointType::View),
+            search_item("calendar", "Calendar", SearchResultEntrypointType::View),
+        ], false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        assert_eq!(index.suggest("calculater").unwrap().as_deref(), Some("Calculator"));
+        assert_eq!(index.suggest("calendr").unwrap().as_deref(), Some("Calendar"));
+    }
+
+    #[test]
+    fn unrelated_query_has_no_suggestion() {
+        let mut index = create_index();
+
+        index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), vec![
+            search_item("calculator", "Calculator", SearchResultEntrypointType::View),
+        ], false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        assert_eq!(index.suggest("xylophone").unwrap(), None);
+        assert_eq!(index.suggest("").unwrap(), None);
+    }
+
+    #[test]
+    fn levenshtein_distance_is_bounded() {
+        assert_eq!(bounded_levenshtein("kitten", "sitting", 3), Some(3));
+        assert_eq!(bounded_levenshtein("kitten", "sitting", 2), None);
+        assert_eq!(bounded_levenshtein("same", "same", 0), Some(0));
+        assert_eq!(bounded_levenshtein("short", "much longer text", 3), None);
+    }
+
+    #[test]
+    fn empty_query_is_ordered_by_frecency() {
+        let mut index = create_index();
+
+        let item = |entrypoint_id: &str, entrypoint_name: &str, frecency: f64| SearchIndexItem {
+            entrypoint_frecency: frecency,
+            ..search_item(entrypoint_id, entrypoint_name, SearchResultEntrypointType::Command)
+        };
+
+        index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), vec![
+            item("rare", "Rare", 1.0),
+            item("frequent", "Frequent", 5.0),
+            item("sometimes-b", "Sometimes B", 3.0),
+            item("sometimes-a", "Sometimes A", 3.0),
+        ], false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        let names = index.search("")
+            .unwrap()
+            .into_iter()
+            .map(|result| result.entrypoint_name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["Frequent", "Sometimes A", "Sometimes B", "Rare"]);
+    }
+
+    fn open_index(index_dir: &Path) -> SearchIndex {
+        let (frontend_sender, _) = utils::channel::channel();
+
+        SearchIndex::open_or_create(FrontendApi::new(frontend_sender), index_dir).unwrap()
+    }
+
+    fn index_with_item(index_dir: &Path) {
+        let mut index = open_index(index_dir);
+
+        let items = vec![search_item("view", "Some View", SearchResultEntrypointType::View)];
+
+        index.save_for_plugin(PluginId::from_string("test-plugin"), "Test Plugin".to_string(), items, false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        assert_eq!(index.search("view").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn index_with_current_schema_version_is_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let index_dir = dir.path().join("search_index");
+
+        index_with_item(&index_dir);
+
+        let index = open_index(&index_dir);
+
+        assert_eq!(index.search("view").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn index_with_older_schema_version_is_rebuilt() {
+        let dir = tempfile::tempdir().unwrap();
+        let index_dir = dir.path().join("search_index");
+
+        index_with_item(&index_dir);
+
+        std::fs::write(index_dir.join(SEARCH_INDEX_SCHEMA_VERSION_FILE), (SEARCH_INDEX_SCHEMA_VERSION - 1).to_string()).unwrap();
+
+        let index = open_index(&index_dir);
+
+        assert!(index.search("view").unwrap().is_empty());
+        assert_eq!(std::fs::read_to_string(index_dir.join(SEARCH_INDEX_SCHEMA_VERSION_FILE)).unwrap(), SEARCH_INDEX_SCHEMA_VERSION.to_string());
+    }
+
+    #[test]
+    fn index_which_cannot_be_opened_is_recreated() {
+        let dir = tempfile::tempdir().unwrap();
+        let index_dir = dir.path().join("search_index");
+
+        index_with_item(&index_dir);
+
+        std::fs::write(index_dir.join("meta.json"), "corrupted").unwrap();
+
+        let index = open_index(&index_dir);
+
+        assert!(index.search("view").unwrap().is_empty());
+    }
+
+    #[test]
+    fn equal_score_results_are_in_stable_order() {
+        let mut index = create_index();
+
+        let items = || vec![
+            search_item("second", "Open Item", SearchResultEntrypointType::Command),
+            search_item("first", "Open Item", SearchResultEntrypointType::Command),
+        ];
+
+        index.save_for_plugin(PluginId::from_string("b-plugin"), "B Plugin".to_string(), items(), false).unwrap();
+        index.save_for_plugin(PluginId::from_string("a-plugin"), "A Plugin".to_string(), items(), false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        let expected = vec![
+            ("a-plugin".to_string(), "first".to_string()),
+            ("a-plugin".to_string(), "second".to_string()),
+            ("b-plugin".to_string(), "first".to_string()),
+            ("b-plugin".to_string(), "second".to_string()),
+        ];
+
+        for query in ["open", ""] {
+            for _ in 0..5 {
+                let results = index.search(query)
+                    .unwrap()
+                    .into_iter()
+                    .map(|result| (result.plugin_id.to_string(), result.entrypoint_id.to_string()))
+                    .collect::<Vec<_>>();
+
+                assert_eq!(results, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn boosted_plugin_outranks_equal_score_plugin() {
+        let mut index = create_index();
+
+        let items = || vec![search_item("open", "Open Item", SearchResultEntrypointType::Command)];
+
+        index.save_for_plugin(PluginId::from_string("a-plugin"), "A Plugin".to_string(), items(), false).unwrap();
+        index.save_for_plugin(PluginId::from_string("b-plugin"), "B Plugin".to_string(), items(), false).unwrap();
+        index.index_reader.reload().unwrap();
+
+        index.set_plugin_boosts(HashMap::from([(PluginId::from_string("b-plugin"), 2.0)]));
+
+        let results = index.search("open").unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].plugin_id, PluginId::from_string("b-plugin"));
+        assert_eq!(results[1].plugin_id, PluginId::from_string("a-plugin"));
+    }
+}
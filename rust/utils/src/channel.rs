@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
 use tokio::sync::{mpsc, oneshot};
@@ -15,6 +16,15 @@ impl From<Elapsed> for RequestError {
 
 pub type Payload<Req, Res> = (Req, Responder<Res>);
 
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// shared by all channels, so that id identifies request in logs regardless of which channel it went through
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+fn next_request_id() -> u64 {
+    NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed)
+}
+
 #[derive(Debug)]
 pub struct ResponseReceiver<Res> {
     pub(crate) response_receiver: Option<oneshot::Receiver<Res>>,
@@ -51,19 +61,43 @@ impl<Req: std::fmt::Debug, Res: std::fmt::Debug> RequestSender<Req, Res> {
     }
 
     pub fn send(&self, request: Req) -> ResponseReceiver<Res> {
+        let request_id = next_request_id();
+
+        tracing::trace!(target = "channel", "Sending request {}: {:?}", request_id, request);
+
         let (response_sender, response_receiver) = oneshot::channel::<Res>();
-        let responder = Responder::new(response_sender);
+        let responder = Responder::new(request_id, response_sender);
         let payload = (request, responder);
         self.request_sender.send(payload).expect("the other side is closed");
         ResponseReceiver::new(response_receiver)
     }
 
     pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError> {
-        let mut receiver = self.send(request);
+        self.send_receive_with_timeout(request, DEFAULT_REQUEST_TIMEOUT).await
+    }
+
+    // for requests which wait for user, e.g. prompts, and are expected to take longer than default timeout
+    pub async fn send_receive_with_timeout(&self, request: Req, duration: Duration) -> Result<Res, RequestError> {
+        let request_id = next_request_id();
+
+        tracing::trace!(target = "channel", "Sending request {}: {:?}", request_id, request);
+
+        let (response_sender, response_receiver) = oneshot::channel::<Res>();
+        let responder = Responder::new(request_id, response_sender);
+        self.request_sender.send((request, responder)).expect("the other side is closed");
+
+        let mut receiver = ResponseReceiver::new(response_receiver);
+
+        let result = match tokio::time::timeout(duration, receiver.recv()).await {
+            Ok(result) => result,
+            Err(err) => {
+                tracing::warn!(target = "channel", "Request {} didn't receive response in {:?}", request_id, duration);
 
-        let duration = Duration::from_secs(30);
+                return Err(err.into())
+            }
+        };
 
-        let result = tokio::time::timeout(duration, receiver.recv()).await?;
+        tracing::trace!(target = "channel", "Received response for request {}: {:?}", request_id, result);
 
         Ok(result)
     }
@@ -98,17 +132,28 @@ impl<Req, Res> RequestReceiver<Req, Res> {
 }
 
 impl<Res: std::fmt::Debug> Responder<Res> {
-    fn new(response_sender: oneshot::Sender<Res>) -> Self {
-        Self { response_sender }
+    fn new(request_id: u64, response_sender: oneshot::Sender<Res>) -> Self {
+        Self { request_id, response_sender }
+    }
+
+    pub fn request_id(&self) -> u64 {
+        self.request_id
     }
 
     pub fn respond(self, response: Res) {
+        tracing::trace!(target = "channel", "Responding to request {}: {:?}", self.request_id, response);
+
         self.response_sender.send(response).expect("the receiver was closed")
     }
+
+    pub fn is_closed(&self) -> bool {
+        self.response_sender.is_closed()
+    }
 }
 
 #[derive(Debug)]
 pub struct Responder<Res> {
+    request_id: u64,
     response_sender: oneshot::Sender<Res>,
 }
 
@@ -118,3 +163,24 @@ pub fn channel<Req: std::fmt::Debug, Res: std::fmt::Debug>() -> (RequestSender<R
     let request_receiver = RequestReceiver::new(receiver);
     (request_sender, request_receiver)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn request_ids_are_unique_and_monotonic() {
+        let (sender, mut receiver) = channel::<u32, u32>();
+
+        let mut previous_request_id = 0;
+        for request in 0..10 {
+            let _response_receiver = sender.send(request);
+
+            let (received, responder) = receiver.recv().await;
+            assert_eq!(received, request);
+            assert!(responder.request_id() > previous_request_id);
+
+            previous_request_id = responder.request_id();
+        }
+    }
+}